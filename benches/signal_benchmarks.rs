@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use windjammer_ui::reactivity::Signal;
+use windjammer_ui::reactivity::{batch, Computed, Effect, Signal};
 
 fn bench_signal_creation(c: &mut Criterion) {
     c.bench_function("signal_new", |b| {
@@ -116,6 +116,96 @@ fn bench_signal_memory(c: &mut Criterion) {
     });
 }
 
+fn bench_batched_updates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("five_field_update");
+
+    let fields: Vec<_> = (0..5).map(Signal::new).collect();
+    let observed = fields.clone();
+    Effect::new(move || {
+        let total: i32 = observed.iter().map(Signal::get).sum();
+        black_box(total);
+    });
+
+    group.bench_function("unbatched", |b| {
+        b.iter(|| {
+            for field in &fields {
+                field.update(|val| *val += 1);
+            }
+        });
+    });
+
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            batch(|| {
+                for field in &fields {
+                    field.update(|val| *val += 1);
+                }
+            });
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_effect_fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("effect_fan_out");
+
+    for effect_count in [1, 4, 16].iter() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(effect_count),
+            effect_count,
+            |b, &count| {
+                let signal = Signal::new(0);
+                for _ in 0..count {
+                    let signal = signal.clone();
+                    Effect::new(move || {
+                        black_box(signal.get());
+                    });
+                }
+
+                b.iter(|| signal.update(|val| *val += 1));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_diamond_propagation(c: &mut Criterion) {
+    c.bench_function("diamond_propagation", |b| {
+        let a = Signal::new(0);
+        let left = {
+            let a = a.clone();
+            Computed::new(move || a.get() + 1)
+        };
+        let right = {
+            let a = a.clone();
+            Computed::new(move || a.get() * 2)
+        };
+        let sum = Computed::new(move || left.get() + right.get());
+        Effect::new(move || {
+            black_box(sum.get());
+        });
+
+        b.iter(|| a.update(|val| *val += 1));
+    });
+}
+
+fn bench_read_large_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_vec_1000");
+    let signal = Signal::new((0..1000).collect::<Vec<i32>>());
+
+    group.bench_function("get_clone", |b| {
+        b.iter(|| black_box(signal.get().len()));
+    });
+
+    group.bench_function("with_borrow", |b| {
+        b.iter(|| black_box(signal.with(Vec::len)));
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_signal_creation,
@@ -126,7 +216,11 @@ criterion_group!(
     bench_multiple_signals,
     bench_computed_signal,
     bench_signal_chain,
-    bench_signal_memory
+    bench_signal_memory,
+    bench_batched_updates,
+    bench_effect_fan_out,
+    bench_diamond_propagation,
+    bench_read_large_value
 );
 
 criterion_main!(benches);
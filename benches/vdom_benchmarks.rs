@@ -0,0 +1,118 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use windjammer_ui::vdom::{diff, MemoCache, VElement, VNode};
+
+/// 100 widgets of 20 rows, 5 nodes per row: about 10k nodes
+const WIDGETS: usize = 100;
+const ROWS: usize = 20;
+
+/// Dashboard data: one value per row
+fn data(tick: usize) -> Vec<Vec<u64>> {
+    (0..WIDGETS)
+        .map(|w| {
+            (0..ROWS)
+                .map(|r| {
+                    // Only the first widget changes between ticks
+                    let base = (w * ROWS + r) as u64;
+                    if w == 0 {
+                        base + tick as u64
+                    } else {
+                        base
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn widget(index: usize, rows: &[u64]) -> VNode {
+    let title = VElement::new("h2").child(format!("Widget {}", index).into());
+    let mut children = vec![title.into()];
+    children.extend(rows.iter().enumerate().map(|(r, value)| {
+        VElement::new("div")
+            .attr("class", "row")
+            .child(
+                VElement::new("span")
+                    .child(format!("Row {}", r).into())
+                    .into(),
+            )
+            .child(
+                VElement::new("span")
+                    .child(format!("{}", value).into())
+                    .into(),
+            )
+            .into()
+    }));
+    VElement::new("section")
+        .attr("class", "widget")
+        .children(children)
+        .into()
+}
+
+fn dashboard(data: &[Vec<u64>]) -> VNode {
+    VElement::new("main")
+        .children(
+            data.iter()
+                .enumerate()
+                .map(|(i, rows)| widget(i, rows))
+                .collect(),
+        )
+        .into()
+}
+
+fn memo_dashboard(cache: &MemoCache, data: &[Vec<u64>]) -> VNode {
+    let widgets = data
+        .iter()
+        .enumerate()
+        .map(|(i, rows)| cache.memo(format!("widget-{}", i), rows, || widget(i, rows)))
+        .collect();
+    cache.sweep();
+    VElement::new("main").children(widgets).into()
+}
+
+fn bench_dashboard_render_and_diff(c: &mut Criterion) {
+    let (before, after) = (data(0), data(1));
+    let mut group = c.benchmark_group("dashboard_10k");
+
+    let old = dashboard(&before);
+    group.bench_function("render_and_diff", |b| {
+        b.iter(|| {
+            let new = dashboard(black_box(&after));
+            black_box(diff(&old, &new));
+        });
+    });
+
+    let cache = MemoCache::new();
+    let old = memo_dashboard(&cache, &before);
+    group.bench_function("memo_render_and_diff", |b| {
+        b.iter(|| {
+            let new = memo_dashboard(&cache, black_box(&after));
+            black_box(diff(&old, &new));
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_dashboard_diff(c: &mut Criterion) {
+    let (before, after) = (data(0), data(1));
+    let mut group = c.benchmark_group("dashboard_10k_diff_only");
+
+    let (old, new) = (dashboard(&before), dashboard(&after));
+    group.bench_function("plain", |b| b.iter(|| black_box(diff(&old, &new))));
+
+    let cache = MemoCache::new();
+    let (old, new) = (
+        memo_dashboard(&cache, &before),
+        memo_dashboard(&cache, &after),
+    );
+    group.bench_function("memo", |b| b.iter(|| black_box(diff(&old, &new))));
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_dashboard_render_and_diff,
+    bench_dashboard_diff
+);
+criterion_main!(benches);
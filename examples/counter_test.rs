@@ -23,8 +23,14 @@ fn main() {
                     attrs
                 },
                 children: vec![VNode::Text(VText::new("Increment"))],
+                key: None,
+                events: HashMap::new(),
+                bindings: HashMap::new(),
             }),
         ],
+        key: None,
+        events: HashMap::new(),
+        bindings: HashMap::new(),
     };
 
     renderer.render(&VNode::Element(vnode));
@@ -47,8 +53,14 @@ fn main() {
                         attrs
                     },
                     children: vec![VNode::Text(VText::new("Increment"))],
+                    key: None,
+                    events: HashMap::new(),
+                    bindings: HashMap::new(),
                 }),
             ],
+            key: None,
+            events: HashMap::new(),
+            bindings: HashMap::new(),
         };
 
         renderer.render(&VNode::Element(updated_vnode));
@@ -96,6 +96,7 @@ impl App {
     #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
     pub fn run(self) {
         use crate::desktop_renderer::DesktopRenderer;
+        use std::sync::{Arc, OnceLock};
 
         let title = self.title;
         let render_fn = self.render_fn;
@@ -108,6 +109,15 @@ impl App {
             ..Default::default()
         };
 
+        // Wake the loop for background writes once it has a context to repaint
+        let repaint = Arc::new(OnceLock::<eframe::egui::Context>::new());
+        let waker_ctx = repaint.clone();
+        crate::sync_signal::set_sync_waker(move || {
+            if let Some(ctx) = waker_ctx.get() {
+                ctx.request_repaint();
+            }
+        });
+
         let _ = eframe::run_simple_native(&title, options, move |ctx, _frame| {
             // Set up repaint callback for reactive updates
             let ctx_clone = ctx.clone();
@@ -115,6 +125,10 @@ impl App {
                 ctx_clone.request_repaint();
             });
 
+            // Deliver writes made by background tasks on the UI thread
+            repaint.get_or_init(|| ctx.clone());
+            crate::sync_signal::flush_sync_updates();
+
             // Re-generate VNode if we have a render function (reactive mode)
             if let Some(ref render) = render_fn {
                 current_vnode = render();
@@ -127,6 +141,8 @@ impl App {
 
         // Cleanup
         crate::desktop_app_context::clear_repaint_callback();
+        crate::sync_signal::clear_sync_waker();
+        crate::persisted::flush_persisted();
     }
 
     /// Run the application (Non-desktop, non-WASM - error)
@@ -4,9 +4,9 @@ use crate::simple_vnode::VNode;
 use std::rc::Rc;
 
 #[cfg(target_arch = "wasm32")]
-use std::cell::RefCell;
+use crate::reactivity::{create_root, Scope};
 #[cfg(target_arch = "wasm32")]
-use wasm_bindgen::prelude::*;
+use std::cell::{Cell, RefCell};
 
 // Global render callback for triggering re-renders
 static mut RENDER_CALLBACK: Option<Box<dyn Fn()>> = None;
@@ -17,6 +17,9 @@ pub fn set_render_callback<F: Fn() + 'static>(callback: F) {
     }
 }
 
+/// Render the running [`ReactiveApp`] again on the next animation frame
+///
+/// Signal changes call this; calls before the frame render once.
 pub fn trigger_rerender() {
     unsafe {
         let callback_ptr = &raw const RENDER_CALLBACK;
@@ -45,68 +48,60 @@ impl ReactiveApp {
         }
     }
 
+    /// Mount into `#app` and render again on the next animation frame
+    /// after signals change
+    ///
+    /// Each render is diffed against the previous one and patched in, so
+    /// unchanged nodes keep focus, scroll position and selection. Text and
+    /// attributes bound with [`VNode::dynamic`] and
+    /// [`crate::simple_vnode::VAttr::Dynamic`] update right away.
     #[cfg(target_arch = "wasm32")]
     pub fn run(self) {
-        use wasm_bindgen::JsCast;
-        use web_sys::{window, HtmlElement};
-
-        let document = window().unwrap().document().unwrap();
-        let root = document
-            .get_element_by_id("app")
-            .expect("Failed to find #app element")
-            .dyn_into::<HtmlElement>()
-            .unwrap();
+        use crate::renderer::{Renderer, WebRenderer};
+        use crate::vdom;
+
+        let mut renderer = WebRenderer::new();
+        renderer
+            .init()
+            .expect("Failed to find or create #app element");
+        let renderer = RefCell::new(renderer);
+        let previous = RefCell::new(None::<vdom::VNode>);
+        // Owns what the previous render created, such as effects
+        let scope = RefCell::new(None::<Scope>);
 
         let render_fn = self.render_fn.clone();
-        let needs_rerender = Rc::new(RefCell::new(true));
-
-        let needs_rerender_clone = needs_rerender.clone();
-        set_render_callback(move || {
-            *needs_rerender_clone.borrow_mut() = true;
+        let render = Rc::new(move || {
+            if let Some(scope) = scope.take() {
+                scope.dispose();
+            }
+            let vnode: vdom::VNode = create_root(|root| {
+                scope.replace(Some(root));
+                render_fn().into()
+            });
+
+            let mut renderer = renderer.borrow_mut();
+            let result = match previous.borrow().as_ref() {
+                Some(old) => renderer.patch(&vdom::diff(old, &vnode)),
+                None => renderer.render(&vnode),
+            };
+            if let Err(e) = result {
+                crate::component_runtime::console_error(&format!("Failed to render: {}", e));
+            }
+            previous.replace(Some(vnode));
         });
 
-        let render_fn_clone = render_fn.clone();
-        let needs_rerender_clone = needs_rerender.clone();
-
-        let render_loop = Rc::new(RefCell::new(None::<Closure<dyn FnMut()>>));
-        let render_loop_clone = render_loop.clone();
-
-        *render_loop.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-            if *needs_rerender_clone.borrow() {
-                *needs_rerender_clone.borrow_mut() = false;
-
-                let vnode = render_fn_clone();
-                let html = crate::simple_renderer::render_to_html(&vnode);
-                root.set_inner_html(&html);
-
-                // Event listeners are attached in future version
-                // crate::renderer::attach_event_listeners(&root, &vnode);
+        let pending = Rc::new(Cell::new(false));
+        set_render_callback(move || {
+            if pending.replace(true) {
+                return;
             }
-
-            web_sys::window()
-                .unwrap()
-                .request_animation_frame(
-                    render_loop_clone
-                        .borrow()
-                        .as_ref()
-                        .unwrap()
-                        .as_ref()
-                        .unchecked_ref(),
-                )
-                .unwrap();
-        }) as Box<dyn FnMut()>));
-
-        web_sys::window()
-            .unwrap()
-            .request_animation_frame(
-                render_loop
-                    .borrow()
-                    .as_ref()
-                    .unwrap()
-                    .as_ref()
-                    .unchecked_ref(),
-            )
-            .unwrap();
+            let (pending, render) = (pending.clone(), render.clone());
+            crate::scheduler::next_frame(move || {
+                pending.set(false);
+                render();
+            });
+        });
+        trigger_rerender();
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -118,6 +118,8 @@ impl ReactiveApp {
                     eprintln!("Failed to run app: {}", e);
                 }
             }
+            // Don't lose changes still waiting for their debounce delay
+            crate::persisted::flush_persisted();
         }
 
         #[cfg(not(feature = "desktop"))]
@@ -152,7 +154,20 @@ impl ReactiveApp {
             ..Default::default()
         };
 
-        eframe::run_simple_native(&self.title, native_options, move |ctx, _frame| {
+        // Wake the loop for background writes once it has a context to repaint
+        let repaint = Arc::new(std::sync::OnceLock::<egui::Context>::new());
+        let waker_ctx = repaint.clone();
+        crate::sync_signal::set_sync_waker(move || {
+            if let Some(ctx) = waker_ctx.get() {
+                ctx.request_repaint();
+            }
+        });
+
+        let result = eframe::run_simple_native(&self.title, native_options, move |ctx, _frame| {
+            // Deliver writes made by background tasks on the UI thread
+            repaint.get_or_init(|| ctx.clone());
+            crate::sync_signal::flush_sync_updates();
+
             // Check if we need to re-render
             if *needs_rerender.lock().unwrap() {
                 *needs_rerender.lock().unwrap() = false;
@@ -164,8 +179,9 @@ impl ReactiveApp {
 
             // Render with our desktop renderer
             renderer.render(ctx, &vnode);
-        })
-        .map_err(|e| format!("eframe error: {}", e))
+        });
+        crate::sync_signal::clear_sync_waker();
+        result.map_err(|e| format!("eframe error: {}", e))
     }
 }
 
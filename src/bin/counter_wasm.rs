@@ -125,7 +125,7 @@ mod app {
                 let dom_element = document.create_element(&element.tag)?;
 
                 // Set attributes
-                for (key, value) in &element.attrs {
+                for (key, value) in &element.current_attrs() {
                     dom_element.set_attribute(key, value)?;
                 }
 
@@ -141,7 +141,20 @@ mod app {
                 let text_node = document.create_text_node(&text.content);
                 Ok(text_node.into())
             }
+            VNode::Dynamic(binding) => {
+                let text_node = document.create_text_node(&binding.get());
+                Ok(text_node.into())
+            }
             VNode::Component(_) => Err(JsValue::from_str("Cannot render component directly")),
+            VNode::Fragment(children) | VNode::Portal { children, .. } => {
+                // This demo has no portal targets; render portal content in place
+                let fragment = document.create_document_fragment();
+                for child in children {
+                    fragment.append_child(&create_dom_element(document, child)?)?;
+                }
+                Ok(fragment.into())
+            }
+            VNode::Memo(memo) => create_dom_element(document, &memo.node),
             VNode::Empty => {
                 let text_node = document.create_text_node("");
                 Ok(text_node.into())
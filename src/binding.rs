@@ -0,0 +1,169 @@
+//! Reactive values bound directly to a rendered node
+//!
+//! A [`Binding`] produces a string from signals. Renderers that keep real
+//! nodes around subscribe to it with [`Binding::bind`] and update just that
+//! text node or attribute when it changes, without re-rendering or diffing;
+//! the others read its current value.
+//!
+//! # Example
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use windjammer_ui::binding::Binding;
+//! use windjammer_ui::reactivity::Signal;
+//!
+//! let count = Signal::new(1);
+//! let label = Binding::from(count.clone());
+//!
+//! let shown = Rc::new(RefCell::new(String::new()));
+//! let shown_c = shown.clone();
+//! label.bind(move |text| *shown_c.borrow_mut() = text);
+//! assert_eq!(*shown.borrow(), "1");
+//!
+//! count.set(2);
+//! assert_eq!(*shown.borrow(), "2");
+//! ```
+
+use crate::reactivity::{untrack, Computed, Effect, EffectId, Memo, Signal};
+use std::fmt;
+use std::rc::Rc;
+
+/// A string computed from signals, bound to one text node or attribute
+#[derive(Clone)]
+pub struct Binding(Rc<dyn Fn() -> String>);
+
+impl Binding {
+    /// Bind to the result of `f`, re-evaluated whenever a signal it reads
+    /// changes
+    pub fn new(f: impl Fn() -> String + 'static) -> Self {
+        Self(Rc::new(f))
+    }
+
+    /// Current value (tracks the signals it reads)
+    pub fn get(&self) -> String {
+        (self.0)()
+    }
+
+    /// Current value without tracking
+    pub fn get_untracked(&self) -> String {
+        untrack(|| self.get())
+    }
+
+    /// Call `apply` with the value now and after every change
+    ///
+    /// The subscription is an [`Effect`] owned by the current scope, so it
+    /// stops when that scope is disposed, or earlier with
+    /// [`Effect::dispose`].
+    pub fn bind(&self, apply: impl Fn(String) + 'static) -> EffectId {
+        let binding = self.clone();
+        Effect::new_labeled("binding", move || {
+            let value = binding.get();
+            untrack(|| apply(value));
+        })
+    }
+}
+
+impl From<String> for Binding {
+    fn from(value: String) -> Self {
+        Self::new(move || value.clone())
+    }
+}
+
+impl From<&str> for Binding {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl<T: Clone + fmt::Display + 'static> From<Signal<T>> for Binding {
+    fn from(signal: Signal<T>) -> Self {
+        Self::new(move || signal.with(T::to_string))
+    }
+}
+
+impl<T: Clone + fmt::Display + 'static> From<Computed<T>> for Binding {
+    fn from(computed: Computed<T>) -> Self {
+        Self::new(move || computed.with(T::to_string))
+    }
+}
+
+impl<T: Clone + fmt::Display + 'static> From<Memo<T>> for Binding {
+    fn from(memo: Memo<T>) -> Self {
+        Computed::from(memo).into()
+    }
+}
+
+impl PartialEq for Binding {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Shows the current value
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.get_untracked())
+    }
+}
+
+impl fmt::Debug for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Binding")
+            .field(&self.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::create_scope;
+    use std::cell::RefCell;
+
+    fn record(binding: &Binding) -> Rc<RefCell<Vec<String>>> {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_c = seen.clone();
+        binding.bind(move |value| seen_c.borrow_mut().push(value));
+        seen
+    }
+
+    #[test]
+    fn test_bind_follows_computed_values() {
+        let first = Signal::new("Ada".to_string());
+        let last = Signal::new("Lovelace".to_string());
+        let (first_c, last_c) = (first.clone(), last.clone());
+        let full = Computed::new(move || format!("{} {}", first_c.get(), last_c.get()));
+
+        let seen = record(&Binding::from(full));
+        first.set("Augusta".to_string());
+        assert_eq!(*seen.borrow(), vec!["Ada Lovelace", "Augusta Lovelace"]);
+    }
+
+    #[test]
+    fn test_bind_stops_with_its_scope() {
+        let count = Signal::new(0);
+        let (scope, seen) = create_scope(|scope| (scope, record(&Binding::from(count.clone()))));
+
+        count.set(1);
+        scope.dispose();
+        count.set(2);
+        assert_eq!(*seen.borrow(), vec!["0", "1"]);
+    }
+
+    #[test]
+    fn test_apply_does_not_subscribe_to_its_own_reads() {
+        let count = Signal::new(0);
+        let other = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+        let (other_c, runs_c) = (other.clone(), runs.clone());
+        Binding::from(count.clone()).bind(move |_| {
+            other_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        other.set(1);
+        count.set(1);
+        assert_eq!(*runs.borrow(), 2);
+    }
+}
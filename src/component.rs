@@ -1,6 +1,7 @@
 //! Component model and traits
 
 use crate::vdom::VNode;
+use std::collections::HashMap;
 
 /// Trait for components
 ///
@@ -11,6 +12,9 @@ pub trait Component: Send + Sync {
     /// Initialize the component
     fn init(&mut self) {}
 
+    /// Receive new props from the parent, followed by [`Component::update`]
+    fn set_props(&mut self, _props: &HashMap<String, String>) {}
+
     /// Update the component with new props
     fn update(&mut self) {}
 
@@ -28,6 +32,49 @@ pub trait ComponentProps: Clone + Send + Sync {}
 /// Implement ComponentProps for unit (components with no props)
 impl ComponentProps for () {}
 
+/// Creates a component instance from its props
+pub type ComponentFactory = Box<dyn Fn(&HashMap<String, String>) -> Box<dyn Component>>;
+
+/// Maps the names used in [`VComponent`](crate::vdom::VComponent) nodes to
+/// factories
+#[derive(Default)]
+pub struct ComponentRegistry {
+    factories: HashMap<String, ComponentFactory>,
+}
+
+impl ComponentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `factory` under `name`, replacing any previous factory
+    pub fn register<C, F>(&mut self, name: impl Into<String>, factory: F)
+    where
+        C: Component + 'static,
+        F: Fn(&HashMap<String, String>) -> C + 'static,
+    {
+        self.factories.insert(
+            name.into(),
+            Box::new(move |props| Box::new(factory(props)) as Box<dyn Component>),
+        );
+    }
+
+    /// Whether a factory is registered under `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Create a component, or `None` if `name` is not registered
+    pub fn create(
+        &self,
+        name: &str,
+        props: &HashMap<String, String>,
+    ) -> Option<Box<dyn Component>> {
+        self.factories.get(name).map(|factory| factory(props))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,144 @@
+//! Contexts for passing services down the component tree
+//!
+//! [`provide_context`] stores a value in the current reactive scope and
+//! [`use_context`] finds the nearest one by type, so deep descendants can
+//! reach a router, undo manager or theme without threading it through every
+//! constructor. This module also has ready-made contexts for the framework's
+//! own services.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::components::theme::ColorPalette;
+//! use windjammer_ui::context::{provide_theme, use_theme};
+//! use windjammer_ui::reactivity::create_root;
+//!
+//! create_root(|_| {
+//!     let theme = provide_theme(ColorPalette::dark());
+//!
+//!     // Anywhere below, e.g. in a child component's render
+//!     let palette = use_theme().expect("theme provided by the app");
+//!     assert_eq!(palette.get().bg_primary, "#0a0a1a");
+//!
+//!     theme.set(ColorPalette::light()); // Re-renders everything reading it
+//! });
+//! ```
+
+use crate::components::theme::ColorPalette;
+use crate::reactivity::Signal;
+use crate::routing::Router;
+use crate::undo_redo::UndoRedoManager;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub use crate::reactivity::{provide_context, use_context};
+
+/// Undo manager shared between the components using it
+pub type SharedUndoRedo = Rc<RefCell<UndoRedoManager>>;
+
+/// Provide the app's router
+pub fn provide_router(router: Router) {
+    provide_context(router);
+}
+
+/// The router provided by an ancestor
+pub fn use_router() -> Option<Router> {
+    use_context::<Router>()
+}
+
+/// Provide an undo manager, returning the shared handle
+pub fn provide_undo_manager(manager: UndoRedoManager) -> SharedUndoRedo {
+    let shared = Rc::new(RefCell::new(manager));
+    provide_context(shared.clone());
+    shared
+}
+
+/// The undo manager provided by an ancestor
+pub fn use_undo_manager() -> Option<SharedUndoRedo> {
+    use_context::<SharedUndoRedo>()
+}
+
+/// Provide the active theme palette, returning the signal that switches it
+pub fn provide_theme(palette: ColorPalette) -> Signal<ColorPalette> {
+    let theme = Signal::new_eq(palette);
+    provide_context(theme.clone());
+    theme
+}
+
+/// The active theme palette provided by an ancestor (tracks dependency when read)
+pub fn use_theme() -> Option<Signal<ColorPalette>> {
+    use_context::<Signal<ColorPalette>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::{create_root, create_scope, Effect};
+    use crate::routing::Route;
+    use crate::undo_redo::PropertyChangeCommand;
+
+    #[test]
+    fn test_nearest_provider_wins() {
+        create_root(|_| {
+            provide_context("app".to_string());
+            create_scope(|_| {
+                assert_eq!(use_context::<String>().as_deref(), Some("app"));
+                provide_context("panel".to_string());
+                create_scope(|_| {
+                    assert_eq!(use_context::<String>().as_deref(), Some("panel"));
+                });
+            });
+            create_scope(|_| {
+                // A sibling's value is not visible
+                assert_eq!(use_context::<String>().as_deref(), Some("app"));
+            });
+            assert_eq!(use_context::<u32>(), None);
+        });
+    }
+
+    #[test]
+    fn test_effects_see_their_scope_context() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_c = seen.clone();
+        let scope = create_root(|scope| {
+            let theme = provide_theme(ColorPalette::dark());
+            Effect::new(move || {
+                let palette = use_theme().unwrap();
+                seen_c.borrow_mut().push(palette.get().bg_primary);
+            });
+            theme.set(ColorPalette::light());
+            scope
+        });
+
+        assert_eq!(*seen.borrow(), vec!["#0a0a1a", "#f5f5f5"]);
+        scope.dispose();
+        assert!(scope.run(use_theme).is_none());
+    }
+
+    #[test]
+    fn test_router_and_undo_contexts_share_state() {
+        create_root(|_| {
+            let router = Router::new();
+            router.add_route(Route::new("/settings".to_string(), "settings".to_string()));
+            provide_router(router.clone());
+            let undo = provide_undo_manager(UndoRedoManager::new());
+
+            create_scope(|_| {
+                use_router().unwrap().navigate("/settings").unwrap();
+                use_undo_manager()
+                    .unwrap()
+                    .borrow_mut()
+                    .execute(Box::new(PropertyChangeCommand::new(
+                        "node".to_string(),
+                        "name".to_string(),
+                        "a".to_string(),
+                        "b".to_string(),
+                    )))
+                    .unwrap();
+            });
+
+            assert_eq!(router.current().unwrap().path, "/settings");
+            assert!(undo.borrow().can_undo());
+        });
+    }
+}
@@ -52,6 +52,11 @@ impl DesktopRenderer {
             });
     }
 
+    /// Render a [`crate::vdom::VNode`] tree with expanded components
+    pub fn render_node(&mut self, ui: &mut Ui, vnode: &crate::vdom::VNode) {
+        self.render_vnode(ui, &VNode::from(vnode.clone()));
+    }
+
     pub fn render_vnode(&mut self, ui: &mut Ui, vnode: &VNode) {
         match vnode {
             VNode::Element {
@@ -64,6 +69,25 @@ impl DesktopRenderer {
             VNode::Text(text) => {
                 ui.label(text);
             }
+            VNode::Dynamic(binding) => {
+                // Read every frame; signal changes request a repaint
+                ui.label(binding.get());
+            }
+            VNode::Fragment(children) => {
+                for child in children {
+                    self.render_vnode(ui, child);
+                }
+            }
+            VNode::Portal { target, children } => {
+                // Draw above the rest of the window instead of in the layout
+                egui::Area::new(egui::Id::new(("portal", target.as_str())))
+                    .order(egui::Order::Foreground)
+                    .show(ui.ctx(), |ui| {
+                        for child in children {
+                            self.render_vnode(ui, child);
+                        }
+                    });
+            }
         }
     }
 
@@ -381,7 +405,8 @@ impl DesktopRenderer {
             .iter()
             .find(|(k, _)| k == name)
             .and_then(|(_, v)| match v {
-                VAttr::Static(s) | VAttr::Dynamic(s) => Some(s.clone()),
+                VAttr::Static(s) => Some(s.clone()),
+                VAttr::Dynamic(binding) => Some(binding.get()),
                 _ => None,
             })
             .unwrap_or_default()
@@ -406,6 +431,7 @@ impl DesktopRenderer {
             .iter()
             .filter_map(|child| match child {
                 VNode::Text(text) => Some(text.clone()),
+                VNode::Dynamic(binding) => Some(binding.get()),
                 _ => None,
             })
             .collect::<Vec<_>>()
@@ -670,12 +696,22 @@ impl DesktopRenderer {
             VNode::Text(text) => {
                 ui.label(RichText::new(text).color(Color32::from_rgb(212, 212, 212)));
             }
+            VNode::Dynamic(binding) => {
+                ui.label(RichText::new(binding.get()).color(Color32::from_rgb(212, 212, 212)));
+            }
+            VNode::Fragment(children) | VNode::Portal { children, .. } => {
+                for child in children {
+                    self.render_code_with_highlighting(ui, child);
+                }
+            }
         }
     }
 
     fn render_code_span(ui: &mut Ui, vnode: &VNode) {
         match vnode {
-            VNode::Element { children, .. } => {
+            VNode::Element { children, .. }
+            | VNode::Fragment(children)
+            | VNode::Portal { children, .. } => {
                 for child in children {
                     Self::render_code_span(ui, child);
                 }
@@ -683,6 +719,9 @@ impl DesktopRenderer {
             VNode::Text(text) => {
                 ui.label(RichText::new(text).color(Color32::from_rgb(212, 212, 212)));
             }
+            VNode::Dynamic(binding) => {
+                ui.label(RichText::new(binding.get()).color(Color32::from_rgb(212, 212, 212)));
+            }
         }
     }
 
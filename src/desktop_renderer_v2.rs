@@ -260,7 +260,8 @@ impl DesktopRendererV2 {
         attrs.iter()
             .find(|(k, _)| k == name)
             .and_then(|(_, v)| match v {
-                VAttr::Static(s) | VAttr::Dynamic(s) => Some(s.clone()),
+                VAttr::Static(s) => Some(s.clone()),
+                VAttr::Dynamic(binding) => Some(binding.get()),
                 _ => None,
             })
             .unwrap_or_default()
@@ -327,10 +327,45 @@ impl Default for EventDispatcher {
     }
 }
 
+thread_local! {
+    static CURRENT_EVENT: std::cell::RefCell<Option<Event>> = const { std::cell::RefCell::new(None) };
+}
+
+/// The event being dispatched
+///
+/// Handlers attached to vdom elements take no arguments; they read the
+/// input value or key from here while they run.
+pub fn current_event() -> Option<Event> {
+    CURRENT_EVENT.with(|current| current.borrow().clone())
+}
+
+/// Run `f` with `event` as the [`current_event`]
+pub fn with_current_event<R>(event: Event, f: impl FnOnce() -> R) -> R {
+    let prev = CURRENT_EVENT.with(|current| current.replace(Some(event)));
+    let result = f();
+    CURRENT_EVENT.with(|current| *current.borrow_mut() = prev);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_current_event_is_scoped_to_the_dispatch() {
+        let input = Event::Input {
+            value: "hi".to_string(),
+        };
+        let seen = with_current_event(input.clone(), || {
+            with_current_event(Event::Submit, || {
+                assert_eq!(current_event(), Some(Event::Submit))
+            });
+            current_event()
+        });
+        assert_eq!(seen, Some(input));
+        assert_eq!(current_event(), None);
+    }
+
     #[test]
     fn test_event_display() {
         let event = Event::Click {
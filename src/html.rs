@@ -0,0 +1,371 @@
+//! HTML serialization shared by every renderer that produces markup
+//!
+//! [`HtmlWriter`] writes elements, text and comments following the HTML
+//! serialization rules, so the same tree always gives the same bytes:
+//! - elements with an invalid tag name skipped along with their children
+//! - attributes in name order, values escaped, invalid names skipped
+//! - boolean attributes such as `disabled` written bare, and left out when
+//!   `"false"`
+//! - void elements such as `<br />` without children or end tag
+//! - `<script>` and `<style>` content written raw, with anything that would
+//!   end the element early escaped
+//! - SVG and MathML elements self-closed when empty, with tag and attribute
+//!   case preserved; `<foreignObject>` switches back to HTML
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::html::HtmlWriter;
+//!
+//! let mut html = HtmlWriter::new();
+//! html.element("button", [("type", "submit"), ("disabled", "true"), ("class", "primary")], |html| {
+//!     html.text("Save & close");
+//! });
+//! assert_eq!(
+//!     html.finish(),
+//!     "<button class=\"primary\" disabled type=\"submit\">Save &amp; close</button>"
+//! );
+//! ```
+
+/// Elements that never have children or an end tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Attributes whose presence alone means true
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// Namespace an element is parsed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    /// Namespace of `tag` when it appears in an element of this namespace
+    fn of(self, tag: &str) -> Namespace {
+        match (self, tag) {
+            (Namespace::Html, "svg") => Namespace::Svg,
+            (Namespace::Html, "math") => Namespace::MathMl,
+            _ => self,
+        }
+    }
+
+    /// Namespace of the children of `tag`, an element of this namespace
+    fn children_of(self, tag: &str) -> Namespace {
+        match (self, tag) {
+            (Namespace::Svg, "foreignObject") => Namespace::Html,
+            _ => self,
+        }
+    }
+}
+
+/// Whether `tag` is an HTML void element
+pub fn is_void_element(tag: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag)
+}
+
+/// Whether `tag` is an HTML element whose content is not escaped
+pub fn is_raw_text_element(tag: &str) -> bool {
+    matches!(tag, "script" | "style")
+}
+
+/// Whether `name` is an HTML boolean attribute
+pub fn is_boolean_attribute(name: &str) -> bool {
+    BOOLEAN_ATTRIBUTES.contains(&name)
+}
+
+/// Whether `tag` can be written as a tag name: an ASCII letter followed by
+/// ASCII letters, digits and `-`
+pub fn is_valid_tag_name(tag: &str) -> bool {
+    tag.starts_with(|c: char| c.is_ascii_alphabetic())
+        && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Whether `name` can be written as an attribute name
+pub fn is_valid_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '>' | '/' | '=' | '<')
+        })
+}
+
+/// Escape text content
+pub fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape a double-quoted attribute value
+pub fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Builds an HTML string element by element
+#[derive(Debug, Default)]
+pub struct HtmlWriter {
+    out: String,
+    /// Open elements with the namespace of their children
+    open: Vec<(String, Namespace)>,
+}
+
+impl HtmlWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write an element; `children` writes its content
+    ///
+    /// Children of void elements are dropped. Nothing is written for an
+    /// invalid tag name.
+    pub fn element<'a>(
+        &mut self,
+        tag: &str,
+        attrs: impl IntoIterator<Item = (&'a str, &'a str)>,
+        children: impl FnOnce(&mut Self),
+    ) {
+        if !is_valid_tag_name(tag) {
+            return;
+        }
+        let namespace = self.namespace().of(tag);
+        let html = namespace == Namespace::Html;
+
+        let mut attrs: Vec<(&str, &str)> = attrs
+            .into_iter()
+            .filter(|(name, _)| is_valid_attribute_name(name))
+            .collect();
+        attrs.sort_by(|a, b| a.0.cmp(b.0));
+        attrs.dedup_by(|a, b| a.0 == b.0);
+
+        self.out.push('<');
+        self.out.push_str(tag);
+        for (name, value) in attrs {
+            if html && is_boolean_attribute(name) {
+                if value != "false" {
+                    self.out.push(' ');
+                    self.out.push_str(name);
+                }
+                continue;
+            }
+            self.out.push(' ');
+            self.out.push_str(name);
+            self.out.push_str("=\"");
+            self.out.push_str(&escape_attribute(value));
+            self.out.push('"');
+        }
+
+        if html && is_void_element(tag) {
+            self.out.push_str(" />");
+            return;
+        }
+        self.out.push('>');
+        let start = self.out.len();
+
+        self.open
+            .push((tag.to_string(), namespace.children_of(tag)));
+        children(self);
+        self.open.pop();
+
+        if !html && self.out.len() == start {
+            // Foreign elements may self-close
+            self.out.pop();
+            self.out.push_str(" />");
+            return;
+        }
+        self.out.push_str("</");
+        self.out.push_str(tag);
+        self.out.push('>');
+    }
+
+    /// Write text, escaped unless inside `<script>` or `<style>`
+    pub fn text(&mut self, text: &str) {
+        match self.open.last() {
+            Some((tag, Namespace::Html)) if is_raw_text_element(tag) => {
+                self.out.push_str(&escape_raw_text(tag, text));
+            }
+            _ => self.out.push_str(&escape_text(text)),
+        }
+    }
+
+    /// Write a comment
+    pub fn comment(&mut self, text: &str) {
+        self.out.push_str("<!--");
+        self.out.push_str(&text.replace("--", "- -"));
+        self.out.push_str("-->");
+    }
+
+    /// Append markup that is already serialized
+    pub fn raw(&mut self, html: &str) {
+        self.out.push_str(html);
+    }
+
+    /// The HTML written so far
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn namespace(&self) -> Namespace {
+        self.open
+            .last()
+            .map_or(Namespace::Html, |(_, namespace)| *namespace)
+    }
+}
+
+/// Raw text with every `</tag` that would close the element early escaped
+fn escape_raw_text(tag: &str, text: &str) -> String {
+    let closing = format!("</{}", tag);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = find_ignore_case(rest, &closing) {
+        out.push_str(&rest[..index]);
+        out.push_str("<\\/");
+        rest = &rest[index + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Byte index of `needle` (ASCII) in `haystack`, ignoring ASCII case
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(f: impl FnOnce(&mut HtmlWriter)) -> String {
+        let mut html = HtmlWriter::new();
+        f(&mut html);
+        html.finish()
+    }
+
+    #[test]
+    fn test_attributes_are_sorted_escaped_and_validated() {
+        let html = write(|html| {
+            html.element(
+                "div",
+                [
+                    ("title", "a \"b\" & <c>"),
+                    ("class", "x"),
+                    ("bad name", "1"),
+                    ("onclick\"", "2"),
+                    ("data-id", "7"),
+                ],
+                |_| {},
+            )
+        });
+        assert_eq!(
+            html,
+            "<div class=\"x\" data-id=\"7\" title=\"a &quot;b&quot; &amp; &lt;c&gt;\"></div>"
+        );
+    }
+
+    #[test]
+    fn test_invalid_tag_names_are_skipped() {
+        let html = write(|html| {
+            html.element("custom-el", [], |html| {
+                html.element("img src=x onerror=alert(1)", [], |_| {});
+                html.element("p><script", [], |html| html.text("hidden"));
+                html.element("", [], |_| {});
+                html.element("h1", [], |html| html.text("kept"));
+            })
+        });
+        assert_eq!(html, "<custom-el><h1>kept</h1></custom-el>");
+    }
+
+    #[test]
+    fn test_boolean_and_void_elements() {
+        let html = write(|html| {
+            html.element(
+                "input",
+                [("checked", "false"), ("disabled", ""), ("type", "checkbox")],
+                |html| html.text("ignored"),
+            );
+            html.element("br", [], |_| {});
+        });
+        assert_eq!(html, "<input disabled type=\"checkbox\" /><br />");
+    }
+
+    #[test]
+    fn test_raw_text_cannot_close_its_element() {
+        let html = write(|html| {
+            html.element("script", [], |html| {
+                html.text("if (a < b && c) { x = '</SCRIPT><b>'; }")
+            });
+            html.element("style", [], |html| html.text("a > b { color: red }"));
+            html.element("p", [], |html| html.text("a < b && c"));
+        });
+        assert_eq!(
+            html,
+            "<script>if (a < b && c) { x = '<\\/SCRIPT><b>'; }</script>\
+             <style>a > b { color: red }</style>\
+             <p>a &lt; b &amp;&amp; c</p>"
+        );
+    }
+
+    #[test]
+    fn test_svg_and_mathml_keep_case_and_self_close() {
+        let html = write(|html| {
+            html.element("svg", [("viewBox", "0 0 10 10")], |html| {
+                html.element("path", [("d", "M0 0")], |_| {});
+                html.element("script", [], |html| html.text("a < b"));
+                html.element("foreignObject", [], |html| {
+                    html.element("input", [("hidden", "true")], |_| {});
+                });
+            });
+            html.element("math", [], |html| {
+                html.element("mi", [], |html| html.text("x"));
+                html.element("mspace", [], |_| {});
+            });
+        });
+        assert_eq!(
+            html,
+            "<svg viewBox=\"0 0 10 10\"><path d=\"M0 0\" /><script>a &lt; b</script>\
+             <foreignObject><input hidden /></foreignObject></svg>\
+             <math><mi>x</mi><mspace /></math>"
+        );
+    }
+
+    #[test]
+    fn test_comments_cannot_end_early() {
+        assert_eq!(write(|html| html.comment("a --> b")), "<!--a - -> b-->");
+    }
+}
@@ -47,6 +47,7 @@
 
 // Re-export the proc macro
 pub use windjammer_ui_macro::component;
+pub use windjammer_ui_macro::Lenses;
 pub use windjammer_ui_macro::Props;
 
 pub mod app;
@@ -57,29 +58,44 @@ pub mod app_reactive;
 #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
 pub mod app_reactive_eframe;
 pub mod event_handler;
-#[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
 // pub mod scene_gizmos; // TODO: Implement scene gizmos module
 pub mod undo_redo; // Available on all platforms
 
+pub mod binding;
+
 // Component trait (used by renderer, runtime, ssr)
 pub mod component;
 pub mod component_runtime;
 pub mod components; // Component library
+pub mod context;
 pub mod events;
+pub mod html;
+pub mod memory_dom;
+pub mod persisted;
 pub mod platform;
+pub mod reactive_graph;
 pub mod reactivity;
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
 pub mod desktop_app_context;
 #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
 pub mod desktop_renderer;
+#[deprecated(note = "merged into `reactivity`; use `windjammer_ui::reactivity` instead")]
 pub mod reactivity_optimized;
+pub mod reconciler;
 pub mod renderer;
+pub mod resource;
 pub mod routing;
 pub mod runtime;
+pub mod scheduler;
+pub mod signal_collections;
 pub mod simple_renderer;
 pub mod simple_vnode;
+pub mod snapshot;
 pub mod ssr;
+pub mod store;
+pub mod sync_signal;
+pub mod testing;
 pub mod to_vnode;
 pub mod vdom;
 pub mod vnode_ffi; // FFI for Windjammer components to construct VNodes
@@ -99,9 +115,10 @@ pub mod prelude {
     pub use crate::component_runtime;
     pub use crate::events::{Event, EventHandler};
     pub use crate::platform::{Event as PlatformEvent, GestureEvent, SwipeDirection, Target};
-    pub use crate::reactivity::{Computed, Effect, Signal};
+    pub use crate::reactivity::{batch, untrack, Computed, Effect, Signal};
     pub use crate::renderer::WebRenderer;
     pub use crate::routing::{Route, Router};
+    pub use crate::signal_collections::{SignalMap, SignalVec};
     pub use crate::simple_vnode::{VAttr, VNode};
     pub use crate::to_vnode::ToVNode;
     pub use crate::vnode_ffi; // VNode FFI for cross-platform components
@@ -128,9 +145,9 @@ pub mod prelude {
     pub use crate::components::text::{Text, TextSize, TextWeight};
 }
 
-/// Mount a component to the DOM (WASM only)
+/// Mount a component to the DOM, or release what was mounted (WASM only)
 #[cfg(target_arch = "wasm32")]
-pub use renderer::mount;
+pub use renderer::{mount, mount_with_registry, unmount};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,539 @@
+//! In-memory DOM for running renderers and patches without a browser
+//!
+//! [`MemoryRenderer`] implements [`Renderer`] on a plain Rust tree that
+//! follows the same rules as [`WebRenderer`](crate::renderer::WebRenderer):
+//! properties such as `value` are kept apart from attributes, event handlers
+//! are attached to their element, fragments are spliced into their parent,
+//! portals keep their content under a placeholder node, bindings stay bound
+//! and are read when inspected, and patches are applied in order with paths
+//! starting at the rendered root. It lets tests check that applying
+//! `diff(old, new)` to `old` yields `new`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::memory_dom::MemoryRenderer;
+//! use windjammer_ui::renderer::Renderer;
+//! use windjammer_ui::vdom::{diff, VElement, VNode};
+//!
+//! let old: VNode = VElement::new("p").child("Hello".into()).into();
+//! let new: VNode = VElement::new("p").attr("class", "big").child("Hi".into()).into();
+//!
+//! let mut dom = MemoryRenderer::new();
+//! dom.init().unwrap();
+//! dom.render(&old).unwrap();
+//! dom.patch(&diff(&old, &new)).unwrap();
+//! assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+//! ```
+
+use crate::binding::Binding;
+use crate::renderer::Renderer;
+use crate::vdom::{is_property, Listener, Patch, VNode};
+use std::collections::BTreeMap;
+
+/// A node of the in-memory DOM
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomNode {
+    Element(DomElement),
+    Text(String),
+    /// A text node bound to a reactive value
+    Dynamic(Binding),
+    /// Placeholder of a portal, holding the content it renders into `target`
+    Portal {
+        target: String,
+        children: Vec<DomNode>,
+    },
+}
+
+/// An element of the in-memory DOM
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DomElement {
+    pub tag: String,
+    pub attributes: BTreeMap<String, String>,
+    /// Live properties, see [`is_property`]
+    pub properties: BTreeMap<String, String>,
+    /// Attached handlers by event name
+    pub listeners: BTreeMap<String, Listener>,
+    /// Bound attributes and properties; see [`DomNode::resolved`]
+    pub bindings: BTreeMap<String, Binding>,
+    pub children: Vec<DomNode>,
+}
+
+impl DomNode {
+    /// Build the DOM nodes for a virtual node; a fragment gives one per
+    /// child, anything else exactly one
+    pub fn from_vnode(vnode: &VNode) -> Result<Vec<Self>, String> {
+        let mut nodes = Vec::new();
+        build(vnode, &mut nodes)?;
+        Ok(nodes)
+    }
+
+    /// Concatenated text of this node and its descendants, including
+    /// portal content
+    pub fn text_content(&self) -> String {
+        match self {
+            DomNode::Text(text) => text.clone(),
+            DomNode::Dynamic(binding) => binding.get_untracked(),
+            DomNode::Element(DomElement { children, .. }) | DomNode::Portal { children, .. } => {
+                children.iter().map(DomNode::text_content).collect()
+            }
+        }
+    }
+
+    /// A copy with every binding replaced by its current value, as a
+    /// browser would show it
+    pub fn resolved(&self) -> DomNode {
+        match self {
+            DomNode::Element(element) => {
+                let mut resolved = DomElement {
+                    bindings: BTreeMap::new(),
+                    children: element.children.iter().map(DomNode::resolved).collect(),
+                    ..element.clone()
+                };
+                for (key, binding) in &element.bindings {
+                    resolved.set(key, &binding.get_untracked());
+                }
+                DomNode::Element(resolved)
+            }
+            DomNode::Text(text) => DomNode::Text(text.clone()),
+            DomNode::Dynamic(binding) => DomNode::Text(binding.get_untracked()),
+            DomNode::Portal { target, children } => DomNode::Portal {
+                target: target.clone(),
+                children: children.iter().map(DomNode::resolved).collect(),
+            },
+        }
+    }
+
+    fn children_mut(&mut self) -> Result<&mut Vec<DomNode>, String> {
+        match self {
+            DomNode::Element(element) => Ok(&mut element.children),
+            DomNode::Text(_) | DomNode::Dynamic(_) => {
+                Err("Text nodes have no children".to_string())
+            }
+            DomNode::Portal { .. } => {
+                Err("Portal content is only reachable through Patch::Portal".to_string())
+            }
+        }
+    }
+
+    fn element_mut(&mut self) -> Result<&mut DomElement, String> {
+        match self {
+            DomNode::Element(element) => Ok(element),
+            _ => Err("Expected an element".to_string()),
+        }
+    }
+}
+
+fn build(vnode: &VNode, out: &mut Vec<DomNode>) -> Result<(), String> {
+    match vnode {
+        VNode::Element(element) => {
+            let mut dom = DomElement {
+                tag: element.tag.clone(),
+                ..Default::default()
+            };
+            for (key, value) in &element.attrs {
+                dom.set(key, value);
+            }
+            for (event, listener) in &element.events {
+                dom.listeners.insert(event.clone(), listener.clone());
+            }
+            for (key, binding) in &element.bindings {
+                dom.bind(key, binding);
+            }
+            for child in &element.children {
+                build(child, &mut dom.children)?;
+            }
+            out.push(DomNode::Element(dom));
+        }
+        VNode::Text(text) => out.push(DomNode::Text(text.content.clone())),
+        VNode::Dynamic(binding) => out.push(DomNode::Dynamic(binding.clone())),
+        VNode::Component(_) => return Err("Cannot render component directly".to_string()),
+        VNode::Fragment(children) => {
+            for child in children {
+                build(child, out)?;
+            }
+        }
+        VNode::Portal { target, children } => {
+            let mut content = Vec::new();
+            for child in children {
+                build(child, &mut content)?;
+            }
+            out.push(DomNode::Portal {
+                target: target.clone(),
+                children: content,
+            });
+        }
+        VNode::Memo(memo) => build(&memo.node, out)?,
+        VNode::Empty => out.push(DomNode::Text(String::new())),
+    }
+    Ok(())
+}
+
+impl DomElement {
+    /// Set an attribute, or a property for keys that are properties
+    fn set(&mut self, key: &str, value: &str) {
+        let map = if is_property(key) {
+            &mut self.properties
+        } else {
+            &mut self.attributes
+        };
+        map.insert(key.to_string(), value.to_string());
+    }
+
+    /// Bind an attribute or property, in place of its value
+    fn bind(&mut self, key: &str, binding: &Binding) {
+        self.attributes.remove(key);
+        self.properties.remove(key);
+        self.bindings.insert(key.to_string(), binding.clone());
+    }
+}
+
+/// [`Renderer`] that keeps the DOM in memory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryRenderer {
+    initialized: bool,
+    /// Children of the mount point: the rendered tree, or the children of a
+    /// root fragment
+    root: Vec<DomNode>,
+}
+
+impl MemoryRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An initialized renderer that has rendered `vnode`
+    pub fn rendered(vnode: &VNode) -> Result<Self, String> {
+        let mut renderer = Self::new();
+        renderer.init()?;
+        renderer.render(vnode)?;
+        Ok(renderer)
+    }
+
+    /// The first rendered node
+    pub fn root(&self) -> Option<&DomNode> {
+        self.root.first()
+    }
+
+    /// Every node rendered at the mount point
+    pub fn roots(&self) -> &[DomNode] {
+        &self.root
+    }
+
+    /// Mutable access to the rendered nodes, for simulating user input
+    pub(crate) fn roots_mut(&mut self) -> &mut [DomNode] {
+        &mut self.root
+    }
+
+    /// The node at `path`, using the same paths as [`Patch`]
+    pub fn node_at(&self, path: &[usize]) -> Option<&DomNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.root.get(*first)?;
+        for index in rest {
+            match node {
+                DomNode::Element(element) => node = element.children.get(*index)?,
+                _ => return None,
+            }
+        }
+        Some(node)
+    }
+
+    /// Run the `event` handler of the element at `path`
+    ///
+    /// Returns false if the element has no handler for `event`.
+    pub fn dispatch(&self, path: &[usize], event: &str) -> Result<bool, String> {
+        let listener = match self.node_at(path) {
+            Some(DomNode::Element(element)) => element.listeners.get(event).cloned(),
+            _ => return Err(format!("No element at {:?}", path)),
+        };
+        // Cloned first so the handler may trigger a re-render
+        Ok(listener.map(|listener| listener.call()).is_some())
+    }
+}
+
+fn node_at_mut<'a>(root: &'a mut Vec<DomNode>, path: &[usize]) -> Result<&'a mut DomNode, String> {
+    let (index, parent) = path.split_last().ok_or("Empty patch path")?;
+    children_at(root, parent)?
+        .get_mut(*index)
+        .ok_or(format!("Child not found at index {}", index))
+}
+
+/// The children of the node at `path`; `root` itself for `[]`
+fn children_at<'a>(
+    root: &'a mut Vec<DomNode>,
+    path: &[usize],
+) -> Result<&'a mut Vec<DomNode>, String> {
+    let mut children = root;
+    for index in path {
+        children = children
+            .get_mut(*index)
+            .ok_or(format!("Child not found at index {}", index))?
+            .children_mut()?;
+    }
+    Ok(children)
+}
+
+/// Apply one patch to the tree whose mount point holds `root`
+fn apply(root: &mut Vec<DomNode>, patch: &Patch) -> Result<(), String> {
+    match patch {
+        Patch::Replace { path, node } => {
+            let (index, parent) = path.split_last().ok_or("Empty patch path")?;
+            let nodes = DomNode::from_vnode(node)?;
+            let children = children_at(root, parent)?;
+            if *index >= children.len() {
+                return Err(format!("Child not found at index {}", index));
+            }
+            children.splice(*index..=*index, nodes);
+        }
+        Patch::UpdateText { path, content } => {
+            if let DomNode::Text(text) = node_at_mut(root, path)? {
+                *text = content.clone();
+            }
+        }
+        Patch::BindText { path, binding } => {
+            let node = node_at_mut(root, path)?;
+            if let DomNode::Dynamic(_) = node {
+                *node = DomNode::Dynamic(binding.clone());
+            }
+        }
+        Patch::SetAttribute { path, key, value } => {
+            let element = node_at_mut(root, path)?.element_mut()?;
+            element.bindings.remove(key);
+            element.attributes.insert(key.clone(), value.clone());
+        }
+        Patch::RemoveAttribute { path, key } => {
+            let element = node_at_mut(root, path)?.element_mut()?;
+            element.attributes.remove(key);
+            element.properties.remove(key);
+            element.bindings.remove(key);
+        }
+        Patch::SetProperty { path, key, value } => {
+            let element = node_at_mut(root, path)?.element_mut()?;
+            element.bindings.remove(key);
+            element.properties.insert(key.clone(), value.clone());
+        }
+        Patch::BindAttribute { path, key, binding } => {
+            node_at_mut(root, path)?.element_mut()?.bind(key, binding);
+        }
+        Patch::SetEvent {
+            path,
+            event,
+            listener,
+        } => {
+            let element = node_at_mut(root, path)?.element_mut()?;
+            element.listeners.insert(event.clone(), listener.clone());
+        }
+        Patch::RemoveEvent { path, event } => {
+            let element = node_at_mut(root, path)?.element_mut()?;
+            element.listeners.remove(event);
+        }
+        Patch::Append { path, node } => {
+            let nodes = DomNode::from_vnode(node)?;
+            children_at(root, path)?.extend(nodes);
+        }
+        Patch::Insert { path, index, node } => {
+            let nodes = DomNode::from_vnode(node)?;
+            let children = children_at(root, path)?;
+            if *index > children.len() {
+                return Err(format!("Insert index {} out of bounds", index));
+            }
+            children.splice(*index..*index, nodes);
+        }
+        Patch::Move { path, from, to } => {
+            let children = children_at(root, path)?;
+            if *from >= children.len() || *to >= children.len() {
+                return Err(format!("Move {} -> {} out of bounds", from, to));
+            }
+            let child = children.remove(*from);
+            children.insert(*to, child);
+        }
+        Patch::Remove { path } => {
+            let (index, parent) = path.split_last().ok_or("Empty patch path")?;
+            let children = children_at(root, parent)?;
+            if *index >= children.len() {
+                return Err(format!("Child not found at index {}", index));
+            }
+            children.remove(*index);
+        }
+        Patch::Portal { path, patches } => match node_at_mut(root, path)? {
+            DomNode::Portal { children, .. } => {
+                for patch in patches {
+                    apply(children, patch)?;
+                }
+            }
+            _ => return Err("Expected a portal".to_string()),
+        },
+    }
+    Ok(())
+}
+
+impl Renderer for MemoryRenderer {
+    fn init(&mut self) -> Result<(), String> {
+        self.initialized = true;
+        self.root.clear();
+        Ok(())
+    }
+
+    fn render(&mut self, vnode: &VNode) -> Result<(), String> {
+        if !self.initialized {
+            return Err("Renderer not initialized".to_string());
+        }
+        self.root = DomNode::from_vnode(vnode)?;
+        Ok(())
+    }
+
+    fn patch(&mut self, patches: &[Patch]) -> Result<(), String> {
+        if !self.initialized {
+            return Err("No root element".to_string());
+        }
+        patches
+            .iter()
+            .try_for_each(|patch| apply(&mut self.root, patch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vdom::{diff, VElement};
+
+    fn patched(old: &VNode, new: &VNode) -> MemoryRenderer {
+        let mut dom = MemoryRenderer::rendered(old).unwrap();
+        dom.patch(&diff(old, new)).unwrap();
+        dom
+    }
+
+    #[test]
+    fn test_dispatch_runs_the_patched_handler() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let button = |label: &'static str| -> VNode {
+            let log = log.clone();
+            VElement::new("button")
+                .on("click", move || log.borrow_mut().push(label))
+                .into()
+        };
+        let (old, new) = (button("old"), button("new"));
+
+        let dom = patched(&old, &new);
+        assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+        assert_eq!(dom.dispatch(&[0], "click"), Ok(true));
+        assert_eq!(dom.dispatch(&[0], "input"), Ok(false));
+        assert!(dom.dispatch(&[1], "click").is_err());
+        assert_eq!(*log.borrow(), vec!["new"]);
+    }
+
+    #[test]
+    fn test_properties_are_kept_apart_from_attributes() {
+        let input: VNode = VElement::new("input")
+            .attr("type", "checkbox")
+            .attr("checked", "true")
+            .into();
+        let dom = MemoryRenderer::rendered(&input).unwrap();
+        let Some(DomNode::Element(element)) = dom.node_at(&[0]) else {
+            panic!("expected an element");
+        };
+        assert_eq!(
+            element.attributes.get("type").map(String::as_str),
+            Some("checkbox")
+        );
+        assert_eq!(
+            element.properties.get("checked").map(String::as_str),
+            Some("true")
+        );
+        assert!(!element.attributes.contains_key("checked"));
+    }
+
+    #[test]
+    fn test_keyed_reorder_matches_a_fresh_render() {
+        let list = |keys: &[&str]| -> VNode {
+            VElement::new("ul")
+                .children(
+                    keys.iter()
+                        .map(|key| VElement::new("li").key(*key).child((*key).into()).into())
+                        .collect(),
+                )
+                .into()
+        };
+        let old = list(&["a", "b", "c", "d", "e"]);
+        let new = list(&["e", "c", "x", "a", "d"]);
+
+        let dom = patched(&old, &new);
+        assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+        assert_eq!(dom.root().unwrap().text_content(), "ecxad");
+    }
+
+    #[test]
+    fn test_bindings_update_without_patches() {
+        use crate::reactivity::Signal;
+
+        let count = Signal::new(1);
+        let old: VNode = VElement::new("progress")
+            .bind("value", count.clone())
+            .child(VNode::dynamic(count.clone()))
+            .into();
+        let mut dom = MemoryRenderer::rendered(&old).unwrap();
+
+        count.set(2);
+        let resolved = dom.root().unwrap().resolved();
+        let DomNode::Element(element) = &resolved else {
+            panic!("expected an element");
+        };
+        assert_eq!(
+            element.properties.get("value").map(String::as_str),
+            Some("2")
+        );
+        assert_eq!(resolved.text_content(), "2");
+
+        // A plain value drops the binding
+        let new: VNode = VElement::new("progress")
+            .attr("value", "5")
+            .child("5".into())
+            .into();
+        dom.patch(&diff(&old, &new)).unwrap();
+        assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+    }
+
+    #[test]
+    fn test_out_of_range_patches_fail() {
+        let mut dom = MemoryRenderer::rendered(&VElement::new("div").into()).unwrap();
+        assert!(dom.patch(&[Patch::Remove { path: vec![0, 3] }]).is_err());
+        assert!(MemoryRenderer::new().patch(&[]).is_err());
+    }
+
+    #[test]
+    fn test_fragments_splice_and_portals_patch_in_place() {
+        let view = |items: Vec<&str>, tooltip: &str| -> VNode {
+            VElement::new("tr")
+                .child(VNode::portal(
+                    "body",
+                    vec![VElement::new("p").child(tooltip.into()).into()],
+                ))
+                .child(VNode::fragment(
+                    items
+                        .into_iter()
+                        .map(|item| VElement::new("td").child(item.into()).into())
+                        .collect(),
+                ))
+                .into()
+        };
+        let old = view(vec!["a", "b"], "saved");
+        let new = view(vec!["a", "b", "c"], "unsaved");
+
+        let patches = diff(&old, &new);
+        assert!(patches
+            .iter()
+            .any(|patch| matches!(patch, Patch::Portal { path, .. } if path == &vec![0, 0])));
+        let dom = patched(&old, &new);
+        assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+
+        let Some(DomNode::Element(row)) = dom.root() else {
+            panic!("expected an element");
+        };
+        assert_eq!(row.children.len(), 4);
+        assert_eq!(row.children[0].text_content(), "unsaved");
+        assert_eq!(row.children[3].text_content(), "c");
+    }
+}
@@ -0,0 +1,615 @@
+//! Signals that survive restarts
+//!
+//! [`persisted_signal`] loads its initial value from storage and writes it
+//! back, debounced, whenever it changes. Values are serialized with serde into
+//! a versioned envelope so stored data can be migrated when its shape changes.
+//!
+//! Storage goes through a [`StorageBackend`]:
+//! - web: the browser's `localStorage` ([`LocalStorage`])
+//! - desktop: one JSON file under the app data directory ([`FileStorage`])
+//! - tests: [`MemoryStorage`]
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//! use std::time::Duration;
+//! use windjammer_ui::persisted::{persisted_signal, set_storage_backend, MemoryStorage, Persisted};
+//!
+//! set_storage_backend(Rc::new(MemoryStorage::new()));
+//!
+//! let sidebar_width = persisted_signal("sidebar_width", 240.0);
+//! sidebar_width.set(320.0); // Written to storage shortly after
+//!
+//! // Version 2 stores a list instead of a single path
+//! let recent = Persisted::new("recent_files", Vec::<String>::new())
+//!     .version(2)
+//!     .migration(1, |path| vec![path].into())
+//!     .debounce(Duration::from_secs(1))
+//!     .signal();
+//! ```
+
+use crate::platform::capabilities::{Capability, CapabilityError, CapabilityResult};
+use crate::reactivity::{untrack, Effect, Signal};
+use crate::scheduler::{self, Scheduler, TimerId};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Delay between the last change and the write when none is configured
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Key/value storage for persisted signals
+pub trait StorageBackend {
+    /// Read the stored string for `key`, if any
+    fn load(&self, key: &str) -> CapabilityResult<Option<String>>;
+    /// Store `value` under `key`
+    fn save(&self, key: &str, value: &str) -> CapabilityResult<()>;
+    /// Delete `key`
+    fn remove(&self, key: &str) -> CapabilityResult<()>;
+}
+
+/// In-memory storage, shared between clones (for tests)
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    entries: Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl MemoryStorage {
+    /// Create empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The raw stored string for `key`
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    /// Store a raw string, e.g. data written by an older version
+    pub fn insert(&self, key: impl Into<String>, value: impl Into<String>) {
+        self.entries.borrow_mut().insert(key.into(), value.into());
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn load(&self, key: &str) -> CapabilityResult<Option<String>> {
+        Ok(self.get(key))
+    }
+
+    fn save(&self, key: &str, value: &str) -> CapabilityResult<()> {
+        self.insert(key, value);
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> CapabilityResult<()> {
+        self.entries.borrow_mut().remove(key);
+        Ok(())
+    }
+}
+
+/// The browser's `localStorage`
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn storage() -> CapabilityResult<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or(CapabilityError::NotAvailable(Capability::LocalStorage))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StorageBackend for LocalStorage {
+    fn load(&self, key: &str) -> CapabilityResult<Option<String>> {
+        Self::storage()?
+            .get_item(key)
+            .map_err(|_| CapabilityError::PermissionDenied(Capability::LocalStorage))
+    }
+
+    fn save(&self, key: &str, value: &str) -> CapabilityResult<()> {
+        Self::storage()?
+            .set_item(key, value)
+            .map_err(|_| CapabilityError::Error(format!("Could not store '{}'", key)))
+    }
+
+    fn remove(&self, key: &str) -> CapabilityResult<()> {
+        Self::storage()?
+            .remove_item(key)
+            .map_err(|_| CapabilityError::Error(format!("Could not remove '{}'", key)))
+    }
+}
+
+/// All keys in one JSON object on disk
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// Store everything in the JSON file at `path`
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store everything in `<data dir>/<app_name>/persisted.json`
+    ///
+    /// The data dir is `%APPDATA%` on Windows, `~/Library/Application Support`
+    /// on macOS and `$XDG_DATA_HOME` (or `~/.local/share`) elsewhere.
+    pub fn app_data(app_name: &str) -> CapabilityResult<Self> {
+        let env = |name: &str| std::env::var_os(name).map(std::path::PathBuf::from);
+        let data_dir = if cfg!(target_os = "windows") {
+            env("APPDATA")
+        } else if cfg!(target_os = "macos") {
+            env("HOME").map(|home| home.join("Library/Application Support"))
+        } else {
+            env("XDG_DATA_HOME").or_else(|| env("HOME").map(|home| home.join(".local/share")))
+        };
+        let data_dir = data_dir.ok_or(CapabilityError::NotAvailable(Capability::Filesystem))?;
+        Ok(Self::new(data_dir.join(app_name).join("persisted.json")))
+    }
+
+    /// Read every entry
+    ///
+    /// A file that isn't valid JSON is moved aside to `<path>.corrupt` so the
+    /// next write starts over instead of failing forever.
+    fn read(&self) -> CapabilityResult<serde_json::Map<String, Value>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(serde_json::Map::new()),
+            Err(e) => return Err(CapabilityError::Error(format!("Read error: {}", e))),
+        };
+        match serde_json::from_str(&content) {
+            Ok(entries) => Ok(entries),
+            Err(e) => {
+                let backup = self.sibling("corrupt");
+                std::fs::rename(&self.path, &backup)
+                    .map_err(|e| CapabilityError::Error(format!("Backup error: {}", e)))?;
+                eprintln!(
+                    "persisted storage: parse error in {} ({}), moved to {}",
+                    self.path.display(),
+                    e,
+                    backup.display()
+                );
+                Ok(serde_json::Map::new())
+            }
+        }
+    }
+
+    /// Replace the file atomically by renaming a fully written temp file
+    fn write(&self, entries: &serde_json::Map<String, Value>) -> CapabilityResult<()> {
+        let error = |e: std::io::Error| CapabilityError::Error(format!("Write error: {}", e));
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(error)?;
+        }
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| CapabilityError::Error(format!("Write error: {}", e)))?;
+        let temp = self.sibling("tmp");
+        std::fs::write(&temp, content).map_err(error)?;
+        std::fs::rename(&temp, &self.path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            error(e)
+        })
+    }
+
+    /// `<path>.<extension>`, next to the storage file
+    fn sibling(&self, extension: &str) -> std::path::PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(extension);
+        self.path.with_file_name(name)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StorageBackend for FileStorage {
+    fn load(&self, key: &str) -> CapabilityResult<Option<String>> {
+        Ok(self.read()?.get(key).map(Value::to_string))
+    }
+
+    fn save(&self, key: &str, value: &str) -> CapabilityResult<()> {
+        let mut entries = self.read()?;
+        // Stored values are JSON; keep them readable instead of escaped
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.into()));
+        entries.insert(key.to_string(), value);
+        self.write(&entries)
+    }
+
+    fn remove(&self, key: &str) -> CapabilityResult<()> {
+        let mut entries = self.read()?;
+        if entries.remove(key).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Option<Rc<dyn StorageBackend>>> = RefCell::new(None);
+    /// Writers with a change waiting for its debounce delay
+    static PENDING: RefCell<Vec<Rc<Writer>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Use `backend` for persisted signals created from now on, on this thread
+pub fn set_storage_backend(backend: Rc<dyn StorageBackend>) {
+    BACKEND.with(|slot| *slot.borrow_mut() = Some(backend));
+}
+
+/// The backend new persisted signals use
+///
+/// Defaults to `localStorage` on web and to a file named after the
+/// executable in the app data dir on desktop, falling back to memory.
+pub fn storage_backend() -> Rc<dyn StorageBackend> {
+    BACKEND.with(|slot| {
+        slot.borrow_mut()
+            .get_or_insert_with(default_backend)
+            .clone()
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default_backend() -> Rc<dyn StorageBackend> {
+    Rc::new(LocalStorage)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_backend() -> Rc<dyn StorageBackend> {
+    let app_name = std::env::current_exe().ok().and_then(|exe| {
+        exe.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+    });
+    match app_name.map(|name| FileStorage::app_data(&name)) {
+        Some(Ok(storage)) => Rc::new(storage),
+        _ => Rc::new(MemoryStorage::new()),
+    }
+}
+
+/// Stored form of a persisted value
+#[derive(Serialize, Deserialize)]
+struct Envelope<V> {
+    version: u32,
+    value: V,
+}
+
+type Migration = Box<dyn Fn(Value) -> Value>;
+
+/// Builder for a persisted signal with a version, migrations, debounce delay
+/// or backend other than the defaults
+pub struct Persisted<T> {
+    key: String,
+    default: T,
+    version: u32,
+    migrations: HashMap<u32, Migration>,
+    debounce: Duration,
+    backend: Option<Rc<dyn StorageBackend>>,
+}
+
+impl<T> Persisted<T>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    /// Persist under `key`, starting from `default` when nothing usable is stored
+    pub fn new(key: impl Into<String>, default: T) -> Self {
+        Self {
+            key: key.into(),
+            default,
+            version: 1,
+            migrations: HashMap::new(),
+            debounce: DEFAULT_DEBOUNCE,
+            backend: None,
+        }
+    }
+
+    /// Current version of the stored format (1 by default)
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Convert data stored at version `from` into version `from + 1`
+    ///
+    /// Data stored by versions without a migration path is discarded in
+    /// favor of the default.
+    pub fn migration(mut self, from: u32, migrate: impl Fn(Value) -> Value + 'static) -> Self {
+        self.migrations.insert(from, Box::new(migrate));
+        self
+    }
+
+    /// Wait this long after the last change before writing
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// Store in `backend` instead of [`storage_backend`]
+    pub fn backend(mut self, backend: Rc<dyn StorageBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Load the stored value and create the signal writing changes back
+    pub fn signal(self) -> Signal<T> {
+        let backend = self.backend.clone().unwrap_or_else(storage_backend);
+        let initial = match backend.load(&self.key) {
+            Ok(Some(stored)) => self.decode(&stored),
+            Ok(None) => None,
+            Err(error) => {
+                log_error(&self.key, &error);
+                None
+            }
+        };
+        let signal = Signal::new(initial.unwrap_or_else(|| self.default.clone()));
+
+        let writer = Rc::new(Writer {
+            key: self.key,
+            backend,
+            pending: RefCell::new(None),
+            timer: RefCell::new(None),
+        });
+        let (version, debounce) = (self.version, self.debounce);
+        let first_run = Cell::new(true);
+        let source = signal.clone();
+        Effect::new(move || {
+            let encoded = source.with(|value| serde_json::to_string(&Envelope { version, value }));
+            // The loaded value is already stored
+            if first_run.replace(false) {
+                return;
+            }
+            match encoded {
+                Ok(encoded) => untrack(|| writer.schedule(encoded, debounce)),
+                Err(error) => log_error(&writer.key, &CapabilityError::Error(error.to_string())),
+            }
+        });
+        signal
+    }
+
+    /// Decode a stored envelope, migrating it up to the current version
+    fn decode(&self, stored: &str) -> Option<T> {
+        let envelope: Envelope<Value> = serde_json::from_str(stored).ok()?;
+        if envelope.version > self.version {
+            return None;
+        }
+        let mut value = envelope.value;
+        for from in envelope.version..self.version {
+            value = self.migrations.get(&from)?(value);
+        }
+        serde_json::from_value(value).ok()
+    }
+}
+
+/// Create a signal stored under `key`, starting from `default` the first time
+///
+/// Uses format version 1, [`DEFAULT_DEBOUNCE`] and the thread's
+/// [`storage_backend`]; see [`Persisted`] for the other options.
+pub fn persisted_signal<T>(key: impl Into<String>, default: T) -> Signal<T>
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+{
+    Persisted::new(key, default).signal()
+}
+
+/// Debounced writes for one persisted signal
+struct Writer {
+    key: String,
+    backend: Rc<dyn StorageBackend>,
+    pending: RefCell<Option<String>>,
+    /// Timer that writes the pending value, and the scheduler running it
+    timer: RefCell<Option<(Rc<dyn Scheduler>, TimerId)>>,
+}
+
+impl Writer {
+    fn schedule(self: &Rc<Self>, encoded: String, debounce: Duration) {
+        *self.pending.borrow_mut() = Some(encoded);
+        if debounce.is_zero() {
+            self.write();
+            return;
+        }
+        self.cancel_timer();
+        let scheduler = scheduler::scheduler();
+        let writer = self.clone();
+        let timer = scheduler.set_timeout(debounce, Box::new(move || writer.write()));
+        *self.timer.borrow_mut() = Some((scheduler, timer));
+        PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            if !pending.iter().any(|writer| Rc::ptr_eq(writer, self)) {
+                pending.push(self.clone());
+            }
+        });
+    }
+
+    fn cancel_timer(&self) {
+        if let Some((scheduler, timer)) = self.timer.take() {
+            scheduler.cancel(timer);
+        }
+    }
+
+    fn write(self: &Rc<Self>) {
+        self.cancel_timer();
+        PENDING.with(|pending| {
+            pending
+                .borrow_mut()
+                .retain(|writer| !Rc::ptr_eq(writer, self))
+        });
+        let Some(encoded) = self.pending.take() else {
+            return;
+        };
+        if let Err(error) = self.backend.save(&self.key, &encoded) {
+            log_error(&self.key, &error);
+        }
+    }
+}
+
+/// Write every pending change now, e.g. before the app exits
+pub fn flush_persisted() {
+    let writers = PENDING.with(|pending| pending.take());
+    for writer in writers {
+        writer.write();
+    }
+}
+
+fn log_error(key: &str, error: &CapabilityError) {
+    let message = format!("persisted signal '{}': {}", key, error);
+    #[cfg(target_arch = "wasm32")]
+    crate::component_runtime::console_error(&message);
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("{}", message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{set_scheduler, VirtualClock};
+
+    fn immediate<T>(storage: &MemoryStorage, key: &str, default: T) -> Signal<T>
+    where
+        T: Serialize + DeserializeOwned + Clone + 'static,
+    {
+        Persisted::new(key, default)
+            .debounce(Duration::ZERO)
+            .backend(Rc::new(storage.clone()))
+            .signal()
+    }
+
+    #[test]
+    fn test_value_survives_a_restart() {
+        let storage = MemoryStorage::new();
+
+        let theme = immediate(&storage, "theme", "light".to_string());
+        assert_eq!(theme.get(), "light");
+        assert_eq!(storage.get("theme"), None);
+
+        theme.set("dark".to_string());
+        assert_eq!(
+            storage.get("theme").as_deref(),
+            Some(r#"{"version":1,"value":"dark"}"#)
+        );
+
+        let restarted = immediate(&storage, "theme", "light".to_string());
+        assert_eq!(restarted.get(), "dark");
+    }
+
+    #[test]
+    fn test_migrations_upgrade_stored_data() {
+        let storage = MemoryStorage::new();
+        storage.insert("recent", r#"{"version":1,"value":"a.txt"}"#);
+
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        struct Recent {
+            files: Vec<String>,
+        }
+        let recent = Persisted::new("recent", Recent { files: vec![] })
+            .version(3)
+            .migration(1, |path| serde_json::json!([path]))
+            .migration(2, |paths| serde_json::json!({ "files": paths }))
+            .backend(Rc::new(storage.clone()))
+            .signal();
+        assert_eq!(recent.get().files, vec!["a.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_unusable_data_falls_back_to_default() {
+        let storage = MemoryStorage::new();
+        storage.insert("garbage", "not json");
+        storage.insert("future", r#"{"version":9,"value":1}"#);
+        storage.insert("gap", r#"{"version":1,"value":1}"#);
+
+        assert_eq!(immediate(&storage, "garbage", 5).get(), 5);
+        assert_eq!(immediate(&storage, "future", 5).get(), 5);
+        let gap = Persisted::new("gap", 5)
+            .version(3)
+            .migration(2, |v| v)
+            .backend(Rc::new(storage.clone()))
+            .signal();
+        assert_eq!(gap.get(), 5);
+    }
+
+    #[test]
+    fn test_writes_are_debounced() {
+        let clock = VirtualClock::new();
+        set_scheduler(Rc::new(clock.clone()));
+        let storage = MemoryStorage::new();
+        let width = Persisted::new("width", 100)
+            .debounce(Duration::from_millis(20))
+            .backend(Rc::new(storage.clone()))
+            .signal();
+
+        for w in 101..=110 {
+            width.set(w);
+        }
+        clock.advance(Duration::from_millis(19));
+        assert_eq!(storage.get("width"), None);
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(
+            storage.get("width").as_deref(),
+            Some(r#"{"version":1,"value":110}"#)
+        );
+    }
+
+    #[test]
+    fn test_flush_persisted_writes_pending_changes() {
+        let storage = MemoryStorage::new();
+        let zoom = Persisted::new("zoom", 1.0)
+            .debounce(Duration::from_secs(60))
+            .backend(Rc::new(storage.clone()))
+            .signal();
+
+        zoom.set(2.5);
+        assert_eq!(storage.get("zoom"), None);
+        flush_persisted();
+        assert_eq!(
+            storage.get("zoom").as_deref(),
+            Some(r#"{"version":1,"value":2.5}"#)
+        );
+    }
+
+    #[test]
+    fn test_file_storage_round_trip() {
+        let dir = std::env::temp_dir().join(format!("windjammer-persisted-{}", std::process::id()));
+        let storage = FileStorage::new(dir.join("persisted.json"));
+
+        storage
+            .save("panel", r#"{"version":1,"value":[1,2]}"#)
+            .unwrap();
+        storage
+            .save("other", r#"{"version":1,"value":true}"#)
+            .unwrap();
+        assert_eq!(
+            storage.load("panel").unwrap().as_deref(),
+            Some(r#"{"value":[1,2],"version":1}"#)
+        );
+
+        storage.remove("panel").unwrap();
+        assert_eq!(storage.load("panel").unwrap(), None);
+        assert!(storage.load("other").unwrap().is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_backs_up_a_corrupt_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "windjammer-persisted-corrupt-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("persisted.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        let storage = FileStorage::new(&path);
+
+        storage.save("panel", r#"{"version":1,"value":2}"#).unwrap();
+        assert!(storage.load("panel").unwrap().is_some());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("persisted.json.corrupt")).unwrap(),
+            "{ not json"
+        );
+        assert!(!dir.join("persisted.json.tmp").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
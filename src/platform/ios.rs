@@ -27,9 +27,22 @@ impl IOSRenderer {
             VNode::Text(text) => {
                 // Map to UILabel
             }
+            VNode::Dynamic(binding) => {
+                // Map to a UILabel updated by the binding
+            }
             VNode::Component(component) => {
                 // Render child component
             }
+            VNode::Fragment(children) => {
+                // Render children into the parent view
+            }
+            VNode::Portal { target, children } => {
+                // Present children in an overlay window
+            }
+            VNode::Memo(memo) => {
+                // Render the shared subtree
+                self.render(&memo.node);
+            }
             VNode::Empty => {
                 // No-op
             }
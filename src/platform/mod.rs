@@ -6,6 +6,7 @@
 // - iOS (UIKit/SwiftUI) - Future
 // - Android (Jetpack Compose) - Future
 
+pub mod capabilities;
 pub mod desktop;
 pub mod web;
 
@@ -0,0 +1,340 @@
+//! Introspection of the reactive graph for debugging
+//!
+//! [`GraphSnapshot::capture`] lists the live signals, computeds and effects of
+//! the current thread together with their dependency edges, debug labels and
+//! run counts, and exports them as Graphviz DOT or JSON. [`log_effect_reruns`]
+//! prints which signal write made each effect run again.
+//!
+//! Labels are attached with `labeled` on signals, computeds and memos,
+//! [`Effect::new_labeled`](crate::reactivity::Effect::new_labeled) for
+//! effects, or [`set_label`] for any node id.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::reactive_graph::GraphSnapshot;
+//! use windjammer_ui::reactivity::{Computed, Effect, Signal};
+//!
+//! let count = Signal::new(1).labeled("count");
+//! let doubled = Computed::new({
+//!     let count = count.clone();
+//!     move || count.get() * 2
+//! })
+//! .labeled("doubled");
+//! Effect::new_labeled("print", move || println!("{}", doubled.get()));
+//!
+//! // Paste into any Graphviz viewer
+//! println!("{}", GraphSnapshot::capture().to_dot());
+//! ```
+
+use crate::reactivity;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+pub use crate::reactivity::NodeKind;
+
+/// Callback receiving the reason for each effect re-run
+type RerunHook = Rc<dyn Fn(&RerunReason)>;
+
+thread_local! {
+    static RERUN_HOOK: RefCell<Option<RerunHook>> = const { RefCell::new(None) };
+}
+
+/// One signal, computed or effect
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub id: usize,
+    pub kind: NodeKind,
+    pub label: Option<String>,
+    /// Times a computed or effect ran, or a signal was written
+    pub runs: usize,
+    /// Distance from the furthest upstream signal
+    pub height: usize,
+    /// Ids of the nodes read during the last run
+    pub sources: Vec<usize>,
+}
+
+impl GraphNode {
+    /// The label, or the kind and id when there is none
+    pub fn name(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("{}#{}", kind_name(self.kind), self.id),
+        }
+    }
+}
+
+impl fmt::Display for GraphNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{} \"{}\" (#{})", kind_name(self.kind), label, self.id),
+            None => write!(f, "{} #{}", kind_name(self.kind), self.id),
+        }
+    }
+}
+
+/// Point-in-time copy of the reactive graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphSnapshot {
+    /// Live nodes ordered by id
+    pub nodes: Vec<GraphNode>,
+}
+
+impl GraphSnapshot {
+    /// Capture the live nodes of the current thread
+    pub fn capture() -> Self {
+        Self {
+            nodes: reactivity::graph_nodes(),
+        }
+    }
+
+    /// Look up a node by id
+    pub fn node(&self, id: usize) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Nodes of one kind
+    pub fn nodes_of(&self, kind: NodeKind) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.iter().filter(move |node| node.kind == kind)
+    }
+
+    /// Dependency edges as `(source, observer)` pairs
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.sources.iter().map(move |source| (*source, node.id)))
+            .collect()
+    }
+
+    /// Render as a Graphviz DOT digraph, edges pointing downstream
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactive {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Signal => "ellipse",
+                NodeKind::Computed => "box",
+                NodeKind::Effect => "diamond",
+            };
+            dot.push_str(&format!(
+                "    n{} [label=\"{}\\nruns: {}\", shape={}];\n",
+                node.id,
+                escape_dot(&node.name()),
+                node.runs,
+                shape
+            ));
+        }
+        for (source, observer) in self.edges() {
+            dot.push_str(&format!("    n{} -> n{};\n", source, observer));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("graph snapshot serializes to JSON")
+    }
+}
+
+/// Why an effect ran again
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RerunReason {
+    /// The effect, with its run count including this run
+    pub effect: GraphNode,
+    /// Changed nodes from the effect's direct source up to the written signal
+    pub chain: Vec<GraphNode>,
+}
+
+impl fmt::Display for RerunReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} re-ran (run {})", self.effect, self.effect.runs)?;
+        if self.chain.is_empty() {
+            return Ok(());
+        }
+        write!(f, " because ")?;
+        for (i, node) in self.chain.iter().rev().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", node)?;
+        }
+        write!(f, " changed")
+    }
+}
+
+/// Attach a debug label to any node, e.g. an effect by its [`EffectId`]
+///
+/// Returns false if no live node has this id.
+///
+/// [`EffectId`]: crate::reactivity::EffectId
+pub fn set_label(id: usize, label: impl Into<String>) -> bool {
+    reactivity::label_node(id, label.into())
+}
+
+/// Call `hook` every time an effect re-runs on this thread
+pub fn on_effect_rerun(hook: impl Fn(&RerunReason) + 'static) {
+    RERUN_HOOK.with(|slot| *slot.borrow_mut() = Some(Rc::new(hook)));
+}
+
+/// Remove the hook installed by [`on_effect_rerun`] or [`log_effect_reruns`]
+pub fn clear_rerun_hook() {
+    RERUN_HOOK.with(|slot| *slot.borrow_mut() = None);
+}
+
+/// Log the reason for every effect re-run to the console (web) or stderr
+pub fn log_effect_reruns() {
+    on_effect_rerun(|reason| {
+        #[cfg(target_arch = "wasm32")]
+        crate::component_runtime::console_log(&reason.to_string());
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!("{}", reason);
+    });
+}
+
+pub(crate) fn rerun_hook() -> Option<RerunHook> {
+    RERUN_HOOK.with(|slot| slot.borrow().clone())
+}
+
+fn kind_name(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Signal => "signal",
+        NodeKind::Computed => "computed",
+        NodeKind::Effect => "effect",
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::{create_root, Computed, Effect, Signal};
+
+    #[test]
+    fn test_snapshot_lists_nodes_edges_and_runs() {
+        let count = Signal::new(1).labeled("count");
+        let doubled = Computed::new({
+            let count = count.clone();
+            move || count.get() * 2
+        })
+        .labeled("doubled");
+        let effect = Effect::new_labeled("render", {
+            let doubled = doubled.clone();
+            move || {
+                doubled.get();
+            }
+        });
+        count.set(2);
+
+        let snapshot = GraphSnapshot::capture();
+        let count_node = snapshot.node(count.id()).unwrap();
+        let doubled_node = snapshot.node(doubled.id()).unwrap();
+        let effect_node = snapshot.node(effect).unwrap();
+
+        assert_eq!(count_node.kind, NodeKind::Signal);
+        assert_eq!(count_node.runs, 1);
+        assert_eq!(doubled_node.label.as_deref(), Some("doubled"));
+        assert_eq!(doubled_node.sources, vec![count.id()]);
+        assert_eq!(effect_node.runs, 2);
+        assert_eq!(effect_node.height, 2);
+
+        let edges = snapshot.edges();
+        assert!(edges.contains(&(count.id(), doubled.id())));
+        assert!(edges.contains(&(doubled.id(), effect)));
+    }
+
+    #[test]
+    fn test_disposed_effects_leave_the_graph() {
+        let count = Signal::new(0);
+        let (scope, effect) = create_root(|scope| {
+            let count = count.clone();
+            (
+                scope,
+                Effect::new(move || {
+                    count.get();
+                }),
+            )
+        });
+        assert!(GraphSnapshot::capture().node(effect).is_some());
+
+        scope.dispose();
+        let snapshot = GraphSnapshot::capture();
+        assert!(snapshot.node(effect).is_none());
+        assert!(snapshot.node(count.id()).is_some());
+    }
+
+    #[test]
+    fn test_dot_and_json_export() {
+        let name = Signal::new(String::new()).labeled("say \"hi\"");
+        let effect = Effect::new({
+            let name = name.clone();
+            move || {
+                name.get();
+            }
+        });
+        assert!(set_label(effect, "greeting"));
+
+        let snapshot = GraphSnapshot::capture();
+        let dot = snapshot.to_dot();
+        assert!(dot.starts_with("digraph reactive {"));
+        assert!(dot.contains(&format!(
+            "n{} [label=\"say \\\"hi\\\"\\nruns: 0\"",
+            name.id()
+        )));
+        assert!(dot.contains(&format!("n{} -> n{};", name.id(), effect)));
+
+        let json: serde_json::Value = serde_json::from_str(&snapshot.to_json()).unwrap();
+        let effect_json = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["id"] == effect)
+            .unwrap();
+        assert_eq!(effect_json["kind"], "effect");
+        assert_eq!(effect_json["label"], "greeting");
+        assert_eq!(effect_json["sources"][0], name.id());
+    }
+
+    #[test]
+    fn test_rerun_reason_traces_back_to_the_written_signal() {
+        let reasons = Rc::new(RefCell::new(Vec::new()));
+        let reasons_c = reasons.clone();
+        on_effect_rerun(move |reason| reasons_c.borrow_mut().push(reason.clone()));
+
+        let items = Signal::new(vec![1, 2, 3]).labeled("items");
+        let filter = Signal::new(0).labeled("filter");
+        let visible = Computed::new({
+            let (items, filter) = (items.clone(), filter.clone());
+            move || {
+                let min = filter.get();
+                items.with(|items| items.iter().filter(|i| **i > min).count())
+            }
+        })
+        .labeled("visible");
+        Effect::new_labeled("list", move || {
+            visible.get();
+        });
+
+        filter.set(1);
+        clear_rerun_hook();
+        filter.set(2);
+
+        let reasons = reasons.borrow();
+        assert_eq!(reasons.len(), 1);
+        let names: Vec<_> = reasons[0].chain.iter().map(GraphNode::name).collect();
+        assert_eq!(names, vec!["visible", "filter"]);
+        assert_eq!(
+            reasons[0].to_string(),
+            format!(
+                "effect \"list\" (#{}) re-ran (run 2) because signal \"filter\" (#{}) -> computed \"visible\" (#{}) changed",
+                reasons[0].effect.id,
+                filter.id(),
+                reasons[0].chain[0].id
+            )
+        );
+    }
+}
@@ -3,6 +3,12 @@
 //! This module provides automatic dependency tracking and fine-grained updates.
 //! Inspired by Solid.js, Vue 3, and Leptos.
 //!
+//! Signals, computeds and effects form a graph. Writing a signal marks its
+//! direct observers dirty and everything further downstream as "check";
+//! queued effects then run in height order and pull their sources up to date
+//! first. Computeds are lazy and memoized, so every node runs at most once per
+//! change and never observes a mix of stale and fresh values.
+//!
 //! # Example
 //!
 //! ```rust
@@ -24,10 +30,36 @@
 //!
 //! count.set(5); // Prints: "Doubled: 10"
 //! ```
+//!
+//! Effects, computeds and [`on_cleanup`] callbacks belong to the reactive
+//! [`Scope`] they were created in. Disposing a scope (see [`create_root`] and
+//! [`create_scope`]) disposes everything it owns, including nested scopes and
+//! effects created by effects.
+//!
+//! Services such as a router or theme can be handed down the scope tree with
+//! [`provide_context`] and looked up by type with [`use_context`].
+//!
+//! Several writes can be grouped with [`batch`]: effects run once after the
+//! outermost batch closes and the UI is asked to re-render a single time.
+//!
+//! ```rust
+//! use windjammer_ui::reactivity::*;
+//!
+//! let first = Signal::new(String::new());
+//! let last = Signal::new(String::new());
+//!
+//! batch(|| {
+//!     first.set("Ada".to_string());
+//!     last.set("Lovelace".to_string());
+//! }); // Dependent effects run once here
+//! ```
 
-use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
+use crate::reactive_graph::{self, GraphNode, RerunReason};
+use smallvec::SmallVec;
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Unique identifier for signals
@@ -36,62 +68,628 @@ pub type SignalId = usize;
 /// Unique identifier for effects
 pub type EffectId = usize;
 
+/// Comparator deciding whether two values are equal (no change to propagate)
+pub type EqFn<T> = Rc<dyn Fn(&T, &T) -> bool>;
+
+/// Signals, computeds and effects share one id space
+static NEXT_NODE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Default number of times one effect may run while flushing a single change
+pub const DEFAULT_EFFECT_RUN_LIMIT: usize = 100;
+
+/// Callback receiving reactive runtime errors
+type ErrorHandler = Rc<dyn Fn(&ReactiveError)>;
+
 // Global reactive context (thread-local for single-threaded WASM)
 thread_local! {
     static REACTIVE_CONTEXT: RefCell<ReactiveContext> = RefCell::new(ReactiveContext::new());
     static EFFECT_REGISTRY: RefCell<HashMap<EffectId, EffectHandle>> = RefCell::new(HashMap::new());
+    /// Every live node by id, for graph introspection
+    static NODE_REGISTRY: RefCell<HashMap<usize, Weak<ReactiveNode>>> = RefCell::new(HashMap::new());
+    static ERROR_HANDLER: RefCell<Option<ErrorHandler>> = const { RefCell::new(None) };
+    /// Contexts provided outside of any scope
+    static ROOT_CONTEXTS: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Registered effect together with the scope owning what it creates
+struct EffectHandle {
+    node: Rc<ReactiveNode>,
+    scope: Scope,
 }
 
 /// Reactive context tracks the current effect being executed
 struct ReactiveContext {
-    /// The effect currently being executed (for dependency tracking)
-    current_effect: Option<EffectId>,
-    /// Cleanup functions to run when effects are disposed
-    cleanups: HashMap<EffectId, Vec<Box<dyn Fn()>>>,
+    /// The computed or effect currently being executed (for dependency tracking)
+    observer: Option<Rc<ReactiveNode>>,
+    /// The scope that newly created effects, computeds and cleanups belong to
+    owner: Option<Scope>,
+    /// Effects marked stale and waiting to run
+    pending_effects: Vec<Rc<ReactiveNode>>,
+    /// Whether pending effects are currently being flushed
+    flushing: bool,
+    /// Nesting depth of `batch` calls; effects are deferred while non-zero
+    batch_depth: usize,
+    /// Runs allowed per effect and flush before the flush is aborted
+    effect_run_limit: usize,
+    /// A signal changed since the UI was last asked to re-render
+    ui_update_pending: bool,
+    /// Number of UI re-render requests issued (for tests)
+    #[cfg(test)]
+    ui_updates: usize,
 }
 
 impl ReactiveContext {
     fn new() -> Self {
         Self {
-            current_effect: None,
-            cleanups: HashMap::new(),
+            observer: None,
+            owner: None,
+            pending_effects: Vec::new(),
+            flushing: false,
+            batch_depth: 0,
+            effect_run_limit: DEFAULT_EFFECT_RUN_LIMIT,
+            ui_update_pending: false,
+            #[cfg(test)]
+            ui_updates: 0,
         }
     }
 }
 
-/// Handle to an effect for execution
-struct EffectHandle {
-    f: Rc<dyn Fn()>,
+/// Puts part of the reactive context back when dropped
+///
+/// Restoring from a guard keeps a panicking effect or batch from leaving the
+/// runtime stuck mid-flush or tracking into a dead observer.
+struct Restore<F: FnMut(&mut ReactiveContext)>(F);
+
+impl<F: FnMut(&mut ReactiveContext)> Drop for Restore<F> {
+    fn drop(&mut self) {
+        let _ = REACTIVE_CONTEXT.try_with(|ctx| (self.0)(&mut ctx.borrow_mut()));
+    }
+}
+
+/// What a node in the reactive graph represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// A [`Signal`]
+    Signal,
+    /// A [`Computed`] or [`Memo`]
+    Computed,
+    /// An [`Effect`]
+    Effect,
+}
+
+/// Freshness of a node, ordered from fresh to stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NodeState {
+    /// The cached value is up to date
+    Clean,
+    /// A transitive source changed; sources must be checked before reuse
+    Check,
+    /// A direct source changed; the node must re-run
+    Dirty,
+}
+
+/// Type-erased node shared by signals, computeds and effects
+///
+/// Sources are held strongly (an effect keeps the computeds it reads alive),
+/// observers weakly (a signal never keeps a dropped computed or a disposed
+/// effect alive).
+struct ReactiveNode {
+    id: usize,
+    kind: NodeKind,
+    state: Cell<NodeState>,
+    /// Set once the owning scope is gone; the node keeps its last value
+    disposed: Cell<bool>,
+    /// Distance from the furthest upstream signal; effects run in height order
+    height: Cell<usize>,
+    sources: RefCell<Vec<Rc<ReactiveNode>>>,
+    /// Inline storage avoids a heap allocation for nodes with few observers
+    observers: RefCell<SmallVec<[Weak<ReactiveNode>; 4]>>,
+    /// Re-runs a computed or effect, returning whether its value changed
+    run: Option<Box<dyn Fn() -> bool>>,
+    /// Debug label shown by graph introspection
+    label: RefCell<Option<String>>,
+    /// Times a computed or effect ran, or a signal was written
+    runs: Cell<usize>,
+    /// Source whose change last made this node dirty
+    cause: Cell<Option<usize>>,
+    /// Set while the node's `run` is executing, to catch self-dependencies
+    running: Cell<bool>,
+}
+
+impl ReactiveNode {
+    fn new(kind: NodeKind, run: Option<Box<dyn Fn() -> bool>>) -> Rc<Self> {
+        let state = if kind == NodeKind::Signal {
+            NodeState::Clean
+        } else {
+            NodeState::Dirty
+        };
+        let node = Rc::new(Self {
+            id: NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed),
+            kind,
+            state: Cell::new(state),
+            disposed: Cell::new(false),
+            height: Cell::new(0),
+            sources: RefCell::new(Vec::new()),
+            observers: RefCell::new(SmallVec::new()),
+            run,
+            label: RefCell::new(None),
+            runs: Cell::new(0),
+            cause: Cell::new(None),
+            running: Cell::new(false),
+        });
+        NODE_REGISTRY.with(|nodes| nodes.borrow_mut().insert(node.id, Rc::downgrade(&node)));
+        node
+    }
+
+    /// Mark an observer dirty because `source` changed
+    fn mark_dirty_by(self: &Rc<Self>, source: usize) {
+        if self.state.get() < NodeState::Dirty {
+            self.cause.set(Some(source));
+        }
+        self.mark(NodeState::Dirty);
+    }
+
+    /// Record a read of this node by the currently running observer
+    fn track(self: &Rc<Self>) {
+        REACTIVE_CONTEXT.with(|ctx| {
+            let ctx = ctx.borrow();
+            let Some(observer) = ctx.observer.as_ref() else {
+                return;
+            };
+            if observer.id == self.id {
+                return;
+            }
+            let mut sources = observer.sources.borrow_mut();
+            if sources.iter().any(|source| source.id == self.id) {
+                return;
+            }
+            sources.push(self.clone());
+            self.observers.borrow_mut().push(Rc::downgrade(observer));
+            observer
+                .height
+                .set(observer.height.get().max(self.height.get() + 1));
+        });
+    }
+
+    /// Live observers, dropping links to nodes that no longer exist
+    fn live_observers(&self) -> Vec<Rc<ReactiveNode>> {
+        let mut observers = self.observers.borrow_mut();
+        observers.retain(|observer| observer.strong_count() > 0);
+        observers.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Raise this node's staleness and propagate "check" downstream
+    fn mark(self: &Rc<Self>, state: NodeState) {
+        if self.disposed.get() || self.state.get() >= state {
+            return;
+        }
+        if self.state.get() == NodeState::Clean && self.kind == NodeKind::Effect {
+            REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().pending_effects.push(self.clone()));
+        }
+        self.state.set(state);
+        for observer in self.live_observers() {
+            observer.mark(NodeState::Check);
+        }
+    }
+
+    /// Bring this node up to date, re-running it only if a source really changed
+    fn update_if_necessary(self: &Rc<Self>) {
+        if self.state.get() == NodeState::Check {
+            let sources = self.sources.borrow().clone();
+            for source in sources {
+                source.update_if_necessary();
+                if self.state.get() == NodeState::Dirty {
+                    break;
+                }
+            }
+        }
+        if self.state.get() == NodeState::Dirty {
+            self.update();
+        }
+        self.state.set(NodeState::Clean);
+    }
+
+    /// Re-run the node, re-tracking its sources from scratch
+    fn update(self: &Rc<Self>) {
+        let Some(run) = self.run.as_ref() else {
+            return;
+        };
+        if self.running.get() {
+            // Reading itself while running; re-entering would never terminate
+            report_error(ReactiveError::Cycle {
+                source: self.info(),
+                observer: self.info(),
+            });
+            return;
+        }
+
+        self.runs.set(self.runs.get() + 1);
+        if self.kind == NodeKind::Effect && self.runs.get() > 1 {
+            report_rerun(self);
+        }
+
+        // Forget the dependency edges of the previous run
+        self.unlink_sources();
+        self.height.set(0);
+
+        let mut prev = REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().observer.replace(self.clone()));
+        self.running.set(true);
+        let restore = Restore(|ctx| {
+            self.running.set(false);
+            self.state.set(NodeState::Clean);
+            ctx.observer = prev.take();
+        });
+        let changed = run();
+        drop(restore);
+
+        // A disposed node may still be read once, but must not stay subscribed
+        if self.disposed.get() {
+            self.unlink_sources();
+        }
+
+        self.state.set(NodeState::Clean);
+        if changed {
+            for observer in self.live_observers() {
+                observer.cause.set(Some(self.id));
+                observer.state.set(NodeState::Dirty);
+            }
+        }
+    }
+
+    /// Detach the node from the graph for good
+    fn dispose(&self) {
+        self.disposed.set(true);
+        self.unlink_sources();
+    }
+
+    /// Remove this node from the observer lists of all its sources
+    fn unlink_sources(&self) {
+        for source in self.sources.take() {
+            source
+                .observers
+                .borrow_mut()
+                .retain(|observer| observer.upgrade().is_some_and(|o| o.id != self.id));
+        }
+    }
+
+    fn set_label(&self, label: String) {
+        *self.label.borrow_mut() = Some(label);
+    }
+
+    fn info(&self) -> GraphNode {
+        GraphNode {
+            id: self.id,
+            kind: self.kind,
+            label: self.label.borrow().clone(),
+            runs: self.runs.get(),
+            height: self.height.get(),
+            sources: self
+                .sources
+                .borrow()
+                .iter()
+                .map(|source| source.id)
+                .collect(),
+        }
+    }
+}
+
+impl Drop for ReactiveNode {
+    fn drop(&mut self) {
+        // The thread-local may already be gone while the thread shuts down
+        let _ = NODE_REGISTRY.try_with(|nodes| {
+            if let Ok(mut nodes) = nodes.try_borrow_mut() {
+                nodes.remove(&self.id);
+            }
+        });
+    }
+}
+
+/// Look up a live node by id
+fn find_node(id: usize) -> Option<Rc<ReactiveNode>> {
+    NODE_REGISTRY.with(|nodes| nodes.borrow().get(&id).and_then(Weak::upgrade))
+}
+
+/// Tell the rerun hook, if any, which change made an effect run again
+fn report_rerun(effect: &ReactiveNode) {
+    let Some(hook) = reactive_graph::rerun_hook() else {
+        return;
+    };
+
+    // Follow the causes upstream until reaching the signal that was written
+    let mut chain = Vec::new();
+    let mut next = effect.cause.get();
+    while let Some(node) = next.and_then(find_node) {
+        if chain.iter().any(|cause: &GraphNode| cause.id == node.id) {
+            break;
+        }
+        next = match node.kind {
+            NodeKind::Signal => None,
+            _ => node.cause.get(),
+        };
+        chain.push(node.info());
+    }
+
+    hook(&RerunReason {
+        effect: effect.info(),
+        chain,
+    });
+}
+
+/// Describe every live node of this thread's reactive graph, ordered by id
+pub(crate) fn graph_nodes() -> Vec<GraphNode> {
+    let live: Vec<Rc<ReactiveNode>> = NODE_REGISTRY.with(|nodes| {
+        nodes
+            .borrow()
+            .values()
+            .filter_map(Weak::upgrade)
+            .filter(|node| !node.disposed.get())
+            .collect()
+    });
+    let mut infos: Vec<GraphNode> = live.iter().map(|node| node.info()).collect();
+    infos.sort_by_key(|node| node.id);
+    infos
+}
+
+/// Attach a debug label to the node with the given id
+pub(crate) fn label_node(id: usize, label: String) -> bool {
+    find_node(id).map(|node| node.set_label(label)).is_some()
+}
+
+/// Run queued effects until none are left, lowest height first, then
+/// request a single UI re-render for everything that changed
+fn flush_effects() {
+    let blocked = REACTIVE_CONTEXT.with(|ctx| {
+        let mut ctx = ctx.borrow_mut();
+        ctx.batch_depth > 0 || std::mem::replace(&mut ctx.flushing, true)
+    });
+    if blocked {
+        return;
+    }
+    // Effects still queued when one panics run on the next flush
+    let queue = RefCell::new(Vec::new().into_iter());
+    let flushing = Restore(|ctx| {
+        ctx.flushing = false;
+        ctx.pending_effects.extend(queue.borrow_mut().by_ref());
+    });
+
+    // Effects that keep re-triggering each other are stopped after a while
+    let limit = REACTIVE_CONTEXT.with(|ctx| ctx.borrow().effect_run_limit);
+    let mut runs: HashMap<usize, (Rc<ReactiveNode>, usize)> = HashMap::new();
+    'flush: loop {
+        let mut pending =
+            REACTIVE_CONTEXT.with(|ctx| std::mem::take(&mut ctx.borrow_mut().pending_effects));
+        if pending.is_empty() {
+            break;
+        }
+        pending.sort_by_key(|effect| effect.height.get());
+        *queue.borrow_mut() = pending.into_iter();
+        loop {
+            let Some(effect) = queue.borrow_mut().next() else {
+                break;
+            };
+            if effect.disposed.get() {
+                continue;
+            }
+            let count = runs.get(&effect.id).map_or(0, |(_, count)| *count);
+            if count >= limit {
+                let rest = queue.take();
+                abort_flush(std::iter::once(effect).chain(rest), &runs, limit);
+                break 'flush;
+            }
+            let before = effect.runs.get();
+            effect.update_if_necessary();
+            if effect.runs.get() != before {
+                runs.insert(effect.id, (effect, count + 1));
+            }
+        }
+    }
+
+    drop(flushing);
+    let ui_update =
+        REACTIVE_CONTEXT.with(|ctx| std::mem::take(&mut ctx.borrow_mut().ui_update_pending));
+    if ui_update {
+        request_ui_update();
+    }
+}
+
+/// Stop a runaway flush, leaving every queued effect ready for the next change
+fn abort_flush(
+    queue: impl Iterator<Item = Rc<ReactiveNode>>,
+    runs: &HashMap<usize, (Rc<ReactiveNode>, usize)>,
+    limit: usize,
+) {
+    let mut stuck: Vec<Rc<ReactiveNode>> = queue.collect();
+    stuck
+        .extend(REACTIVE_CONTEXT.with(|ctx| std::mem::take(&mut ctx.borrow_mut().pending_effects)));
+    for effect in &stuck {
+        // Settle computeds so they are re-marked by later changes
+        let sources = effect.sources.borrow().clone();
+        for source in sources {
+            source.update_if_necessary();
+        }
+    }
+    stuck
+        .extend(REACTIVE_CONTEXT.with(|ctx| std::mem::take(&mut ctx.borrow_mut().pending_effects)));
+    for effect in &stuck {
+        effect.state.set(NodeState::Clean);
+    }
+
+    let mut involved: Vec<&(Rc<ReactiveNode>, usize)> =
+        runs.values().filter(|(_, count)| *count > 1).collect();
+    involved.sort_by_key(|(effect, count)| (std::cmp::Reverse(*count), effect.id));
+    report_error(ReactiveError::Runaway {
+        limit,
+        effects: involved.iter().map(|(effect, _)| effect.info()).collect(),
+    });
+}
+
+/// Ask the platform app to re-render
+fn request_ui_update() {
+    #[cfg(test)]
+    REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().ui_updates += 1);
+
+    // Trigger UI re-render if we're in a WASM context; several changes
+    // before the next animation frame render once
+    #[cfg(target_arch = "wasm32")]
+    {
+        crate::app_reactive::trigger_rerender();
+    }
+
+    // Trigger UI re-render for desktop apps
+    #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
+    {
+        crate::desktop_app_context::trigger_repaint();
+    }
+}
+
+/// Problem detected by the reactive runtime
+///
+/// Errors are passed to the handler installed with [`on_reactive_error`]
+/// (logged by default) and the runtime carries on; nodes are identified by
+/// id and debug label as in [`reactive_graph`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum ReactiveError {
+    /// A computed or effect wrote a signal it read in the same run, or a
+    /// computed read itself; the observer is not re-run for that write
+    Cycle {
+        source: GraphNode,
+        observer: GraphNode,
+    },
+    /// Effects kept triggering each other while flushing a single change;
+    /// the flush was stopped after `limit` runs of one effect
+    Runaway {
+        limit: usize,
+        /// Effects that ran more than once, most runs first
+        effects: Vec<GraphNode>,
+    },
+    /// A signal was written while being read by reference; the write was
+    /// dropped
+    ReentrantWrite { signal: GraphNode },
+}
+
+impl std::fmt::Display for ReactiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReactiveError::Cycle { source, observer } if source.id == observer.id => {
+                write!(f, "reactive cycle: {} reads itself", observer)
+            }
+            ReactiveError::Cycle { source, observer } => {
+                write!(
+                    f,
+                    "reactive cycle: {} writes {} which it reads",
+                    observer, source
+                )
+            }
+            ReactiveError::Runaway { limit, effects } => {
+                write!(f, "runaway effects stopped after {} runs:", limit)?;
+                for effect in effects {
+                    write!(f, " {}", effect)?;
+                }
+                Ok(())
+            }
+            ReactiveError::ReentrantWrite { signal } => {
+                write!(f, "{} was written while being read; write dropped", signal)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReactiveError {}
+
+/// Handle reactive runtime errors with `handler` instead of logging them
+pub fn on_reactive_error(handler: impl Fn(&ReactiveError) + 'static) {
+    ERROR_HANDLER.with(|slot| *slot.borrow_mut() = Some(Rc::new(handler)));
+}
+
+/// Restore the default handler, which logs errors to the console or stderr
+pub fn clear_reactive_error_handler() {
+    ERROR_HANDLER.with(|slot| *slot.borrow_mut() = None);
+}
+
+/// Set how often one effect may run while flushing a single change
+pub fn set_effect_run_limit(limit: usize) {
+    REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().effect_run_limit = limit.max(1));
+}
+
+fn report_error(error: ReactiveError) {
+    let handler = ERROR_HANDLER.with(|slot| slot.borrow().clone());
+    match handler {
+        Some(handler) => handler(&error),
+        None => {
+            #[cfg(target_arch = "wasm32")]
+            crate::component_runtime::console_error(&error.to_string());
+            #[cfg(not(target_arch = "wasm32"))]
+            eprintln!("{}", error);
+        }
+    }
+}
+
+/// Run `f` with all signal writes batched together
+///
+/// Effects triggered inside the batch are deferred until the outermost batch
+/// returns; each runs at most once and the UI re-renders once.
+pub fn batch<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().batch_depth += 1);
+    let depth = Restore(|ctx| ctx.batch_depth -= 1);
+    let result = f();
+    drop(depth);
+    flush_effects();
+    result
+}
+
+/// Run `f` without tracking any reads as dependencies of the current observer
+pub fn untrack<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let mut prev = REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().observer.take());
+    let _restore = Restore(|ctx| ctx.observer = prev.take());
+    f()
 }
 
 /// Core reactive primitive - a value that notifies subscribers when it changes
 #[derive(Clone)]
 pub struct Signal<T: Clone> {
-    id: SignalId,
     value: Rc<RefCell<T>>,
-    subscribers: Rc<RefCell<HashSet<EffectId>>>,
+    node: Rc<ReactiveNode>,
+    /// Skips notification when a write leaves the value equal
+    eq: Option<EqFn<T>>,
 }
 
 impl<T: Clone> Signal<T> {
     /// Create a new signal with an initial value
     pub fn new(value: T) -> Self {
-        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
         Self {
-            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             value: Rc::new(RefCell::new(value)),
-            subscribers: Rc::new(RefCell::new(HashSet::new())),
+            node: ReactiveNode::new(NodeKind::Signal, None),
+            eq: None,
+        }
+    }
+
+    /// Create a signal that ignores writes equal to the current value
+    pub fn new_eq(value: T) -> Self
+    where
+        T: PartialEq + 'static,
+    {
+        Self::with_eq(value, T::eq)
+    }
+
+    /// Create a signal that ignores writes `eq` considers unchanged
+    pub fn with_eq(value: T, eq: impl Fn(&T, &T) -> bool + 'static) -> Self {
+        Self {
+            eq: Some(Rc::new(eq)),
+            ..Self::new(value)
         }
     }
 
     /// Get the current value (tracks dependency in reactive context)
     pub fn get(&self) -> T {
         // Track this read in the current reactive context
-        REACTIVE_CONTEXT.with(|ctx| {
-            let ctx = ctx.borrow();
-            if let Some(effect_id) = ctx.current_effect {
-                self.subscribers.borrow_mut().insert(effect_id);
-            }
-        });
+        self.node.track();
 
         self.value.borrow().clone()
     }
@@ -101,102 +699,235 @@ impl<T: Clone> Signal<T> {
         self.value.borrow().clone()
     }
 
+    /// Read the value by reference without cloning it (tracks dependency)
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.node.track();
+        f(&self.value.borrow())
+    }
+
+    /// Read the value by reference without cloning or tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.value.borrow())
+    }
+
     /// Set a new value and notify subscribers
+    ///
+    /// Signals created with [`Signal::new_eq`] or [`Signal::with_eq`] skip the
+    /// notification when the new value equals the current one.
     pub fn set(&self, value: T) {
-        *self.value.borrow_mut() = value;
-        self.notify();
+        let unchanged = self.eq.as_ref().is_some_and(|eq| {
+            self.value
+                .try_borrow()
+                .is_ok_and(|current| eq(&current, &value))
+        });
+        if unchanged {
+            return;
+        }
+        if self.write(|current| *current = value) {
+            self.notify();
+        }
+    }
+
+    /// Set a new value, notifying subscribers only if it differs from the
+    /// current one. Returns whether the value changed.
+    pub fn set_if_changed(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if self
+            .value
+            .try_borrow()
+            .is_ok_and(|current| *current == value)
+        {
+            return false;
+        }
+        let written = self.write(|current| *current = value);
+        if written {
+            self.notify();
+        }
+        written
     }
 
     /// Update the value using a function and notify subscribers
+    ///
+    /// With a comparator configured, the old value is kept aside and
+    /// subscribers are only notified if the update changed it.
     pub fn update<F>(&self, f: F)
     where
         F: FnOnce(&mut T),
     {
-        f(&mut self.value.borrow_mut());
+        let old = self
+            .eq
+            .as_ref()
+            .and_then(|_| self.value.try_borrow().ok().map(|value| value.clone()));
+        if !self.write(f) {
+            return;
+        }
+        if let (Some(eq), Some(old)) = (self.eq.as_ref(), old) {
+            if eq(&old, &self.value.borrow()) {
+                return;
+            }
+        }
         self.notify();
     }
 
+    /// Apply a write, refusing it while the value is borrowed by a read
+    fn write(&self, f: impl FnOnce(&mut T)) -> bool {
+        match self.value.try_borrow_mut() {
+            Ok(mut value) => {
+                f(&mut value);
+                true
+            }
+            Err(_) => {
+                self.report_reentrant_write();
+                false
+            }
+        }
+    }
+
+    /// Report a write to data this signal tracks made while it was being read
+    pub(crate) fn report_reentrant_write(&self) {
+        report_error(ReactiveError::ReentrantWrite {
+            signal: self.node.info(),
+        });
+    }
+
     /// Notify all subscribers that the value has changed
     fn notify(&self) {
-        let subscribers = self.subscribers.borrow().clone();
-        for effect_id in subscribers {
-            EFFECT_REGISTRY.with(|registry| {
-                if let Some(effect) = registry.borrow().get(&effect_id) {
-                    (effect.f)();
-                }
-            });
+        self.node.runs.set(self.node.runs.get() + 1);
+        let observers = self.node.live_observers();
+
+        // The running observer read this signal and now writes it
+        let current = REACTIVE_CONTEXT.with(|ctx| ctx.borrow().observer.clone());
+        if let Some(current) = current {
+            if observers.iter().any(|observer| observer.id == current.id) {
+                report_error(ReactiveError::Cycle {
+                    source: self.node.info(),
+                    observer: current.info(),
+                });
+            }
         }
 
-        // Trigger UI re-render if we're in a WASM context
-        #[cfg(target_arch = "wasm32")]
-        {
-            crate::app_reactive::trigger_rerender();
+        for observer in observers {
+            observer.mark_dirty_by(self.node.id);
         }
+        REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().ui_update_pending = true);
+        flush_effects();
+    }
 
-        // Trigger UI re-render for desktop apps
-        #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
-        {
-            crate::desktop_app_context::trigger_repaint();
-        }
+    /// Attach a debug label shown by [`reactive_graph`] introspection
+    pub fn labeled(self, label: impl Into<String>) -> Self {
+        self.node.set_label(label.into());
+        self
     }
 
     /// Get the signal's unique ID
     pub fn id(&self) -> SignalId {
-        self.id
+        self.node.id
     }
 }
 
 impl<T: Clone + std::fmt::Debug> std::fmt::Debug for Signal<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Signal")
-            .field("id", &self.id)
+            .field("id", &self.node.id)
             .field("value", &self.get_untracked())
             .finish()
     }
 }
 
-/// Computed signal - a derived value that automatically updates when dependencies change
+/// Signal of a `Copy` value
+///
+/// Reads of `Copy` values never allocate, so this is the regular [`Signal`];
+/// the alias is kept for code written against the former optimized module.
+pub type CopySignal<T> = Signal<T>;
+
+/// Computed signal - a lazily evaluated, memoized derived value
+///
+/// The compute function runs on first read and afterwards only when one of
+/// the values it read has changed and the computed is read again (directly
+/// or through an effect).
 #[derive(Clone)]
 pub struct Computed<T: Clone> {
-    signal: Signal<T>,
-    _effect_id: EffectId,
+    value: Rc<RefCell<Option<T>>>,
+    node: Rc<ReactiveNode>,
 }
 
 impl<T: Clone + 'static> Computed<T> {
     /// Create a new computed value
+    ///
+    /// Every recomputation counts as a change for downstream observers; use
+    /// [`Memo`] to stop propagation when the result is equal.
     pub fn new<F>(compute: F) -> Self
     where
         F: Fn() -> T + 'static,
     {
-        let compute = Rc::new(compute);
-
-        // Compute initial value
-        let initial_value = compute();
-        let signal = Signal::new(initial_value);
+        Self::build(compute, None)
+    }
 
-        // Create effect that updates signal when dependencies change
-        let signal_clone = signal.clone();
-        let compute_clone = compute.clone();
-        let effect_id = Effect::new(move || {
-            let new_value = compute_clone();
-            // Use direct assignment to avoid infinite loops
-            *signal_clone.value.borrow_mut() = new_value;
-        });
+    fn build<F>(compute: F, eq: Option<EqFn<T>>) -> Self
+    where
+        F: Fn() -> T + 'static,
+    {
+        let value = Rc::new(RefCell::new(None));
+        let slot = value.clone();
+        let node = ReactiveNode::new(
+            NodeKind::Computed,
+            Some(Box::new(move || {
+                let new_value = compute();
+                let mut slot = slot.borrow_mut();
+                let unchanged = match (eq.as_ref(), slot.as_ref()) {
+                    (Some(eq), Some(old)) => eq(old, &new_value),
+                    _ => false,
+                };
+                if !unchanged {
+                    *slot = Some(new_value);
+                }
+                !unchanged
+            })),
+        );
+        Scope::adopt_node(&node);
 
-        Self {
-            signal,
-            _effect_id: effect_id,
-        }
+        Self { value, node }
     }
 
     /// Get the current computed value (tracks dependency)
     pub fn get(&self) -> T {
-        self.signal.get()
+        // Update before tracking so the observer sees this node's final height
+        self.node.update_if_necessary();
+        self.node.track();
+        self.get_untracked()
     }
 
     /// Get the current computed value without tracking
     pub fn get_untracked(&self) -> T {
-        self.signal.get_untracked()
+        self.node.update_if_necessary();
+        self.value
+            .borrow()
+            .clone()
+            .expect("computed value is available after update")
+    }
+
+    /// Read the computed value by reference without cloning it (tracks dependency)
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.node.update_if_necessary();
+        self.node.track();
+        f(self
+            .value
+            .borrow()
+            .as_ref()
+            .expect("computed value is available after update"))
+    }
+
+    /// Attach a debug label shown by [`reactive_graph`] introspection
+    pub fn labeled(self, label: impl Into<String>) -> Self {
+        self.node.set_label(label.into());
+        self
+    }
+
+    /// Get the computed's unique ID
+    pub fn id(&self) -> SignalId {
+        self.node.id
     }
 }
 
@@ -208,84 +939,343 @@ impl<T: Clone + std::fmt::Debug + 'static> std::fmt::Debug for Computed<T> {
     }
 }
 
+/// Memo - a [`Computed`] that only notifies observers when its value changes
+///
+/// When a recomputation yields a value equal to the previous one, downstream
+/// computeds and effects are left untouched.
+#[derive(Clone)]
+pub struct Memo<T: Clone>(Computed<T>);
+
+impl<T: Clone + 'static> Memo<T> {
+    /// Create a memo compared with `PartialEq`
+    pub fn new<F>(compute: F) -> Self
+    where
+        F: Fn() -> T + 'static,
+        T: PartialEq,
+    {
+        Self::with_eq(compute, T::eq)
+    }
+
+    /// Create a memo with a custom comparator
+    pub fn with_eq<F, E>(compute: F, eq: E) -> Self
+    where
+        F: Fn() -> T + 'static,
+        E: Fn(&T, &T) -> bool + 'static,
+    {
+        Self(Computed::build(compute, Some(Rc::new(eq))))
+    }
+
+    /// Attach a debug label shown by [`reactive_graph`] introspection
+    pub fn labeled(self, label: impl Into<String>) -> Self {
+        Self(self.0.labeled(label))
+    }
+}
+
+impl<T: Clone> std::ops::Deref for Memo<T> {
+    type Target = Computed<T>;
+
+    fn deref(&self) -> &Computed<T> {
+        &self.0
+    }
+}
+
+impl<T: Clone> From<Memo<T>> for Computed<T> {
+    fn from(memo: Memo<T>) -> Self {
+        memo.0
+    }
+}
+
+impl<T: Clone + std::fmt::Debug + 'static> std::fmt::Debug for Memo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memo")
+            .field("value", &self.get_untracked())
+            .finish()
+    }
+}
+
 /// Effect - a side effect that runs when its dependencies change
+///
+/// Every run happens inside the effect's own [`Scope`]: effects, computeds and
+/// cleanups created by the previous run are disposed before the next one.
 pub struct Effect {
     _id: EffectId,
 }
 
 impl Effect {
-    /// Create a new effect
+    /// Create a new effect owned by the current scope
     #[allow(clippy::new_ret_no_self)]
     pub fn new<F>(f: F) -> EffectId
     where
         F: Fn() + 'static,
     {
-        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
-        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
-        let f: Rc<dyn Fn()> = Rc::new(f);
+        Self::build(None, f)
+    }
+
+    /// Create an effect with a debug label shown by [`reactive_graph`]
+    /// introspection
+    pub fn new_labeled<F>(label: impl Into<String>, f: F) -> EffectId
+    where
+        F: Fn() + 'static,
+    {
+        Self::build(Some(label.into()), f)
+    }
+
+    fn build<F>(label: Option<String>, f: F) -> EffectId
+    where
+        F: Fn() + 'static,
+    {
+        let scope = Scope::new(Scope::current());
+        let run_scope = scope.clone();
+        let node = ReactiveNode::new(
+            NodeKind::Effect,
+            Some(Box::new(move || {
+                run_scope.clean();
+                run_scope.run(&f);
+                false
+            })),
+        );
+        let id = node.id;
+        if let Some(label) = label {
+            node.set_label(label);
+        }
+
+        // Register effect
+        EFFECT_REGISTRY.with(|registry| {
+            registry.borrow_mut().insert(
+                id,
+                EffectHandle {
+                    node: node.clone(),
+                    scope: scope.clone(),
+                },
+            );
+        });
+        if let Some(parent) = scope.parent() {
+            parent.inner.effects.borrow_mut().push(id);
+        }
+
+        // Run effect once to establish dependencies
+        node.update();
+
+        id
+    }
+
+    /// Dispose of an effect (stop tracking and run cleanup)
+    pub fn dispose(id: EffectId) {
+        // Remove from registry and unsubscribe from all sources
+        let handle = EFFECT_REGISTRY.with(|registry| registry.borrow_mut().remove(&id));
+        if let Some(handle) = handle {
+            handle.node.dispose();
+            // Dispose everything the effect created and run its cleanups
+            handle.scope.dispose();
+        }
+    }
+}
+
+/// Shared state of a [`Scope`]
+struct ScopeInner {
+    parent: Option<Weak<ScopeInner>>,
+    children: RefCell<Vec<Scope>>,
+    effects: RefCell<Vec<EffectId>>,
+    nodes: RefCell<Vec<Rc<ReactiveNode>>>,
+    cleanups: RefCell<Vec<Box<dyn FnOnce()>>>,
+    /// Values provided to this scope and its descendants, by type
+    contexts: RefCell<HashMap<TypeId, Rc<dyn Any>>>,
+    disposed: Cell<bool>,
+}
+
+/// Reactive ownership scope
+///
+/// Effects, computeds, nested scopes and [`on_cleanup`] callbacks created
+/// while a scope is current belong to it and are disposed together with it.
+#[derive(Clone)]
+pub struct Scope {
+    inner: Rc<ScopeInner>,
+}
+
+impl Scope {
+    /// Create a scope, attached to `parent` if given
+    fn new(parent: Option<Scope>) -> Self {
+        let scope = Self {
+            inner: Rc::new(ScopeInner {
+                parent: parent.as_ref().map(|p| Rc::downgrade(&p.inner)),
+                children: RefCell::new(Vec::new()),
+                effects: RefCell::new(Vec::new()),
+                nodes: RefCell::new(Vec::new()),
+                cleanups: RefCell::new(Vec::new()),
+                contexts: RefCell::new(HashMap::new()),
+                disposed: Cell::new(false),
+            }),
+        };
+        if let Some(parent) = parent {
+            parent.inner.children.borrow_mut().push(scope.clone());
+        }
+        scope
+    }
 
-        // Register effect
-        EFFECT_REGISTRY.with(|registry| {
-            registry
-                .borrow_mut()
-                .insert(id, EffectHandle { f: f.clone() });
-        });
+    /// The scope currently collecting effects, computeds and cleanups
+    pub fn current() -> Option<Scope> {
+        REACTIVE_CONTEXT.with(|ctx| ctx.borrow().owner.clone())
+    }
 
-        // Run effect once to establish dependencies
-        Self::run_effect(id, f.clone());
+    /// Create a child scope of this scope
+    pub fn child(&self) -> Scope {
+        Scope::new(Some(self.clone()))
+    }
 
-        id
+    /// Run `f` with this scope as the current owner
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        let mut prev = REACTIVE_CONTEXT.with(|ctx| ctx.borrow_mut().owner.replace(self.clone()));
+        let _restore = Restore(|ctx| ctx.owner = prev.take());
+        f()
     }
 
-    /// Run an effect with the given ID as the current reactive context
-    fn run_effect(id: EffectId, f: Rc<dyn Fn()>) {
-        REACTIVE_CONTEXT.with(|ctx| {
-            // Save previous effect
-            let prev_effect = ctx.borrow().current_effect;
+    /// Dispose the scope and everything it owns
+    pub fn dispose(&self) {
+        if self.inner.disposed.replace(true) {
+            return;
+        }
+        self.clean();
+        if let Some(parent) = self.parent() {
+            parent
+                .inner
+                .children
+                .borrow_mut()
+                .retain(|child| !Rc::ptr_eq(&child.inner, &self.inner));
+        }
+    }
 
-            // Set current effect
-            ctx.borrow_mut().current_effect = Some(id);
+    /// Replace the contexts of this scope with those provided in `other`
+    pub(crate) fn inherit_contexts(&self, other: &Scope) {
+        let contexts = other.inner.contexts.borrow().clone();
+        *self.inner.contexts.borrow_mut() = contexts;
+    }
 
-            // Run the effect
-            f();
+    /// Whether [`Scope::dispose`] has been called
+    pub fn is_disposed(&self) -> bool {
+        self.inner.disposed.get()
+    }
 
-            // Restore previous effect
-            ctx.borrow_mut().current_effect = prev_effect;
-        });
+    fn parent(&self) -> Option<Scope> {
+        self.inner
+            .parent
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .map(|inner| Scope { inner })
     }
 
-    /// Dispose of an effect (stop tracking and run cleanup)
-    pub fn dispose(id: EffectId) {
-        // Run cleanup functions
-        REACTIVE_CONTEXT.with(|ctx| {
-            if let Some(cleanups) = ctx.borrow_mut().cleanups.remove(&id) {
-                for cleanup in cleanups {
-                    cleanup();
-                }
-            }
-        });
+    /// Dispose everything owned so far, leaving the scope itself usable
+    fn clean(&self) {
+        for child in self.inner.children.take() {
+            child.dispose();
+        }
+        for effect_id in self.inner.effects.take() {
+            Effect::dispose(effect_id);
+        }
+        for node in self.inner.nodes.take() {
+            node.dispose();
+        }
+        // Cleanups run in reverse registration order
+        let cleanups = self.inner.cleanups.take();
+        for cleanup in cleanups.into_iter().rev() {
+            cleanup();
+        }
+        self.inner.contexts.take();
+    }
 
-        // Remove from registry
-        EFFECT_REGISTRY.with(|registry| {
-            registry.borrow_mut().remove(&id);
-        });
+    /// Attach a computed node to the current scope
+    fn adopt_node(node: &Rc<ReactiveNode>) {
+        if let Some(owner) = Scope::current() {
+            owner.inner.nodes.borrow_mut().push(node.clone());
+        }
     }
 }
 
-/// Register a cleanup function to run when the current effect is disposed
+/// Create a detached root scope and run `f` inside it
+///
+/// The root is not owned by the current scope; it lives until the [`Scope`]
+/// passed to `f` is disposed.
+///
+/// ```rust
+/// use windjammer_ui::reactivity::*;
+///
+/// let count = Signal::new(0);
+/// let dispose = create_root(|dispose| {
+///     let count = count.clone();
+///     create_effect(move || println!("count = {}", count.get()));
+///     dispose
+/// });
+///
+/// dispose.dispose(); // The effect no longer runs
+/// count.set(1);
+/// ```
+pub fn create_root<R>(f: impl FnOnce(Scope) -> R) -> R {
+    let root = Scope::new(None);
+    root.run(|| f(root.clone()))
+}
+
+/// Create a child of the current scope and run `f` inside it
+pub fn create_scope<R>(f: impl FnOnce(Scope) -> R) -> R {
+    let scope = Scope::new(Scope::current());
+    scope.run(|| f(scope.clone()))
+}
+
+/// Register a cleanup function with the current scope
+///
+/// Inside an effect it runs before the effect re-runs and when the effect is
+/// disposed; inside a scope it runs when the scope is disposed.
 pub fn on_cleanup<F>(cleanup: F)
 where
-    F: Fn() + 'static,
+    F: FnOnce() + 'static,
 {
-    REACTIVE_CONTEXT.with(|ctx| {
-        let mut ctx = ctx.borrow_mut();
-        if let Some(effect_id) = ctx.current_effect {
-            ctx.cleanups
-                .entry(effect_id)
-                .or_insert_with(Vec::new)
-                .push(Box::new(cleanup));
+    if let Some(owner) = Scope::current() {
+        owner.inner.cleanups.borrow_mut().push(Box::new(cleanup));
+    }
+}
+
+/// Make `value` available to [`use_context`] in the current scope and all
+/// scopes created inside it
+///
+/// A value provided again for the same type replaces the previous one in this
+/// scope and shadows values provided further up. Outside any scope the value
+/// is provided to the whole thread.
+pub fn provide_context<T: Clone + 'static>(value: T) {
+    let value: Rc<dyn Any> = Rc::new(value);
+    match Scope::current() {
+        Some(owner) => {
+            owner
+                .inner
+                .contexts
+                .borrow_mut()
+                .insert(TypeId::of::<T>(), value);
         }
-    });
+        None => ROOT_CONTEXTS.with(|contexts| {
+            contexts.borrow_mut().insert(TypeId::of::<T>(), value);
+        }),
+    }
+}
+
+/// Get the nearest value of type `T` provided by the current scope or one of
+/// its ancestors
+pub fn use_context<T: Clone + 'static>() -> Option<T> {
+    let mut scope = Scope::current();
+    while let Some(current) = scope {
+        let value = current
+            .inner
+            .contexts
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .cloned();
+        if let Some(value) = value {
+            return value.downcast_ref::<T>().cloned();
+        }
+        scope = current.parent();
+    }
+    ROOT_CONTEXTS.with(|contexts| {
+        contexts
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>().cloned())
+    })
 }
 
 /// Create a reactive scope that tracks dependencies
@@ -305,6 +1295,15 @@ where
     Computed::new(compute)
 }
 
+/// Create a memo (a computed value that skips unchanged results)
+pub fn create_memo<T, F>(compute: F) -> Memo<T>
+where
+    T: Clone + PartialEq + 'static,
+    F: Fn() -> T + 'static,
+{
+    Memo::new(compute)
+}
+
 /// Create a signal
 pub fn create_signal<T: Clone>(value: T) -> Signal<T> {
     Signal::new(value)
@@ -438,4 +1437,674 @@ mod tests {
         // Note: quadrupled won't update automatically because it doesn't depend on a signal
         // This is expected behavior - effects only track signal reads
     }
+
+    #[test]
+    fn test_conditional_dependencies() {
+        let use_a = Signal::new(true);
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+        let runs = Rc::new(RefCell::new(0));
+        let result = Rc::new(RefCell::new(0));
+
+        let (use_a_c, a_c, b_c) = (use_a.clone(), a.clone(), b.clone());
+        let (runs_c, result_c) = (runs.clone(), result.clone());
+        Effect::new(move || {
+            *runs_c.borrow_mut() += 1;
+            *result_c.borrow_mut() = if use_a_c.get() { a_c.get() } else { b_c.get() };
+        });
+        assert_eq!(*runs.borrow(), 1);
+
+        // Only the active branch is tracked
+        b.set(20);
+        assert_eq!(*runs.borrow(), 1);
+
+        use_a.set(false);
+        assert_eq!(*runs.borrow(), 2);
+        assert_eq!(*result.borrow(), 20);
+
+        // The branch that is no longer read no longer triggers the effect
+        a.set(10);
+        assert_eq!(*runs.borrow(), 2);
+
+        b.set(30);
+        assert_eq!(*runs.borrow(), 3);
+        assert_eq!(*result.borrow(), 30);
+    }
+
+    #[test]
+    fn test_dependencies_re_tracked_after_update() {
+        let count = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (count_c, runs_c) = (count.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = count_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        // Each re-run records its dependencies again
+        for i in 1..=3 {
+            count.set(i);
+        }
+        assert_eq!(*runs.borrow(), 4);
+        assert_eq!(count.node.observers.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_dispose_unsubscribes() {
+        let count = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+        let cleaned = Rc::new(RefCell::new(false));
+
+        let (count_c, runs_c, cleaned_c) = (count.clone(), runs.clone(), cleaned.clone());
+        let id = Effect::new(move || {
+            let _ = count_c.get();
+            *runs_c.borrow_mut() += 1;
+            let cleaned_c = cleaned_c.clone();
+            on_cleanup(move || *cleaned_c.borrow_mut() = true);
+        });
+        assert_eq!(count.node.observers.borrow().len(), 1);
+
+        Effect::dispose(id);
+        assert!(*cleaned.borrow());
+        assert!(count.node.observers.borrow().is_empty());
+
+        count.set(1);
+        assert_eq!(*runs.borrow(), 1);
+    }
+
+    #[test]
+    fn test_computed_is_lazy_and_memoized() {
+        let count = Signal::new(1);
+        let computes = Rc::new(RefCell::new(0));
+
+        let (count_c, computes_c) = (count.clone(), computes.clone());
+        let doubled = Computed::new(move || {
+            *computes_c.borrow_mut() += 1;
+            count_c.get() * 2
+        });
+        assert_eq!(*computes.borrow(), 0);
+
+        assert_eq!(doubled.get(), 2);
+        assert_eq!(doubled.get(), 2);
+        assert_eq!(*computes.borrow(), 1);
+
+        // Writes only mark the computed; it recomputes on the next read
+        count.set(2);
+        count.set(3);
+        assert_eq!(*computes.borrow(), 1);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(*computes.borrow(), 2);
+    }
+
+    #[test]
+    fn test_computed_chain() {
+        let base = Signal::new(1);
+        let base_c = base.clone();
+        let doubled = Computed::new(move || base_c.get() * 2);
+        let doubled_c = doubled.clone();
+        let quadrupled = Computed::new(move || doubled_c.get() * 2);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (quadrupled_c, seen_c) = (quadrupled.clone(), seen.clone());
+        Effect::new(move || seen_c.borrow_mut().push(quadrupled_c.get()));
+
+        base.set(2);
+        base.set(3);
+        assert_eq!(*seen.borrow(), vec![4, 8, 12]);
+    }
+
+    #[test]
+    fn test_diamond_is_glitch_free() {
+        let a = Signal::new(1);
+        let runs_d = Rc::new(RefCell::new(0));
+
+        let a_b = a.clone();
+        let b = Computed::new(move || a_b.get() + 1);
+        let a_c = a.clone();
+        let c = Computed::new(move || a_c.get() * 10);
+
+        let (b_d, c_d, runs_d_c) = (b.clone(), c.clone(), runs_d.clone());
+        let d = Computed::new(move || {
+            *runs_d_c.borrow_mut() += 1;
+            b_d.get() + c_d.get()
+        });
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (d_e, seen_c) = (d.clone(), seen.clone());
+        Effect::new(move || seen_c.borrow_mut().push(d_e.get()));
+        assert_eq!(*seen.borrow(), vec![12]);
+        assert_eq!(*runs_d.borrow(), 1);
+
+        // d recomputes once and the effect never sees (b, c) out of sync
+        a.set(2);
+        assert_eq!(*seen.borrow(), vec![12, 23]);
+        assert_eq!(*runs_d.borrow(), 2);
+    }
+
+    #[test]
+    fn test_effect_runs_once_per_change() {
+        let a = Signal::new(1);
+        let a_c = a.clone();
+        let doubled = Computed::new(move || a_c.get() * 2);
+        let runs = Rc::new(RefCell::new(0));
+
+        // Reads the signal directly and through a computed
+        let (a_e, doubled_e, runs_c) = (a.clone(), doubled.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = a_e.get() + doubled_e.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        a.set(2);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_effects_run_in_height_order() {
+        let a = Signal::new(0);
+        let order = Rc::new(RefCell::new(Vec::new()));
+
+        let a_c = a.clone();
+        let doubled = Computed::new(move || a_c.get() * 2);
+
+        // Created first but deeper in the graph
+        let (doubled_e, order_c) = (doubled.clone(), order.clone());
+        Effect::new(move || {
+            let _ = doubled_e.get();
+            order_c.borrow_mut().push("deep");
+        });
+        let (a_e, order_c) = (a.clone(), order.clone());
+        Effect::new(move || {
+            let _ = a_e.get();
+            order_c.borrow_mut().push("shallow");
+        });
+
+        order.borrow_mut().clear();
+        a.set(1);
+        assert_eq!(*order.borrow(), vec!["shallow", "deep"]);
+    }
+
+    #[test]
+    fn test_signal_set_inside_effect_is_queued() {
+        let source = Signal::new(1);
+        let mirror = Signal::new(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let (source_c, mirror_c) = (source.clone(), mirror.clone());
+        Effect::new(move || mirror_c.set(source_c.get()));
+        let (mirror_c, seen_c) = (mirror.clone(), seen.clone());
+        Effect::new(move || seen_c.borrow_mut().push(mirror_c.get()));
+
+        source.set(5);
+        assert_eq!(*seen.borrow(), vec![1, 5]);
+    }
+
+    fn ui_updates() -> usize {
+        REACTIVE_CONTEXT.with(|ctx| ctx.borrow().ui_updates)
+    }
+
+    #[test]
+    fn test_batch_runs_effects_once() {
+        let signals: Vec<_> = (0..5).map(Signal::new).collect();
+        let runs = Rc::new(RefCell::new(0));
+
+        let (signals_c, runs_c) = (signals.clone(), runs.clone());
+        Effect::new(move || {
+            let _: i32 = signals_c.iter().map(Signal::get).sum();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        let before = ui_updates();
+        batch(|| {
+            for signal in &signals {
+                signal.update(|v| *v += 10);
+            }
+            // Effects are deferred until the batch closes
+            assert_eq!(*runs.borrow(), 1);
+        });
+        assert_eq!(*runs.borrow(), 2);
+        assert_eq!(ui_updates() - before, 1);
+    }
+
+    #[test]
+    fn test_nested_batch_flushes_at_outermost() {
+        let count = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (count_c, runs_c) = (count.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = count_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        let result = batch(|| {
+            count.set(1);
+            batch(|| count.set(2));
+            assert_eq!(*runs.borrow(), 1);
+            count.get()
+        });
+        assert_eq!(result, 2);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_panics_do_not_leave_the_runtime_blocked() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let count = Signal::new(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let (count_c, seen_c) = (count.clone(), seen.clone());
+        Effect::new(move || {
+            let value = count_c.get();
+            if value == 1 {
+                panic!("effect failed");
+            }
+            seen_c.borrow_mut().push(value);
+        });
+
+        assert!(catch_unwind(AssertUnwindSafe(|| count.set(1))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| batch(|| panic!("batch failed")))).is_err());
+        REACTIVE_CONTEXT.with(|ctx| {
+            let ctx = ctx.borrow();
+            assert!(!ctx.flushing);
+            assert_eq!(ctx.batch_depth, 0);
+            assert!(ctx.observer.is_none());
+        });
+
+        count.set(2);
+        assert_eq!(*seen.borrow(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_unbatched_writes_rerender_each_time() {
+        let a = Signal::new(0);
+        let b = Signal::new(0);
+
+        let before = ui_updates();
+        a.set(1);
+        b.set(1);
+        assert_eq!(ui_updates() - before, 2);
+    }
+
+    #[test]
+    fn test_untrack() {
+        let tracked = Signal::new(0);
+        let ignored = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (tracked_c, ignored_c, runs_c) = (tracked.clone(), ignored.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = tracked_c.get() + untrack(|| ignored_c.get());
+            *runs_c.borrow_mut() += 1;
+        });
+
+        ignored.set(1);
+        assert_eq!(*runs.borrow(), 1);
+        tracked.set(1);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_with_reads_by_reference() {
+        let items = Signal::new(vec![1, 2, 3]);
+        let total = Rc::new(RefCell::new(0));
+
+        let (items_c, total_c) = (items.clone(), total.clone());
+        Effect::new(move || *total_c.borrow_mut() = items_c.with(|v| v.iter().sum::<i32>()));
+        assert_eq!(*total.borrow(), 6);
+
+        items.update(|v| v.push(4));
+        assert_eq!(*total.borrow(), 10);
+        assert_eq!(items.with_untracked(Vec::len), 4);
+    }
+
+    #[test]
+    fn test_copy_signal() {
+        let signal: CopySignal<i32> = CopySignal::new(42);
+        assert_eq!(signal.get(), 42);
+
+        signal.set(100);
+        assert_eq!(signal.get(), 100);
+    }
+
+    #[test]
+    fn test_root_disposes_effects() {
+        let count = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let root = create_root(|root| {
+            let (count_c, runs_c) = (count.clone(), runs.clone());
+            Effect::new(move || {
+                let _ = count_c.get();
+                *runs_c.borrow_mut() += 1;
+            });
+            root
+        });
+
+        count.set(1);
+        assert_eq!(*runs.borrow(), 2);
+
+        root.dispose();
+        assert!(root.is_disposed());
+        count.set(2);
+        assert_eq!(*runs.borrow(), 2);
+        assert!(count.node.observers.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_disposal_cascades_to_child_scopes() {
+        let count = Signal::new(0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let root = create_root(|root| {
+            let log_c = log.clone();
+            on_cleanup(move || log_c.borrow_mut().push("root"));
+
+            create_scope(|_child| {
+                let (count_c, log_c) = (count.clone(), log.clone());
+                Effect::new(move || {
+                    let _ = count_c.get();
+                    log_c.borrow_mut().push("run");
+                });
+                let log_c = log.clone();
+                on_cleanup(move || log_c.borrow_mut().push("child"));
+            });
+            root
+        });
+
+        root.dispose();
+        count.set(1);
+        assert_eq!(*log.borrow(), vec!["run", "child", "root"]);
+    }
+
+    #[test]
+    fn test_effect_disposes_nested_effects_before_rerun() {
+        let outer = Signal::new(0);
+        let inner = Signal::new(0);
+        let inner_runs = Rc::new(RefCell::new(0));
+        let cleanups = Rc::new(RefCell::new(0));
+
+        let (outer_c, inner_c) = (outer.clone(), inner.clone());
+        let (inner_runs_c, cleanups_c) = (inner_runs.clone(), cleanups.clone());
+        Effect::new(move || {
+            let _ = outer_c.get();
+            let cleanups_c = cleanups_c.clone();
+            on_cleanup(move || *cleanups_c.borrow_mut() += 1);
+
+            let (inner_c, inner_runs_c) = (inner_c.clone(), inner_runs_c.clone());
+            Effect::new(move || {
+                let _ = inner_c.get();
+                *inner_runs_c.borrow_mut() += 1;
+            });
+        });
+
+        // Each outer run replaces the nested effect instead of adding one
+        outer.set(1);
+        outer.set(2);
+        assert_eq!(*cleanups.borrow(), 2);
+        assert_eq!(inner.node.observers.borrow().len(), 1);
+
+        *inner_runs.borrow_mut() = 0;
+        inner.set(1);
+        assert_eq!(*inner_runs.borrow(), 1);
+    }
+
+    #[test]
+    fn test_disposed_computed_keeps_last_value() {
+        let count = Signal::new(1);
+        let computes = Rc::new(RefCell::new(0));
+
+        let (doubled, root) = create_root(|root| {
+            let (count_c, computes_c) = (count.clone(), computes.clone());
+            let doubled = Computed::new(move || {
+                *computes_c.borrow_mut() += 1;
+                count_c.get() * 2
+            });
+            assert_eq!(doubled.get(), 2);
+            (doubled, root)
+        });
+
+        root.dispose();
+        assert!(count.node.observers.borrow().is_empty());
+        count.set(5);
+        assert_eq!(doubled.get(), 2);
+        assert_eq!(*computes.borrow(), 1);
+    }
+
+    #[test]
+    fn test_eq_signal_skips_equal_writes() {
+        let count = Signal::new_eq(1);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (count_c, runs_c) = (count.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = count_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        let before = ui_updates();
+        count.set(1);
+        count.update(|v| *v *= 1);
+        assert_eq!(*runs.borrow(), 1);
+        assert_eq!(ui_updates(), before);
+
+        count.set(2);
+        assert_eq!(*runs.borrow(), 2);
+        assert_eq!(ui_updates() - before, 1);
+    }
+
+    #[test]
+    fn test_set_if_changed() {
+        let name = Signal::new("a".to_string());
+        let runs = Rc::new(RefCell::new(0));
+
+        let (name_c, runs_c) = (name.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = name_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        assert!(!name.set_if_changed("a".to_string()));
+        assert!(name.set_if_changed("b".to_string()));
+        assert_eq!(*runs.borrow(), 2);
+
+        // Plain `set` still notifies on every write
+        name.set("b".to_string());
+        assert_eq!(*runs.borrow(), 3);
+    }
+
+    #[test]
+    fn test_signal_custom_comparator() {
+        // Only the integer part matters
+        let value = Signal::with_eq(1.2_f64, |a, b| a.floor() == b.floor());
+        let runs = Rc::new(RefCell::new(0));
+
+        let (value_c, runs_c) = (value.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = value_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        value.set(1.7);
+        assert_eq!(*runs.borrow(), 1);
+        assert_eq!(value.get(), 1.2);
+        value.set(2.1);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_memo_stops_unchanged_propagation() {
+        let count = Signal::new(1);
+        let count_c = count.clone();
+        let is_even = Memo::new(move || count_c.get() % 2 == 0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (is_even_c, runs_c) = (is_even.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = is_even_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        count.set(3);
+        assert_eq!(*runs.borrow(), 1);
+        count.set(4);
+        assert_eq!(*runs.borrow(), 2);
+        assert!(is_even.get());
+    }
+
+    #[test]
+    fn test_memo_with_eq() {
+        let items = Signal::new(vec![3, 1, 2]);
+        let items_c = items.clone();
+        // Compare by length only
+        let sorted = Memo::with_eq(
+            move || {
+                let mut v = items_c.get();
+                v.sort();
+                v
+            },
+            |a: &Vec<i32>, b: &Vec<i32>| a.len() == b.len(),
+        );
+        let downstream = {
+            let sorted = sorted.clone();
+            Computed::new(move || sorted.get().len())
+        };
+        let runs = Rc::new(RefCell::new(0));
+
+        let (downstream_c, runs_c) = (downstream.clone(), runs.clone());
+        Effect::new(move || {
+            let _ = downstream_c.get();
+            *runs_c.borrow_mut() += 1;
+        });
+
+        items.set(vec![9, 8, 7]);
+        assert_eq!(*runs.borrow(), 1);
+        assert_eq!(sorted.get(), vec![1, 2, 3]);
+
+        items.set(vec![1]);
+        assert_eq!(*runs.borrow(), 2);
+        assert_eq!(downstream.get(), 1);
+    }
+
+    fn collect_errors() -> Rc<RefCell<Vec<ReactiveError>>> {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_c = errors.clone();
+        on_reactive_error(move |error| errors_c.borrow_mut().push(error.clone()));
+        errors
+    }
+
+    #[test]
+    fn test_effect_writing_what_it_reads_is_reported() {
+        let errors = collect_errors();
+        let count = Signal::new(0).labeled("count");
+        let runs = Rc::new(Cell::new(0));
+
+        let (count_c, runs_c) = (count.clone(), runs.clone());
+        let effect = Effect::new_labeled("clamp", move || {
+            runs_c.set(runs_c.get() + 1);
+            if count_c.get() > 10 {
+                count_c.set(10);
+            }
+        });
+
+        count.set(50);
+        assert_eq!(count.get(), 10);
+        assert_eq!(runs.get(), 2);
+
+        let errors = errors.borrow();
+        assert_eq!(errors.len(), 1);
+        let ReactiveError::Cycle { source, observer } = &errors[0] else {
+            panic!("expected a cycle, got {:?}", errors[0]);
+        };
+        assert_eq!(
+            (source.id, source.label.as_deref()),
+            (count.id(), Some("count"))
+        );
+        assert_eq!(
+            (observer.id, observer.label.as_deref()),
+            (effect, Some("clamp"))
+        );
+    }
+
+    #[test]
+    fn test_runaway_effects_are_stopped_and_reported() {
+        let errors = collect_errors();
+        set_effect_run_limit(10);
+        let ping = Signal::new(0);
+        let pong = Signal::new(0);
+
+        let (ping_c, pong_c) = (ping.clone(), pong.clone());
+        let forward = Effect::new(move || pong_c.set(ping_c.get() + 1));
+        let (ping_c, pong_c) = (ping.clone(), pong.clone());
+        let backward = Effect::new(move || ping_c.set(pong_c.get() + 1));
+
+        ping.set(100);
+        {
+            let errors = errors.borrow();
+            let runaway = errors
+                .iter()
+                .find(|error| matches!(error, ReactiveError::Runaway { .. }));
+            let Some(ReactiveError::Runaway { limit, effects }) = runaway else {
+                panic!("expected a runaway error, got {:?}", errors);
+            };
+            assert_eq!(*limit, 10);
+            let mut ids: Vec<_> = effects.iter().map(|effect| effect.id).collect();
+            ids.sort();
+            assert_eq!(ids, vec![forward, backward]);
+        }
+
+        // The runtime keeps working after the flush was stopped
+        errors.borrow_mut().clear();
+        let seen = Rc::new(Cell::new(0));
+        let (ping_c, seen_c) = (ping.clone(), seen.clone());
+        Effect::dispose(backward);
+        Effect::new(move || seen_c.set(ping_c.get()));
+        ping.set(7);
+        assert_eq!(seen.get(), 7);
+        assert_eq!(pong.get(), 8);
+        assert!(errors.borrow().is_empty());
+        set_effect_run_limit(DEFAULT_EFFECT_RUN_LIMIT);
+    }
+
+    #[test]
+    fn test_write_during_read_is_dropped_and_reported() {
+        let errors = collect_errors();
+        let name = Signal::new("a".to_string());
+
+        name.with(|current| name.set(format!("{}b", current)));
+        assert_eq!(name.get(), "a");
+        assert!(matches!(
+            &errors.borrow()[..],
+            [ReactiveError::ReentrantWrite { signal }] if signal.id == name.id()
+        ));
+    }
+
+    #[test]
+    fn test_computed_reading_itself_is_reported() {
+        let errors = collect_errors();
+        let recurse = Signal::new(false);
+        let slot: Rc<RefCell<Option<Computed<i32>>>> = Rc::new(RefCell::new(None));
+
+        let (recurse_c, slot_c) = (recurse.clone(), slot.clone());
+        let computed = Computed::new(move || {
+            if recurse_c.get() {
+                slot_c.borrow().as_ref().map_or(0, |me| me.get()) + 1
+            } else {
+                1
+            }
+        });
+        *slot.borrow_mut() = Some(computed.clone());
+
+        assert_eq!(computed.get(), 1);
+        recurse.set(true);
+        assert_eq!(computed.get(), 2);
+        assert!(matches!(
+            &errors.borrow()[..],
+            [ReactiveError::Cycle { source, observer }]
+                if source.id == computed.id() && observer.id == computed.id()
+        ));
+        slot.borrow_mut().take();
+    }
 }
@@ -0,0 +1,654 @@
+//! Expansion of component nodes through a [`ComponentRegistry`]
+//!
+//! [`Reconciler::expand`] replaces every [`VNode::Component`] in a tree with
+//! what its instance renders, so renderers only ever see elements and text.
+//! Instances are kept between expansions and matched by position (or element
+//! key) and component name:
+//!
+//! - a component seen for the first time is created by its factory and gets
+//!   [`Component::init`]
+//! - one whose props changed gets [`Component::set_props`] and
+//!   [`Component::update`]
+//! - one no longer in the tree gets [`Component::cleanup`] and its reactive
+//!   scope is disposed
+//!
+//! Each hook runs in mount order, parents before their children. Components
+//! without a registered factory are left in the tree as they are, and a
+//! [`VNode::Memo`] whose key and props are unchanged reuses its previous
+//! expansion without rendering the components inside it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::rc::Rc;
+//! use windjammer_ui::component::{Component, ComponentRegistry};
+//! use windjammer_ui::reconciler::Reconciler;
+//! use windjammer_ui::vdom::{VComponent, VElement, VNode};
+//!
+//! struct Greeting {
+//!     name: String,
+//! }
+//!
+//! impl Component for Greeting {
+//!     fn set_props(&mut self, props: &HashMap<String, String>) {
+//!         self.name = props["name"].clone();
+//!     }
+//!
+//!     fn render(&self) -> VNode {
+//!         VElement::new("p").child(format!("Hello, {}", self.name).into()).into()
+//!     }
+//! }
+//!
+//! let mut registry = ComponentRegistry::new();
+//! registry.register("Greeting", |props| Greeting {
+//!     name: props["name"].clone(),
+//! });
+//!
+//! let mut reconciler = Reconciler::new(Rc::new(registry));
+//! let view: VNode = VElement::new("main")
+//!     .child(VComponent::new("Greeting").prop("name", "Ada").into())
+//!     .into();
+//! let expanded = reconciler.expand(&view);
+//! assert_eq!(
+//!     expanded,
+//!     VElement::new("main")
+//!         .child(VElement::new("p").child("Hello, Ada".into()).into())
+//!         .into()
+//! );
+//!
+//! reconciler.unmount(); // Greeting::cleanup runs
+//! ```
+
+use crate::component::{Component, ComponentRegistry};
+use crate::reactivity::{create_scope, Scope};
+use crate::vdom::{VComponent, VElement, VMemo, VNode};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// One step of the path identifying an instance
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Slot {
+    Index(usize),
+    Key(String),
+    Component(String),
+}
+
+type InstanceId = Vec<Slot>;
+
+/// A mounted component
+struct Instance {
+    component: Box<dyn Component>,
+    props: HashMap<String, String>,
+    /// Owns everything the component creates, nested in its parent's scope
+    scope: Scope,
+    /// Child scope of the latest render, replaced on every render
+    render_scope: Option<Scope>,
+    /// Parent of the scopes of the components it renders, with the contexts
+    /// provided by the latest render
+    children: Scope,
+    /// Position in mount order
+    mounted: u64,
+}
+
+impl Instance {
+    fn render(&mut self) -> VNode {
+        if let Some(previous) = self.render_scope.take() {
+            previous.dispose();
+        }
+        let render_scope = self.scope.child();
+        let vnode = render_scope.run(|| self.component.render());
+        self.children.inherit_contexts(&render_scope);
+        self.render_scope = Some(render_scope);
+        vnode
+    }
+}
+
+/// The expansion of a memo node
+struct MemoEntry {
+    input: VMemo,
+    output: VNode,
+    /// Instances and memos inside the memo
+    instances: Vec<InstanceId>,
+    memos: Vec<InstanceId>,
+}
+
+/// Instances and memos reached by one expansion, in order
+#[derive(Default)]
+struct Visit {
+    instances: Vec<InstanceId>,
+    memos: Vec<InstanceId>,
+}
+
+/// Keeps the component instances of a tree across expansions
+pub struct Reconciler {
+    registry: Rc<ComponentRegistry>,
+    instances: HashMap<InstanceId, Instance>,
+    memos: HashMap<InstanceId, MemoEntry>,
+    next_mount: u64,
+}
+
+impl Reconciler {
+    /// Create a reconciler with no mounted components
+    pub fn new(registry: Rc<ComponentRegistry>) -> Self {
+        Self {
+            registry,
+            instances: HashMap::new(),
+            memos: HashMap::new(),
+            next_mount: 0,
+        }
+    }
+
+    /// Expand every component in `vnode`, mounting, updating and tearing
+    /// down instances to match
+    ///
+    /// New instance scopes are children of the current scope.
+    pub fn expand(&mut self, vnode: &VNode) -> VNode {
+        let mut seen = Visit::default();
+        let expanded = self.expand_node(vnode, Vec::new(), None, &mut seen);
+
+        let memos: HashSet<InstanceId> = seen.memos.into_iter().collect();
+        self.memos.retain(|id, _| memos.contains(id));
+        let instances: HashSet<InstanceId> = seen.instances.into_iter().collect();
+        self.teardown(|id| !instances.contains(id));
+        expanded
+    }
+
+    /// Tear down every mounted component
+    pub fn unmount(&mut self) {
+        self.memos.clear();
+        self.teardown(|_| true);
+    }
+
+    /// Number of mounted components
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Whether no component is mounted
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    fn expand_node(
+        &mut self,
+        vnode: &VNode,
+        id: InstanceId,
+        parent: Option<&Scope>,
+        seen: &mut Visit,
+    ) -> VNode {
+        match vnode {
+            VNode::Element(element) => VNode::Element(VElement {
+                children: self.expand_children(&element.children, &id, parent, seen),
+                ..element.clone()
+            }),
+            VNode::Fragment(children) => {
+                VNode::Fragment(self.expand_children(children, &id, parent, seen))
+            }
+            VNode::Portal { target, children } => VNode::Portal {
+                target: target.clone(),
+                children: self.expand_children(children, &id, parent, seen),
+            },
+            VNode::Component(component) => self.expand_component(component, id, parent, seen),
+            VNode::Memo(memo) => self.expand_memo(memo, id, parent, seen),
+            VNode::Text(_) | VNode::Dynamic(_) | VNode::Empty => vnode.clone(),
+        }
+    }
+
+    fn expand_children(
+        &mut self,
+        children: &[VNode],
+        id: &InstanceId,
+        parent: Option<&Scope>,
+        seen: &mut Visit,
+    ) -> Vec<VNode> {
+        // Only the first child with a key is matched by it; later ones
+        // sharing the key fall back to their position
+        let mut keys = HashSet::new();
+        children
+            .iter()
+            .enumerate()
+            .map(|(i, child)| {
+                let slot = match child {
+                    VNode::Element(VElement { key: Some(key), .. }) if keys.insert(key) => {
+                        Slot::Key(key.clone())
+                    }
+                    _ => Slot::Index(i),
+                };
+                let mut child_id = id.clone();
+                child_id.push(slot);
+                self.expand_node(child, child_id, parent, seen)
+            })
+            .collect()
+    }
+
+    fn expand_component(
+        &mut self,
+        node: &VComponent,
+        mut id: InstanceId,
+        parent: Option<&Scope>,
+        seen: &mut Visit,
+    ) -> VNode {
+        id.push(Slot::Component(node.name.clone()));
+
+        let (rendered, children) = match self.instances.get_mut(&id) {
+            Some(instance) => {
+                if instance.props != node.props {
+                    let component = &mut instance.component;
+                    instance.scope.run(|| {
+                        component.set_props(&node.props);
+                        component.update();
+                    });
+                    instance.props = node.props.clone();
+                }
+                (instance.render(), instance.children.clone())
+            }
+            None => {
+                let Some(mut component) = self.registry.create(&node.name, &node.props) else {
+                    return VNode::Component(node.clone());
+                };
+                let scope = match parent {
+                    Some(parent) => parent.child(),
+                    None => create_scope(|scope| scope),
+                };
+                scope.run(|| component.init());
+                let mut instance = Instance {
+                    component,
+                    props: node.props.clone(),
+                    children: scope.child(),
+                    scope,
+                    render_scope: None,
+                    mounted: self.next_mount,
+                };
+                self.next_mount += 1;
+                let rendered = instance.render();
+                let children = instance.children.clone();
+                self.instances.insert(id.clone(), instance);
+                (rendered, children)
+            }
+        };
+        seen.instances.push(id.clone());
+
+        // Children of the rendered tree belong to this instance
+        self.expand_node(&rendered, id, Some(&children), seen)
+    }
+
+    fn expand_memo(
+        &mut self,
+        memo: &VMemo,
+        id: InstanceId,
+        parent: Option<&Scope>,
+        seen: &mut Visit,
+    ) -> VNode {
+        seen.memos.push(id.clone());
+        if let Some(entry) = self.memos.get(&id) {
+            if entry.input.same_as(memo) {
+                seen.instances.extend(entry.instances.iter().cloned());
+                seen.memos.extend(entry.memos.iter().cloned());
+                return entry.output.clone();
+            }
+        }
+
+        let (instances, memos) = (seen.instances.len(), seen.memos.len());
+        let node = self.expand_node(&memo.node, id.clone(), parent, seen);
+        let output = VNode::Memo(VMemo {
+            key: memo.key.clone(),
+            props_hash: memo.props_hash,
+            node: Rc::new(node),
+        });
+        self.memos.insert(
+            id,
+            MemoEntry {
+                input: memo.clone(),
+                output: output.clone(),
+                instances: seen.instances[instances..].to_vec(),
+                memos: seen.memos[memos..].to_vec(),
+            },
+        );
+        output
+    }
+
+    /// Clean up and dispose the instances matching `remove`, in mount order
+    fn teardown(&mut self, remove: impl Fn(&InstanceId) -> bool) {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .keys()
+            .filter(|id| remove(id))
+            .cloned()
+            .collect();
+        let mut removed: Vec<Instance> = ids
+            .iter()
+            .filter_map(|id| self.instances.remove(id))
+            .collect();
+        removed.sort_by_key(|instance| instance.mounted);
+
+        for instance in &mut removed {
+            instance.component.cleanup();
+        }
+        for instance in removed {
+            instance.scope.dispose();
+        }
+    }
+}
+
+impl Drop for Reconciler {
+    fn drop(&mut self) {
+        self.unmount();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::on_cleanup;
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    /// Logs its hooks and renders its children as `Child` components
+    struct Logged {
+        name: String,
+        props: HashMap<String, String>,
+        log: Log,
+    }
+
+    impl Logged {
+        fn record(&self, hook: &str) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", hook, self.name));
+        }
+    }
+
+    impl Component for Logged {
+        fn init(&mut self) {
+            self.record("init");
+        }
+
+        fn set_props(&mut self, props: &HashMap<String, String>) {
+            self.props = props.clone();
+        }
+
+        fn update(&mut self) {
+            self.record("update");
+        }
+
+        fn cleanup(&mut self) {
+            self.record("cleanup");
+        }
+
+        fn render(&self) -> VNode {
+            let children = self.props.get("children").map_or("", String::as_str);
+            let mut element = VElement::new("div").attr("data-name", self.name.as_str());
+            for child in children.split(',').filter(|child| !child.is_empty()) {
+                element = element.child(
+                    VComponent::new("Logged")
+                        .prop("name", format!("{}.{}", self.name, child))
+                        .into(),
+                );
+            }
+            element.into()
+        }
+    }
+
+    fn registry(log: &Log) -> Rc<ComponentRegistry> {
+        let mut registry = ComponentRegistry::new();
+        let log = log.clone();
+        registry.register("Logged", move |props| Logged {
+            name: props["name"].clone(),
+            props: props.clone(),
+            log: log.clone(),
+        });
+        Rc::new(registry)
+    }
+
+    fn logged(name: &str, children: &str) -> VNode {
+        VComponent::new("Logged")
+            .prop("name", name)
+            .prop("children", children)
+            .into()
+    }
+
+    fn take(log: &Log) -> Vec<String> {
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+
+    #[test]
+    fn test_expand_mounts_nested_components_in_order() {
+        let log = Log::default();
+        let mut reconciler = Reconciler::new(registry(&log));
+
+        let expanded = reconciler.expand(&logged("app", "a,b"));
+        assert_eq!(
+            expanded,
+            VElement::new("div")
+                .attr("data-name", "app")
+                .child(VElement::new("div").attr("data-name", "app.a").into())
+                .child(VElement::new("div").attr("data-name", "app.b").into())
+                .into()
+        );
+        assert_eq!(reconciler.len(), 3);
+        assert_eq!(take(&log), vec!["init app", "init app.a", "init app.b"]);
+
+        // Same props: no hooks
+        reconciler.expand(&logged("app", "a,b"));
+        assert!(take(&log).is_empty());
+
+        reconciler.unmount();
+        assert_eq!(
+            take(&log),
+            vec!["cleanup app", "cleanup app.a", "cleanup app.b"]
+        );
+        assert!(reconciler.is_empty());
+    }
+
+    #[test]
+    fn test_props_change_updates_and_removed_children_clean_up() {
+        let log = Log::default();
+        let mut reconciler = Reconciler::new(registry(&log));
+        reconciler.expand(&logged("app", "a,b"));
+        take(&log);
+
+        reconciler.expand(&logged("app", "a"));
+        assert_eq!(take(&log), vec!["update app", "cleanup app.b"]);
+        assert_eq!(reconciler.len(), 2);
+
+        // A different component at the same position is a new instance
+        let view: VNode = VElement::new("main").child(logged("other", "")).into();
+        reconciler.expand(&view);
+        assert_eq!(
+            take(&log),
+            vec!["init other", "cleanup app", "cleanup app.a"]
+        );
+    }
+
+    #[test]
+    fn test_keyed_elements_keep_their_components() {
+        let log = Log::default();
+        let mut reconciler = Reconciler::new(registry(&log));
+        let row =
+            |key: &str| -> VNode { VElement::new("li").key(key).child(logged(key, "")).into() };
+
+        reconciler.expand(
+            &VElement::new("ul")
+                .children(vec![row("x"), row("y")])
+                .into(),
+        );
+        take(&log);
+        reconciler.expand(
+            &VElement::new("ul")
+                .children(vec![row("y"), row("x")])
+                .into(),
+        );
+        assert!(take(&log).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_keys_match_components_by_position() {
+        let log = Log::default();
+        let mut reconciler = Reconciler::new(registry(&log));
+        let row = |name: &str| -> VNode {
+            VElement::new("li")
+                .key("row")
+                .child(logged(name, ""))
+                .into()
+        };
+
+        reconciler.expand(
+            &VElement::new("ul")
+                .children(vec![row("x"), row("y")])
+                .into(),
+        );
+        assert_eq!(reconciler.len(), 2);
+        assert_eq!(take(&log), vec!["init x", "init y"]);
+
+        reconciler.expand(&VElement::new("ul").child(row("x")).into());
+        assert_eq!(reconciler.len(), 1);
+        assert_eq!(take(&log), vec!["cleanup y"]);
+    }
+
+    #[test]
+    fn test_unchanged_memo_keeps_components_without_rendering() {
+        let log = Log::default();
+        let mut reconciler = Reconciler::new(registry(&log));
+        let view = |children: &str| -> VNode {
+            VElement::new("main")
+                .child(VNode::memo("app", children, || logged("app", children)))
+                .into()
+        };
+
+        let first = reconciler.expand(&view("a"));
+        take(&log);
+        let second = reconciler.expand(&view("a"));
+        assert_eq!(second, first);
+        assert_eq!(reconciler.len(), 2);
+
+        reconciler.expand(&view(""));
+        assert_eq!(take(&log), vec!["update app", "cleanup app.a"]);
+    }
+
+    #[test]
+    fn test_unknown_components_are_left_in_place() {
+        let mut reconciler = Reconciler::new(Rc::new(ComponentRegistry::new()));
+        let view: VNode = VComponent::new("Missing").into();
+        assert_eq!(reconciler.expand(&view), view);
+        assert!(reconciler.is_empty());
+    }
+
+    #[test]
+    fn test_teardown_disposes_render_scope() {
+        struct Scoped(Rc<std::cell::Cell<usize>>);
+
+        // SAFETY: test-only component used on a single thread
+        unsafe impl Send for Scoped {}
+        unsafe impl Sync for Scoped {}
+
+        impl Component for Scoped {
+            fn render(&self) -> VNode {
+                let disposed = self.0.clone();
+                on_cleanup(move || disposed.set(disposed.get() + 1));
+                VNode::Empty
+            }
+        }
+
+        let disposed = Rc::new(std::cell::Cell::new(0));
+        let mut registry = ComponentRegistry::new();
+        let counter = disposed.clone();
+        registry.register("Scoped", move |_| Scoped(counter.clone()));
+
+        let mut reconciler = Reconciler::new(Rc::new(registry));
+        let view: VNode = VComponent::new("Scoped").into();
+        reconciler.expand(&view);
+        reconciler.expand(&view);
+        assert_eq!(disposed.get(), 1);
+
+        drop(reconciler);
+        assert_eq!(disposed.get(), 2);
+    }
+
+    #[test]
+    fn test_children_read_context_provided_while_rendering() {
+        use crate::reactivity::{provide_context, use_context};
+
+        struct Themed {
+            theme: String,
+        }
+
+        impl Component for Themed {
+            fn set_props(&mut self, props: &HashMap<String, String>) {
+                self.theme = props["theme"].clone();
+            }
+
+            fn render(&self) -> VNode {
+                provide_context(self.theme.clone());
+                VComponent::new("Label").into()
+            }
+        }
+
+        struct Label;
+
+        impl Component for Label {
+            fn render(&self) -> VNode {
+                use_context::<String>().unwrap_or_default().into()
+            }
+        }
+
+        let mut registry = ComponentRegistry::new();
+        registry.register("Themed", |props| Themed {
+            theme: props["theme"].clone(),
+        });
+        registry.register("Label", |_| Label);
+
+        let mut reconciler = Reconciler::new(Rc::new(registry));
+        let view = |theme: &str| -> VNode { VComponent::new("Themed").prop("theme", theme).into() };
+        assert_eq!(reconciler.expand(&view("dark")), "dark".into());
+        assert_eq!(reconciler.expand(&view("light")), "light".into());
+    }
+
+    #[test]
+    fn test_init_effects_stop_on_unmount() {
+        use crate::reactivity::{Effect, Signal};
+
+        struct Watcher {
+            source: Signal<i32>,
+            runs: Rc<std::cell::Cell<usize>>,
+        }
+
+        // SAFETY: test-only component used on a single thread
+        unsafe impl Send for Watcher {}
+        unsafe impl Sync for Watcher {}
+
+        impl Component for Watcher {
+            fn init(&mut self) {
+                let (source, runs) = (self.source.clone(), self.runs.clone());
+                Effect::new(move || {
+                    source.get();
+                    runs.set(runs.get() + 1);
+                });
+            }
+
+            fn render(&self) -> VNode {
+                VNode::Empty
+            }
+        }
+
+        let source = Signal::new(0);
+        let runs = Rc::new(std::cell::Cell::new(0));
+        let mut registry = ComponentRegistry::new();
+        let (source_c, runs_c) = (source.clone(), runs.clone());
+        registry.register("Watcher", move |_| Watcher {
+            source: source_c.clone(),
+            runs: runs_c.clone(),
+        });
+
+        let mut reconciler = Reconciler::new(Rc::new(registry));
+        reconciler.expand(&VComponent::new("Watcher").into());
+        source.set(1);
+        assert_eq!(runs.get(), 2);
+
+        reconciler.unmount();
+        source.set(2);
+        assert_eq!(runs.get(), 2);
+    }
+}
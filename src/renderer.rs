@@ -1,13 +1,35 @@
 //! Cross-platform renderer
 
-use crate::component::Component;
+use crate::component::{Component, ComponentRegistry};
+use std::rc::Rc;
 
 #[cfg(not(feature = "web"))]
 use crate::platform::create_platform;
 
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Components expanded by [`mount_with_registry`], by selector
+    static MOUNTED: std::cell::RefCell<std::collections::HashMap<String, crate::reconciler::Reconciler>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
 /// Mount a component to the target selector
 #[cfg(feature = "web")]
 pub fn mount<C: Component>(selector: &str, component: C) -> Result<(), String> {
+    mount_with_registry(selector, component, Rc::new(ComponentRegistry::new()))
+}
+
+/// Mount a component to the target selector, expanding the components it
+/// renders with the factories in `registry`
+///
+/// They stay mounted until another component is mounted to the same
+/// selector, which cleans them up.
+#[cfg(feature = "web")]
+pub fn mount_with_registry<C: Component>(
+    selector: &str,
+    component: C,
+    registry: Rc<ComponentRegistry>,
+) -> Result<(), String> {
     #[cfg(target_arch = "wasm32")]
     {
         // Get the window and document
@@ -20,8 +42,13 @@ pub fn mount<C: Component>(selector: &str, component: C) -> Result<(), String> {
             .map_err(|_| format!("Invalid selector: {}", selector))?
             .ok_or(format!("Element not found: {}", selector))?;
 
-        // Render the component to a VNode
-        let vnode = component.render();
+        // Clean up what was mounted here before
+        let previous = MOUNTED.with(|mounted| mounted.borrow_mut().remove(selector));
+        drop(previous);
+
+        // Render the component to a VNode, with child components expanded
+        let mut reconciler = crate::reconciler::Reconciler::new(registry);
+        let vnode = reconciler.expand(&component.render());
 
         // Create a WebRenderer
         let renderer = WebRenderer::new();
@@ -31,6 +58,7 @@ pub fn mount<C: Component>(selector: &str, component: C) -> Result<(), String> {
 
         // Clear the target and append the rendered content
         while let Some(child) = target.first_child() {
+            unmount(&child);
             target
                 .remove_child(&child)
                 .map_err(|_| "Failed to clear target")?;
@@ -40,11 +68,16 @@ pub fn mount<C: Component>(selector: &str, component: C) -> Result<(), String> {
             .append_child(&dom_node)
             .map_err(|_| "Failed to mount component")?;
 
+        MOUNTED.with(|mounted| {
+            mounted
+                .borrow_mut()
+                .insert(selector.to_string(), reconciler)
+        });
         Ok(())
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let _ = (selector, component);
+        let _ = (selector, component, registry);
         Err("mount() is only available on WASM target".to_string())
     }
 }
@@ -64,21 +97,19 @@ pub fn mount<C: Component>(_selector: &str, _component: C) -> Result<(), String>
     Ok(())
 }
 
-/// Renderer trait for different platforms
-#[cfg(not(target_arch = "wasm32"))]
-pub trait Renderer: Send + Sync {
-    /// Initialize the renderer
-    fn init(&mut self) -> Result<(), String>;
-
-    /// Render a virtual DOM tree
-    fn render(&mut self, vnode: &crate::vdom::VNode) -> Result<(), String>;
-
-    /// Apply patches to the rendered tree
-    fn patch(&mut self, patches: &[crate::vdom::Patch]) -> Result<(), String>;
+/// Mount a component to the target selector (non-web platforms)
+#[cfg(not(feature = "web"))]
+pub fn mount_with_registry<C: Component>(
+    selector: &str,
+    component: C,
+    _registry: Rc<ComponentRegistry>,
+) -> Result<(), String> {
+    mount(selector, component)
 }
 
-/// Renderer trait for WASM (no Send + Sync required)
-#[cfg(target_arch = "wasm32")]
+/// Renderer trait for different platforms
+///
+/// Not `Send`: trees carry `Rc` event handlers, which renderers keep.
 pub trait Renderer {
     /// Initialize the renderer
     fn init(&mut self) -> Result<(), String>;
@@ -97,6 +128,9 @@ pub struct WebRenderer {
     document: web_sys::Document,
     #[cfg(target_arch = "wasm32")]
     root: Option<web_sys::Element>,
+    /// Expands component nodes before rendering, when set
+    #[cfg(target_arch = "wasm32")]
+    reconciler: Option<crate::reconciler::Reconciler>,
     #[cfg(not(target_arch = "wasm32"))]
     _dummy: (),
 }
@@ -116,6 +150,7 @@ impl WebRenderer {
             Self {
                 document,
                 root: None,
+                reconciler: None,
             }
         }
         #[cfg(not(target_arch = "wasm32"))]
@@ -124,11 +159,30 @@ impl WebRenderer {
         }
     }
 
+    /// Create a renderer that expands component nodes with the factories in
+    /// `registry` before rendering
+    ///
+    /// Instances are kept from one [`Renderer::render`] to the next, as with
+    /// [`Reconciler::expand`](crate::reconciler::Reconciler::expand);
+    /// patches must be diffed from expanded trees.
+    pub fn with_registry(registry: Rc<ComponentRegistry>) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let mut renderer = Self::new();
+            renderer.reconciler = Some(crate::reconciler::Reconciler::new(registry));
+            renderer
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = registry;
+            Self::new()
+        }
+    }
+
     #[cfg(target_arch = "wasm32")]
     pub fn create_element(&self, vnode: &crate::vdom::VNode) -> Result<web_sys::Node, String> {
         use crate::vdom::VNode;
         use wasm_bindgen::closure::Closure;
-        use wasm_bindgen::JsCast;
 
         match vnode {
             VNode::Element(element) => {
@@ -139,33 +193,29 @@ impl WebRenderer {
 
                 // Set attributes and event handlers
                 for (key, value) in &element.attrs {
+                    if element.bindings.contains_key(key) {
+                        continue;
+                    }
                     // Check if this is an event handler (starts with "on")
                     if key.starts_with("on") {
                         let event_type = key.strip_prefix("on").unwrap_or(key);
+                        let name = event_type.to_string();
 
                         // For now, we'll just log events
                         // In a full implementation, this would dispatch to component methods
                         let callback = Closure::wrap(Box::new(move |event: web_sys::Event| {
-                            web_sys::console::log_1(
-                                &format!("Event triggered: {}", event_type).into(),
-                            );
+                            web_sys::console::log_1(&format!("Event triggered: {}", name).into());
                             // Prevent default behavior for some events
-                            if event_type == "submit" {
+                            if name == "submit" {
                                 event.prevent_default();
                             }
                         })
                             as Box<dyn FnMut(_)>);
 
-                        dom_element
-                            .add_event_listener_with_callback(
-                                event_type,
-                                callback.as_ref().unchecked_ref(),
-                            )
-                            .map_err(|_| format!("Failed to add event listener: {}", key))?;
-
-                        // Leak the closure to keep it alive
-                        // In production, we'd store these and clean them up properly
-                        callback.forget();
+                        add_callback(&dom_element, event_type, callback)?;
+                    } else if crate::vdom::is_property(key) {
+                        // Live state such as an input's value
+                        set_property(&dom_element, key, Some(value))?;
                     } else {
                         // Regular attribute
                         dom_element
@@ -174,6 +224,14 @@ impl WebRenderer {
                     }
                 }
 
+                for (key, binding) in &element.bindings {
+                    bind_attribute(&dom_element, key, binding)?;
+                }
+
+                for (event, listener) in &element.events {
+                    set_listener(&dom_element, event, Some(listener))?;
+                }
+
                 // Append children
                 for child in &element.children {
                     let child_node = self.create_element(child)?;
@@ -188,10 +246,39 @@ impl WebRenderer {
                 let text_node = self.document.create_text_node(&text.content);
                 Ok(text_node.into())
             }
-            VNode::Component(_) => {
-                // Components should be expanded to elements before rendering
-                Err("Cannot render component directly".to_string())
+            VNode::Dynamic(binding) => {
+                let text_node = self.document.create_text_node("");
+                let node = text_node.clone();
+                bind(&text_node, "", binding, move |content| {
+                    node.set_data(&content)
+                })?;
+                Ok(text_node.into())
+            }
+            VNode::Component(component) => {
+                // Expanded before rendering when a registry is set
+                Err(format!("Component not registered: {}", component.name))
             }
+            VNode::Fragment(children) => {
+                let fragment = self.document.create_document_fragment();
+                for child in children {
+                    let child_node = self.create_element(child)?;
+                    fragment
+                        .append_child(&child_node)
+                        .map_err(|_| "Failed to append child".to_string())?;
+                }
+                Ok(fragment.into())
+            }
+            VNode::Portal { target, children } => {
+                let content = self.document.create_document_fragment();
+                for child in children {
+                    let child_node = self.create_element(child)?;
+                    content
+                        .append_child(&child_node)
+                        .map_err(|_| "Failed to append child".to_string())?;
+                }
+                mount_portal(&self.document, target, &content)
+            }
+            VNode::Memo(memo) => self.create_element(&memo.node),
             VNode::Empty => {
                 let text_node = self.document.create_text_node("");
                 Ok(text_node.into())
@@ -205,6 +292,10 @@ impl WebRenderer {
     pub fn new() -> Self {
         Self { initialized: false }
     }
+
+    pub fn with_registry(_registry: Rc<ComponentRegistry>) -> Self {
+        Self::new()
+    }
 }
 
 impl Default for WebRenderer {
@@ -243,10 +334,19 @@ impl Renderer for WebRenderer {
     fn render(&mut self, vnode: &crate::vdom::VNode) -> Result<(), String> {
         #[cfg(target_arch = "wasm32")]
         {
-            let root = self.root.as_ref().ok_or("Renderer not initialized")?;
+            let root = self.root.clone().ok_or("Renderer not initialized")?;
+            let expanded;
+            let vnode = match self.reconciler.as_mut() {
+                Some(reconciler) => {
+                    expanded = reconciler.expand(vnode);
+                    &expanded
+                }
+                None => vnode,
+            };
 
             // Clear existing content
             while let Some(child) = root.first_child() {
+                unmount(&child);
                 root.remove_child(&child)
                     .map_err(|_| "Failed to remove child")?;
             }
@@ -268,66 +368,8 @@ impl Renderer for WebRenderer {
     fn patch(&mut self, patches: &[crate::vdom::Patch]) -> Result<(), String> {
         #[cfg(target_arch = "wasm32")]
         {
-            use crate::vdom::Patch;
-            use wasm_bindgen::JsCast;
-
-            let root = self.root.as_ref().ok_or("No root element")?;
-
-            for patch in patches {
-                match patch {
-                    Patch::Replace { path, node } => {
-                        // Find the node at path and replace it
-                        let target = self.find_node_at_path(root, path)?;
-                        let new_node = self.create_element(node)?;
-
-                        if let Some(parent) = target.parent_node() {
-                            parent
-                                .replace_child(&new_node, &target)
-                                .map_err(|_| "Failed to replace node")?;
-                        }
-                    }
-                    Patch::UpdateText { path, content } => {
-                        // Update text node content
-                        let target = self.find_node_at_path(root, path)?;
-                        if let Some(text_node) = target.dyn_ref::<web_sys::Text>() {
-                            text_node.set_data(content);
-                        }
-                    }
-                    Patch::SetAttribute { path, key, value } => {
-                        // Set attribute on element
-                        let target = self.find_node_at_path(root, path)?;
-                        if let Some(element) = target.dyn_ref::<web_sys::Element>() {
-                            if value.is_empty() {
-                                element
-                                    .remove_attribute(key)
-                                    .map_err(|_| format!("Failed to remove attribute: {}", key))?;
-                            } else {
-                                element
-                                    .set_attribute(key, value)
-                                    .map_err(|_| format!("Failed to set attribute: {}", key))?;
-                            }
-                        }
-                    }
-                    Patch::Append { path, node } => {
-                        // Append child node
-                        let target = self.find_node_at_path(root, path)?;
-                        let new_node = self.create_element(node)?;
-                        target
-                            .append_child(&new_node)
-                            .map_err(|_| "Failed to append child")?;
-                    }
-                    Patch::Remove { path } => {
-                        // Remove child node
-                        let target = self.find_node_at_path(root, path)?;
-                        if let Some(parent) = target.parent_node() {
-                            parent
-                                .remove_child(&target)
-                                .map_err(|_| "Failed to remove child")?;
-                        }
-                    }
-                }
-            }
-            Ok(())
+            let root = self.root.clone().ok_or("No root element")?;
+            self.apply_patches(&root, patches)
         }
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -337,19 +379,582 @@ impl Renderer for WebRenderer {
     }
 }
 
+/// Set a DOM property (see [`crate::vdom::is_property`]), or reset it when
+/// `value` is `None`
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_property(
+    element: &web_sys::Element,
+    key: &str,
+    value: Option<&str>,
+) -> Result<(), String> {
+    use wasm_bindgen::JsValue;
+
+    let value = match key {
+        "checked" | "selected" => JsValue::from_bool(value.is_some_and(|v| v != "false")),
+        _ => JsValue::from_str(value.unwrap_or("")),
+    };
+    js_sys::Reflect::set(element, &JsValue::from_str(key), &value)
+        .map(|_| ())
+        .map_err(|_| format!("Failed to set property: {}", key))
+}
+
+/// Property on a node holding its id in `LISTENERS`, `CALLBACKS` and
+/// `BINDINGS`
+#[cfg(target_arch = "wasm32")]
+const NODE_ID: &str = "__windjammerNode";
+
+/// A DOM callback and the event it listens to
+#[cfg(target_arch = "wasm32")]
+type Callback = (
+    String,
+    wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+);
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Handlers by node id and event name; `None` once detached
+    static LISTENERS: std::cell::RefCell<
+        std::collections::HashMap<u32, std::collections::HashMap<String, Option<crate::vdom::Listener>>>,
+    > = std::cell::RefCell::new(std::collections::HashMap::new());
+    /// DOM callbacks by node id, dropped when the node is released
+    static CALLBACKS: std::cell::RefCell<std::collections::HashMap<u32, Vec<Callback>>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+    /// Scopes of the binding effects by node id and key (`""` for text)
+    static BINDINGS: std::cell::RefCell<
+        std::collections::HashMap<u32, std::collections::HashMap<String, crate::reactivity::Scope>>,
+    > = std::cell::RefCell::new(std::collections::HashMap::new());
+    static NEXT_NODE_ID: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+/// The id of `node` in the registries, if it has one
+#[cfg(target_arch = "wasm32")]
+fn existing_node_id(node: &web_sys::Node) -> Option<u32> {
+    use wasm_bindgen::JsValue;
+
+    js_sys::Reflect::get(node, &JsValue::from_str(NODE_ID))
+        .ok()
+        .and_then(|id| id.as_f64())
+        .map(|id| id as u32)
+}
+
+/// The id of `node` in the registries, assigned on first use
+#[cfg(target_arch = "wasm32")]
+fn node_id(node: &web_sys::Node) -> Result<u32, String> {
+    use wasm_bindgen::JsValue;
+
+    if let Some(id) = existing_node_id(node) {
+        return Ok(id);
+    }
+    let id = NEXT_NODE_ID.with(|next| next.replace(next.get() + 1));
+    js_sys::Reflect::set(node, &JsValue::from_str(NODE_ID), &JsValue::from(id))
+        .map_err(|_| "Failed to register node".to_string())?;
+    Ok(id)
+}
+
+/// Listen to `event` on `node` with `callback`, which is kept until the node
+/// is released
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn add_callback(
+    node: &web_sys::Node,
+    event: &str,
+    callback: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+
+    let id = node_id(node)?;
+    node.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref())
+        .map_err(|_| format!("Failed to add event listener: {}", event))?;
+    CALLBACKS.with(|callbacks| {
+        callbacks
+            .borrow_mut()
+            .entry(id)
+            .or_default()
+            .push((event.to_string(), callback))
+    });
+    Ok(())
+}
+
+/// Call `apply` with the value of `binding` now and after every change,
+/// until `node` is released or `key` is bound or set again
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn bind(
+    node: &web_sys::Node,
+    key: &str,
+    binding: &crate::binding::Binding,
+    apply: impl Fn(String) + 'static,
+) -> Result<(), String> {
+    let id = node_id(node)?;
+    unbind(node, key);
+    // A root of its own, so it outlives whatever effect is rendering
+    let scope = crate::reactivity::create_root(|scope| {
+        binding.bind(apply);
+        scope
+    });
+    BINDINGS.with(|bindings| {
+        bindings
+            .borrow_mut()
+            .entry(id)
+            .or_default()
+            .insert(key.to_string(), scope)
+    });
+    Ok(())
+}
+
+/// Bind the attribute, or property, `key` of `element`
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn bind_attribute(
+    element: &web_sys::Element,
+    key: &str,
+    binding: &crate::binding::Binding,
+) -> Result<(), String> {
+    let (target, key_c) = (element.clone(), key.to_string());
+    bind(element, key, binding, move |value| {
+        let result = if crate::vdom::is_property(&key_c) {
+            set_property(&target, &key_c, Some(&value))
+        } else {
+            target
+                .set_attribute(&key_c, &value)
+                .map_err(|_| format!("Failed to set attribute: {}", key_c))
+        };
+        if let Err(e) = result {
+            crate::component_runtime::console_error(&e);
+        }
+    })
+}
+
+/// Stop the binding of `key` on `node`, if any
+#[cfg(target_arch = "wasm32")]
+fn unbind(node: &web_sys::Node, key: &str) {
+    let Some(id) = existing_node_id(node) else {
+        return;
+    };
+    let scope = BINDINGS.with(|bindings| bindings.borrow_mut().get_mut(&id)?.remove(key));
+    if let Some(scope) = scope {
+        scope.dispose();
+    }
+}
+
+/// Attach or replace the handler for `event`, or detach it when `listener`
+/// is `None`
+///
+/// An element gets one DOM listener per event type, which calls whatever
+/// handler is current, so replacing a handler does not touch the DOM.
+#[cfg(target_arch = "wasm32")]
+fn set_listener(
+    element: &web_sys::Element,
+    event: &str,
+    listener: Option<&crate::vdom::Listener>,
+) -> Result<(), String> {
+    use wasm_bindgen::closure::Closure;
+
+    let id = node_id(element)?;
+
+    let install = LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let slots = listeners.entry(id).or_default();
+        match (slots.get_mut(event), listener) {
+            (Some(slot), _) => {
+                *slot = listener.cloned();
+                false
+            }
+            (None, Some(listener)) => {
+                slots.insert(event.to_string(), Some(listener.clone()));
+                true
+            }
+            (None, None) => false,
+        }
+    });
+    if install {
+        let event_name = event.to_string();
+        let callback = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            // Cloned out so the handler may patch the DOM
+            let listener = LISTENERS.with(|listeners| {
+                listeners
+                    .borrow()
+                    .get(&id)
+                    .and_then(|slots| slots.get(&event_name).cloned().flatten())
+            });
+            if let Some(listener) = listener {
+                let event = to_event(&event_name, &event);
+                crate::events::with_current_event(event, || listener.call());
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        add_callback(element, event, callback)?;
+    }
+    Ok(())
+}
+
+/// The cross-platform form of a DOM event, for
+/// [`crate::events::current_event`]
+#[cfg(target_arch = "wasm32")]
+fn to_event(name: &str, event: &web_sys::Event) -> crate::events::Event {
+    use crate::events::{Event, Modifiers, MouseButton};
+    use wasm_bindgen::JsValue;
+
+    let get =
+        |target: &JsValue, key: &str| js_sys::Reflect::get(target, &JsValue::from_str(key)).ok();
+    let target: Option<JsValue> = event.target().map(Into::into);
+    let event: &JsValue = event;
+    let number = |key: &str| get(event, key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let flag = |key: &str| get(event, key).and_then(|v| v.as_bool()).unwrap_or(false);
+    let value = || {
+        target
+            .as_ref()
+            .and_then(|target| get(target, "value"))
+            .and_then(|v| v.as_string())
+            .unwrap_or_default()
+    };
+    let key = || {
+        get(event, "key")
+            .and_then(|v| v.as_string())
+            .unwrap_or_default()
+    };
+    let modifiers = || Modifiers {
+        ctrl: flag("ctrlKey"),
+        alt: flag("altKey"),
+        shift: flag("shiftKey"),
+        meta: flag("metaKey"),
+    };
+
+    match name {
+        "click" => Event::Click {
+            x: number("clientX"),
+            y: number("clientY"),
+            button: match number("button") as i32 {
+                1 => MouseButton::Middle,
+                2 => MouseButton::Right,
+                _ => MouseButton::Left,
+            },
+        },
+        "input" => Event::Input { value: value() },
+        "change" => Event::Change { value: value() },
+        "keydown" => Event::KeyDown {
+            key: key(),
+            modifiers: modifiers(),
+        },
+        "keyup" => Event::KeyUp {
+            key: key(),
+            modifiers: modifiers(),
+        },
+        "keypress" => Event::KeyPress {
+            key: key(),
+            modifiers: modifiers(),
+        },
+        "focus" => Event::Focus,
+        "blur" => Event::Blur,
+        "submit" => Event::Submit,
+        _ => Event::Custom {
+            name: name.to_string(),
+            data: String::new(),
+        },
+    }
+}
+
+/// Release what the web renderer attached to `node`'s subtree (handlers,
+/// DOM callbacks and bindings) and remove the content of its portals
+///
+/// Patches do this for the nodes they replace or remove; call it before
+/// removing rendered nodes yourself, e.g. ones added with
+/// [`crate::simple_vnode::VNode::mount`].
+#[cfg(target_arch = "wasm32")]
+pub fn unmount(node: &web_sys::Node) {
+    release(node);
+    unmount_portals(node);
+}
+
+/// Drop what is registered for every node in `node`'s subtree, portal
+/// content included
+#[cfg(target_arch = "wasm32")]
+fn release(node: &web_sys::Node) {
+    use wasm_bindgen::JsCast;
+
+    if let Some(id) = existing_node_id(node) {
+        // Taken out first so nothing is borrowed while they are dropped
+        let listeners = LISTENERS.with(|listeners| listeners.borrow_mut().remove(&id));
+        let callbacks = CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(&id));
+        let bindings = BINDINGS.with(|bindings| bindings.borrow_mut().remove(&id));
+        drop(listeners);
+        for (event, callback) in callbacks.unwrap_or_default() {
+            let _ =
+                node.remove_event_listener_with_callback(&event, callback.as_ref().unchecked_ref());
+        }
+        for scope in bindings.unwrap_or_default().into_values() {
+            scope.dispose();
+        }
+    }
+    if let Some(container) = portal_container(node) {
+        release(&container);
+    }
+    let children = node.child_nodes();
+    for i in 0..children.length() {
+        if let Some(child) = children.get(i) {
+            release(&child);
+        }
+    }
+}
+
+/// Property on a portal's placeholder node holding its container element
+#[cfg(target_arch = "wasm32")]
+const PORTAL_CONTAINER: &str = "__windjammerPortal";
+
+/// Bubbling events forwarded from portal content to the portal's parent
+#[cfg(target_arch = "wasm32")]
+const PORTAL_EVENTS: &[&str] = &[
+    "click",
+    "dblclick",
+    "contextmenu",
+    "input",
+    "change",
+    "submit",
+    "keydown",
+    "keyup",
+    "pointerdown",
+    "pointerup",
+    "mousedown",
+    "mouseup",
+];
+
+/// Mount `content` into the element matching `target` and return the
+/// placeholder to put where the portal appears in the tree
+///
+/// Events from the content stop at the portal container and are re-dispatched
+/// from the placeholder's parent, so they bubble through the portal's logical
+/// ancestors rather than the target's.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn mount_portal(
+    document: &web_sys::Document,
+    target: &str,
+    content: &web_sys::Node,
+) -> Result<web_sys::Node, String> {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsValue;
+
+    let host = document
+        .query_selector(target)
+        .map_err(|_| format!("Invalid portal target: {}", target))?
+        .ok_or(format!("Portal target not found: {}", target))?;
+    let container = document
+        .create_element("div")
+        .map_err(|_| "Failed to create portal container".to_string())?;
+    container
+        .set_attribute("data-portal", target)
+        .map_err(|_| "Failed to set attribute: data-portal".to_string())?;
+    container
+        .append_child(content)
+        .map_err(|_| "Failed to append portal content".to_string())?;
+    host.append_child(&container)
+        .map_err(|_| format!("Failed to mount portal into {}", target))?;
+
+    let placeholder: web_sys::Node = document.create_comment("portal").into();
+    js_sys::Reflect::set(
+        &placeholder,
+        &JsValue::from_str(PORTAL_CONTAINER),
+        &container,
+    )
+    .map_err(|_| "Failed to link portal".to_string())?;
+
+    for event_type in PORTAL_EVENTS {
+        let placeholder = placeholder.clone();
+        let forward = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            event.stop_propagation();
+            let Some(parent) = placeholder.parent_node() else {
+                return;
+            };
+            let init = web_sys::EventInit::new();
+            init.set_bubbles(true);
+            init.set_cancelable(event.cancelable());
+            if let Ok(forwarded) = web_sys::Event::new_with_event_init_dict(&event.type_(), &init) {
+                let _ = parent.dispatch_event(&forwarded);
+                if forwarded.default_prevented() {
+                    event.prevent_default();
+                }
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        add_callback(&container, event_type, forward)?;
+    }
+
+    Ok(placeholder)
+}
+
+/// The container of the portal whose placeholder is `node`
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn portal_container(node: &web_sys::Node) -> Option<web_sys::Element> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    js_sys::Reflect::get(node, &JsValue::from_str(PORTAL_CONTAINER))
+        .ok()?
+        .dyn_into::<web_sys::Element>()
+        .ok()
+}
+
+/// Remove the content of every portal placed in `node`'s subtree
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn unmount_portals(node: &web_sys::Node) {
+    if let Some(container) = portal_container(node) {
+        unmount_portals(&container);
+        container.remove();
+    }
+    let children = node.child_nodes();
+    for i in 0..children.length() {
+        if let Some(child) = children.get(i) {
+            unmount_portals(&child);
+        }
+    }
+}
+
 // Helper methods for WebRenderer
 #[cfg(target_arch = "wasm32")]
 impl WebRenderer {
+    /// Apply patches whose paths start at `root`'s children
+    fn apply_patches(
+        &self,
+        root: &web_sys::Element,
+        patches: &[crate::vdom::Patch],
+    ) -> Result<(), String> {
+        use crate::vdom::Patch;
+        use wasm_bindgen::JsCast;
+
+        for patch in patches {
+            match patch {
+                Patch::Replace { path, node } => {
+                    // Find the node at path and replace it
+                    let target = self.find_node_at_path(root, path)?;
+                    let new_node = self.create_element(node)?;
+                    unmount(&target);
+
+                    if let Some(parent) = target.parent_node() {
+                        parent
+                            .replace_child(&new_node, &target)
+                            .map_err(|_| "Failed to replace node")?;
+                    }
+                }
+                Patch::UpdateText { path, content } => {
+                    // Update text node content
+                    let target = self.find_node_at_path(root, path)?;
+                    if let Some(text_node) = target.dyn_ref::<web_sys::Text>() {
+                        text_node.set_data(content);
+                    }
+                }
+                Patch::BindText { path, binding } => {
+                    let target = self.find_node_at_path(root, path)?;
+                    if let Some(text_node) = target.dyn_ref::<web_sys::Text>() {
+                        let node = text_node.clone();
+                        bind(&target, "", binding, move |content| node.set_data(&content))?;
+                    }
+                }
+                Patch::SetAttribute { path, key, value } => {
+                    // Set attribute on element
+                    let target = self.find_node_at_path(root, path)?;
+                    unbind(&target, key);
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        element
+                            .set_attribute(key, value)
+                            .map_err(|_| format!("Failed to set attribute: {}", key))?;
+                    }
+                }
+                Patch::RemoveAttribute { path, key } => {
+                    let target = self.find_node_at_path(root, path)?;
+                    unbind(&target, key);
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        element
+                            .remove_attribute(key)
+                            .map_err(|_| format!("Failed to remove attribute: {}", key))?;
+                        if crate::vdom::is_property(key) {
+                            set_property(element, key, None)?;
+                        }
+                    }
+                }
+                Patch::SetProperty { path, key, value } => {
+                    // Write the live property, not the default-value attribute
+                    let target = self.find_node_at_path(root, path)?;
+                    unbind(&target, key);
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        set_property(element, key, Some(value))?;
+                    }
+                }
+                Patch::BindAttribute { path, key, binding } => {
+                    let target = self.find_node_at_path(root, path)?;
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        bind_attribute(element, key, binding)?;
+                    }
+                }
+                Patch::SetEvent {
+                    path,
+                    event,
+                    listener,
+                } => {
+                    let target = self.find_node_at_path(root, path)?;
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        set_listener(element, event, Some(listener))?;
+                    }
+                }
+                Patch::RemoveEvent { path, event } => {
+                    let target = self.find_node_at_path(root, path)?;
+                    if let Some(element) = target.dyn_ref::<web_sys::Element>() {
+                        set_listener(element, event, None)?;
+                    }
+                }
+                Patch::Append { path, node } => {
+                    // Append child node
+                    let target = self.find_node_at_path(root, path)?;
+                    let new_node = self.create_element(node)?;
+                    target
+                        .append_child(&new_node)
+                        .map_err(|_| "Failed to append child")?;
+                }
+                Patch::Insert { path, index, node } => {
+                    // Insert before the child currently at `index`
+                    let parent = self.find_node_at_path(root, path)?;
+                    let new_node = self.create_element(node)?;
+                    let reference = parent.child_nodes().get(*index as u32);
+                    parent
+                        .insert_before(&new_node, reference.as_ref())
+                        .map_err(|_| "Failed to insert child")?;
+                }
+                Patch::Move { path, from, to } => {
+                    // insertBefore moves the existing node, keeping its
+                    // state; the reference is counted before the move
+                    let parent = self.find_node_at_path(root, path)?;
+                    let children = parent.child_nodes();
+                    let child = children
+                        .get(*from as u32)
+                        .ok_or(format!("Child not found at index {}", from))?;
+                    let before = if to > from { to + 1 } else { *to };
+                    let reference = children.get(before as u32);
+                    parent
+                        .insert_before(&child, reference.as_ref())
+                        .map_err(|_| "Failed to move child")?;
+                }
+                Patch::Remove { path } => {
+                    // Remove child node
+                    let target = self.find_node_at_path(root, path)?;
+                    unmount(&target);
+                    if let Some(parent) = target.parent_node() {
+                        parent
+                            .remove_child(&target)
+                            .map_err(|_| "Failed to remove child")?;
+                    }
+                }
+                Patch::Portal { path, patches } => {
+                    let placeholder = self.find_node_at_path(root, path)?;
+                    let container = portal_container(&placeholder).ok_or("Expected a portal")?;
+                    self.apply_patches(&container, patches)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn find_node_at_path(
         &self,
         root: &web_sys::Element,
         path: &[usize],
     ) -> Result<web_sys::Node, String> {
+        // The rendered tree is the first child of the root element, so
+        // path [0] is its top node
         let mut current: web_sys::Node = root.clone().into();
 
-        for &index in path.iter().skip(1) {
-            // Skip first index (root)
-            // Node has child_nodes() method, but we need to access it correctly
+        for &index in path {
             let children = current.child_nodes();
             current = children
                 .get(index as u32)
@@ -406,7 +1011,7 @@ impl DesktopRenderer {
                 let mut html = format!("<{}", element.tag);
 
                 // Add attributes
-                for (key, value) in &element.attrs {
+                for (key, value) in &element.current_attrs() {
                     html.push_str(&format!(" {}=\"{}\"", key, value));
                 }
 
@@ -421,7 +1026,22 @@ impl DesktopRenderer {
                 html
             }
             VNode::Text(text) => text.content.clone(),
+            VNode::Dynamic(binding) => binding.get(),
             VNode::Component(_) => String::new(),
+            VNode::Fragment(children) => children
+                .iter()
+                .map(|child| self.vnode_to_html(child))
+                .collect(),
+            VNode::Portal { target, children } => {
+                // The webview has no separate layer; keep the content in a
+                // marked container
+                let content: String = children
+                    .iter()
+                    .map(|child| self.vnode_to_html(child))
+                    .collect();
+                format!("<div data-portal=\"{}\">{}</div>", target, content)
+            }
+            VNode::Memo(memo) => self.vnode_to_html(&memo.node),
             VNode::Empty => String::new(),
         }
     }
@@ -486,11 +1106,18 @@ impl Renderer for DesktopRenderer {
         for patch in patches {
             let js_command = match patch {
                 Patch::Replace { .. } => "document.getElementById('app').innerHTML = ...;",
-                Patch::UpdateText { .. } => "element.textContent = ...;",
+                Patch::UpdateText { .. } | Patch::BindText { .. } => "element.textContent = ...;",
                 Patch::SetAttribute { .. } => "element.setAttribute(...);",
-                // RemoveAttribute is not a separate variant, it's handled by SetAttribute with empty value
+                Patch::RemoveAttribute { .. } => "element.removeAttribute(...);",
+                Patch::SetProperty { .. } => "element[key] = ...;",
+                Patch::BindAttribute { .. } => "element.setAttribute(...);",
                 Patch::Append { .. } => "element.appendChild(...);",
+                Patch::Insert { .. } => "element.insertBefore(...);",
+                Patch::Move { .. } => "element.insertBefore(child, ...);",
                 Patch::Remove { .. } => "element.removeChild(...);",
+                Patch::SetEvent { .. } => "element.addEventListener(...);",
+                Patch::RemoveEvent { .. } => "element.removeEventListener(...);",
+                Patch::Portal { .. } => "portal.update(...);",
             };
             self.pending_updates.push(js_command.to_string());
         }
@@ -520,6 +1147,8 @@ impl Renderer for DesktopRenderer {
 pub struct MobileRenderer {
     view_hierarchy: Vec<NativeView>,
     root_view: Option<usize>,
+    /// Expands component nodes before rendering, when set
+    reconciler: Option<crate::reconciler::Reconciler>,
 }
 
 #[cfg(not(any(feature = "mobile-ios", feature = "mobile-android")))]
@@ -543,6 +1172,19 @@ impl MobileRenderer {
         Self {
             view_hierarchy: Vec::new(),
             root_view: None,
+            reconciler: None,
+        }
+    }
+
+    /// Create a renderer that expands component nodes with the factories in
+    /// `registry` before rendering
+    ///
+    /// Instances are kept from one [`Renderer::render`] to the next, as with
+    /// [`Reconciler::expand`](crate::reconciler::Reconciler::expand).
+    pub fn with_registry(registry: Rc<ComponentRegistry>) -> Self {
+        Self {
+            reconciler: Some(crate::reconciler::Reconciler::new(registry)),
+            ..Self::new()
         }
     }
 
@@ -554,10 +1196,7 @@ impl MobileRenderer {
                 let view_type = self.map_html_to_native(&element.tag);
                 let id = self.view_hierarchy.len();
 
-                let mut properties = std::collections::HashMap::new();
-                for (key, value) in &element.attrs {
-                    properties.insert(key.clone(), value.clone());
-                }
+                let properties = element.current_attrs();
 
                 let mut view = NativeView {
                     id,
@@ -567,7 +1206,7 @@ impl MobileRenderer {
                 };
 
                 // Process children
-                for child in &element.children {
+                for child in crate::vdom::flatten(&element.children) {
                     if let Some(child_id) = self.vnode_to_native_view(child) {
                         view.children.push(child_id);
                     }
@@ -576,26 +1215,67 @@ impl MobileRenderer {
                 self.view_hierarchy.push(view);
                 Some(id)
             }
-            VNode::Text(text) => {
+            VNode::Text(text) => Some(self.text_view(text.content.clone())),
+            VNode::Dynamic(binding) => Some(self.text_view(binding.get())),
+            // Expanded before rendering when a registry is set
+            VNode::Component(_) => None,
+            VNode::Fragment(children) => {
+                // Only reached for a root fragment; nested ones are flattened
+                let id = self.view_hierarchy.len();
+                let mut view = NativeView {
+                    id,
+                    view_type: "ContainerView".to_string(),
+                    properties: std::collections::HashMap::new(),
+                    children: Vec::new(),
+                };
+                for child in crate::vdom::flatten(children) {
+                    if let Some(child_id) = self.vnode_to_native_view(child) {
+                        view.children.push(child_id);
+                    }
+                }
+                self.view_hierarchy.push(view);
+                Some(id)
+            }
+            VNode::Portal { target, children } => {
+                // Presented above the rest of the hierarchy
                 let id = self.view_hierarchy.len();
                 let mut properties = std::collections::HashMap::new();
-                properties.insert("text".to_string(), text.content.clone());
-
-                let view = NativeView {
+                properties.insert("target".to_string(), target.clone());
+                let mut view = NativeView {
                     id,
-                    view_type: "TextView".to_string(),
+                    view_type: "OverlayView".to_string(),
                     properties,
                     children: Vec::new(),
                 };
-
+                for child in crate::vdom::flatten(children) {
+                    if let Some(child_id) = self.vnode_to_native_view(child) {
+                        view.children.push(child_id);
+                    }
+                }
                 self.view_hierarchy.push(view);
                 Some(id)
             }
-            VNode::Component(_) => None,
+            VNode::Memo(memo) => self.vnode_to_native_view(&memo.node),
             VNode::Empty => None,
         }
     }
 
+    fn text_view(&mut self, content: String) -> usize {
+        let id = self.view_hierarchy.len();
+        let mut properties = std::collections::HashMap::new();
+        properties.insert("text".to_string(), content);
+
+        let view = NativeView {
+            id,
+            view_type: "TextView".to_string(),
+            properties,
+            children: Vec::new(),
+        };
+
+        self.view_hierarchy.push(view);
+        id
+    }
+
     fn map_html_to_native(&self, tag: &str) -> String {
         // Map HTML tags to native view types
         match tag {
@@ -634,6 +1314,10 @@ impl MobileRenderer {
     pub fn new() -> Self {
         Self { initialized: false }
     }
+
+    pub fn with_registry(_registry: Rc<ComponentRegistry>) -> Self {
+        Self::new()
+    }
 }
 
 impl Default for MobileRenderer {
@@ -656,7 +1340,11 @@ impl Renderer for MobileRenderer {
         self.view_hierarchy.clear();
 
         // Build native view hierarchy
-        self.root_view = self.vnode_to_native_view(vnode);
+        let expanded = self
+            .reconciler
+            .as_mut()
+            .map(|reconciler| reconciler.expand(vnode));
+        self.root_view = self.vnode_to_native_view(expanded.as_ref().unwrap_or(vnode));
 
         // Create platform-specific views
         #[cfg(feature = "mobile-ios")]
@@ -677,18 +1365,35 @@ impl Renderer for MobileRenderer {
                 Patch::Replace { .. } => {
                     // Replace native view
                 }
-                Patch::UpdateText { .. } => {
+                Patch::UpdateText { .. } | Patch::BindText { .. } => {
                     // Update native text view
                 }
-                Patch::SetAttribute { .. } => {
-                    // Update view property (or remove if value is empty)
+                Patch::SetAttribute { .. }
+                | Patch::SetProperty { .. }
+                | Patch::BindAttribute { .. } => {
+                    // Update view property
+                }
+                Patch::RemoveAttribute { .. } => {
+                    // Reset view property
+                }
+                Patch::SetEvent { .. } | Patch::RemoveEvent { .. } => {
+                    // Update the view's gesture handlers
                 }
                 Patch::Append { .. } => {
                     // Add subview
                 }
+                Patch::Insert { .. } => {
+                    // Insert subview at index
+                }
+                Patch::Move { .. } => {
+                    // Reorder subview
+                }
                 Patch::Remove { .. } => {
                     // Remove subview
                 }
+                Patch::Portal { .. } => {
+                    // Update overlay subviews
+                }
             }
         }
 
@@ -772,4 +1477,35 @@ mod tests {
 
         assert!(renderer.render(&vnode).is_ok());
     }
+
+    #[cfg(any(feature = "mobile-ios", feature = "mobile-android"))]
+    #[test]
+    fn test_mobile_renderer_expands_registered_components() {
+        use crate::vdom::{VComponent, VElement, VNode};
+
+        struct Badge;
+
+        impl Component for Badge {
+            fn render(&self) -> VNode {
+                VElement::new("button").child("New".into()).into()
+            }
+        }
+
+        let mut registry = ComponentRegistry::new();
+        registry.register("Badge", |_| Badge);
+        let mut renderer = MobileRenderer::with_registry(Rc::new(registry));
+        renderer.init().unwrap();
+
+        let vnode: VNode = VElement::new("div")
+            .child(VComponent::new("Badge").into())
+            .into();
+        renderer.render(&vnode).unwrap();
+
+        let types: Vec<&str> = renderer
+            .view_hierarchy
+            .iter()
+            .map(|view| view.view_type.as_str())
+            .collect();
+        assert_eq!(types, vec!["TextView", "Button", "ContainerView"]);
+    }
 }
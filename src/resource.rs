@@ -0,0 +1,614 @@
+//! Async resources driven by the reactive system
+//!
+//! A [`Resource`] reads a reactive source, runs an async fetcher with it and
+//! exposes the outcome as signals: [`Resource::loading`], [`Resource::error`]
+//! and [`Resource::value`]. Whenever the source changes a new fetch starts and
+//! the in-flight one is cancelled, so a slow stale response can never
+//! overwrite a newer one.
+//!
+//! Fetches run on an [`Executor`]: `wasm_bindgen_futures::spawn_local` on the
+//! web, tokio on desktop, and a [`ManualExecutor`] in tests. Results travel
+//! back to the UI thread through a [`SyncSignal`], so fetchers may run on
+//! background threads.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::reactivity::Signal;
+//! use windjammer_ui::resource::{ManualExecutor, Resource};
+//! use std::rc::Rc;
+//!
+//! let user_id = Signal::new(1);
+//! let executor = ManualExecutor::new();
+//!
+//! let user = Resource::with_executor(
+//!     {
+//!         let user_id = user_id.clone();
+//!         move || user_id.get()
+//!     },
+//!     |id| async move { Ok::<_, String>(format!("user #{}", id)) },
+//!     Rc::new(executor.clone()),
+//! );
+//!
+//! assert!(user.loading().get());
+//! executor.run_until_stalled();
+//! assert_eq!(user.value().get(), Some("user #1".to_string()));
+//! ```
+
+use crate::reactivity::{batch, on_cleanup, untrack, Effect, Signal};
+use crate::sync_signal::SyncSignal;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// `Send` on native targets, where fetches may run on worker threads;
+/// no bound on wasm, where futures such as `JsFuture` are `!Send`
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// `Send` on native targets, where fetches may run on worker threads;
+/// no bound on wasm, where futures such as `JsFuture` are `!Send`
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// A spawned unit of async work
+#[cfg(not(target_arch = "wasm32"))]
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A spawned unit of async work
+#[cfg(target_arch = "wasm32")]
+pub type Task = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+/// Runs the futures started by resources
+pub trait Executor {
+    /// Start driving `task` to completion
+    fn spawn(&self, task: Task);
+}
+
+/// Default executor for the current platform
+///
+/// - Web: `wasm_bindgen_futures::spawn_local`
+/// - Desktop: the ambient tokio runtime, or a shared one if there is none
+/// - Otherwise: one background thread per task
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlatformExecutor;
+
+impl Executor for PlatformExecutor {
+    #[cfg(all(target_arch = "wasm32", feature = "web"))]
+    fn spawn(&self, task: Task) {
+        wasm_bindgen_futures::spawn_local(task);
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(feature = "web")))]
+    fn spawn(&self, _task: Task) {
+        panic!("PlatformExecutor on wasm32 requires the 'web' feature");
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+    fn spawn(&self, task: Task) {
+        use std::sync::OnceLock;
+        static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(task);
+            }
+            Err(_) => {
+                RUNTIME
+                    .get_or_init(|| {
+                        tokio::runtime::Builder::new_multi_thread()
+                            .enable_all()
+                            .build()
+                            .expect("Failed to start tokio runtime for resources")
+                    })
+                    .spawn(task);
+            }
+        }
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "tokio")))]
+    fn spawn(&self, task: Task) {
+        std::thread::spawn(move || block_on(task));
+    }
+}
+
+/// Drive a future to completion on the current thread
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "tokio")))]
+fn block_on(mut task: Task) {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    while task.as_mut().poll(&mut cx).is_pending() {
+        std::thread::park();
+    }
+}
+
+/// Executor that only makes progress when asked to, for deterministic tests
+#[derive(Clone, Default)]
+pub struct ManualExecutor {
+    tasks: Rc<RefCell<Vec<Task>>>,
+}
+
+impl ManualExecutor {
+    /// Create an executor with no tasks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of spawned tasks that have not completed yet
+    pub fn pending(&self) -> usize {
+        self.tasks.borrow().len()
+    }
+
+    /// Poll tasks until none of them can make progress
+    ///
+    /// Returns the number of tasks that completed.
+    pub fn run_until_stalled(&self) -> usize {
+        /// Records whether any task asked to be polled again
+        struct FlagWaker(AtomicBool);
+
+        impl Wake for FlagWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut completed = 0;
+
+        loop {
+            flag.0.store(false, Ordering::SeqCst);
+            // Take the queue so tasks may spawn more tasks while being polled
+            let tasks = self.tasks.take();
+            let polled = tasks.len();
+            let mut still_pending = Vec::new();
+            for mut task in tasks {
+                if task.as_mut().poll(&mut cx).is_ready() {
+                    completed += 1;
+                } else {
+                    still_pending.push(task);
+                }
+            }
+            let spawned = std::mem::take(&mut *self.tasks.borrow_mut());
+            let progressed = still_pending.len() < polled || !spawned.is_empty();
+            still_pending.extend(spawned);
+            *self.tasks.borrow_mut() = still_pending;
+
+            if !progressed && !flag.0.load(Ordering::SeqCst) {
+                return completed;
+            }
+        }
+    }
+}
+
+impl Executor for ManualExecutor {
+    fn spawn(&self, task: Task) {
+        self.tasks.borrow_mut().push(task);
+    }
+}
+
+/// Cancellation flag shared between a fetch and the effect that started it
+#[derive(Clone, Default)]
+struct CancelHandle(Arc<Mutex<CancelState>>);
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    /// Waker of the last poll, woken on cancel
+    waker: Option<Waker>,
+}
+
+impl CancelHandle {
+    /// Mark the fetch cancelled and wake its task so it finishes
+    fn cancel(&self) {
+        let waker = match self.0.lock() {
+            Ok(mut state) => {
+                state.cancelled = true;
+                state.waker.take()
+            }
+            Err(_) => None,
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Future wrapper that resolves to `None` as soon as it is cancelled,
+/// dropping the inner future (and with it any request it owns)
+///
+/// Cancelling wakes the task, so this happens without waiting for the inner
+/// future to make progress.
+struct Cancellable<F> {
+    inner: Pin<Box<F>>,
+    cancel: CancelHandle,
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Ok(mut state) = self.cancel.0.lock() {
+            if state.cancelled {
+                return Poll::Ready(None);
+            }
+            state.waker = Some(cx.waker().clone());
+        }
+        self.inner.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// Snapshot of a resource
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceState<T, E> {
+    /// A fetch is in flight
+    Loading,
+    /// The latest fetch succeeded
+    Ready(T),
+    /// The latest fetch failed
+    Error(E),
+}
+
+/// Latest finished fetch, tagged with its generation
+type ResultSlot<T, E> = Arc<Mutex<Option<(u64, Result<T, E>)>>>;
+
+/// Async value derived from a reactive source
+///
+/// The fetch effects belong to the current reactive scope: disposing the
+/// scope stops refetching and cancels the in-flight request.
+#[derive(Clone)]
+pub struct Resource<T: Clone, E: Clone> {
+    value: Signal<Option<T>>,
+    error: Signal<Option<E>>,
+    loading: Signal<bool>,
+    refetch: Signal<u64>,
+}
+
+impl<T, E> Resource<T, E>
+where
+    T: Clone + MaybeSend + 'static,
+    E: Clone + MaybeSend + 'static,
+{
+    /// Create a resource fetched on the [`PlatformExecutor`]
+    pub fn new<S, Src, F, Fut>(source: Src, fetcher: F) -> Self
+    where
+        S: 'static,
+        Src: Fn() -> S + 'static,
+        F: Fn(S) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + MaybeSend + 'static,
+    {
+        Self::with_executor(source, fetcher, Rc::new(PlatformExecutor))
+    }
+
+    /// Create a resource fetched on a custom executor
+    pub fn with_executor<S, Src, F, Fut>(
+        source: Src,
+        fetcher: F,
+        executor: Rc<dyn Executor>,
+    ) -> Self
+    where
+        S: 'static,
+        Src: Fn() -> S + 'static,
+        F: Fn(S) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + MaybeSend + 'static,
+    {
+        let resource = Self {
+            value: Signal::new(None),
+            error: Signal::new(None),
+            loading: Signal::new_eq(false),
+            refetch: Signal::new(0),
+        };
+        let generation = Rc::new(Cell::new(0u64));
+        let slot: ResultSlot<T, E> = Arc::new(Mutex::new(None));
+        let completed = SyncSignal::new(0u64);
+
+        // Apply finished fetches on the UI thread, ignoring stale ones
+        Effect::new({
+            let (resource, generation, slot, completed) = (
+                resource.clone(),
+                generation.clone(),
+                slot.clone(),
+                completed.clone(),
+            );
+            move || {
+                completed.get();
+                let finished = slot.lock().ok().and_then(|mut slot| slot.take());
+                let Some((finished_generation, result)) = finished else {
+                    return;
+                };
+                if finished_generation != generation.get() {
+                    return;
+                }
+                untrack(|| {
+                    batch(|| {
+                        resource.loading.set(false);
+                        match result {
+                            Ok(value) => {
+                                resource.value.set(Some(value));
+                                resource.error.set(None);
+                            }
+                            Err(error) => resource.error.set(Some(error)),
+                        }
+                    })
+                });
+            }
+        });
+
+        // Start a fetch whenever the source changes or a refetch is requested
+        Effect::new({
+            let resource = resource.clone();
+            move || {
+                resource.refetch.get();
+                let input = source();
+                untrack(|| {
+                    let current = generation.get() + 1;
+                    generation.set(current);
+
+                    // Runs before the next fetch starts and when the scope is disposed
+                    let cancel = CancelHandle::default();
+                    on_cleanup({
+                        let cancel = cancel.clone();
+                        move || cancel.cancel()
+                    });
+
+                    resource.loading.set(true);
+                    let fetch = Cancellable {
+                        inner: Box::pin(fetcher(input)),
+                        cancel,
+                    };
+                    let (slot, completed) = (slot.clone(), completed.clone());
+                    executor.spawn(Box::pin(async move {
+                        let Some(result) = fetch.await else {
+                            return;
+                        };
+                        if let Ok(mut slot) = slot.lock() {
+                            // Never let an older fetch replace a newer result
+                            if slot.as_ref().is_some_and(|(g, _)| *g > current) {
+                                return;
+                            }
+                            *slot = Some((current, result));
+                        }
+                        completed.set(current);
+                    }));
+                });
+            }
+        });
+
+        resource
+    }
+
+    /// Whether a fetch is in flight
+    pub fn loading(&self) -> Signal<bool> {
+        self.loading.clone()
+    }
+
+    /// Error of the latest fetch, if it failed
+    pub fn error(&self) -> Signal<Option<E>> {
+        self.error.clone()
+    }
+
+    /// Value of the latest successful fetch
+    ///
+    /// The previous value stays available while a refetch is loading or
+    /// after it failed.
+    pub fn value(&self) -> Signal<Option<T>> {
+        self.value.clone()
+    }
+
+    /// Current state (tracks all three signals)
+    pub fn state(&self) -> ResourceState<T, E> {
+        if self.loading.get() {
+            return ResourceState::Loading;
+        }
+        match (self.error.get(), self.value.get()) {
+            (Some(error), _) => ResourceState::Error(error),
+            (None, Some(value)) => ResourceState::Ready(value),
+            (None, None) => ResourceState::Loading,
+        }
+    }
+
+    /// Fetch again with the current source value
+    pub fn refetch(&self) {
+        self.refetch.update(|n| *n += 1);
+    }
+}
+
+/// Create a resource fetched on the [`PlatformExecutor`]
+pub fn create_resource<T, E, S, Src, F, Fut>(source: Src, fetcher: F) -> Resource<T, E>
+where
+    T: Clone + MaybeSend + 'static,
+    E: Clone + MaybeSend + 'static,
+    S: 'static,
+    Src: Fn() -> S + 'static,
+    F: Fn(S) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + MaybeSend + 'static,
+{
+    Resource::new(source, fetcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::create_root;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Fetch results the test releases by hand, keyed by request
+    #[derive(Clone, Default)]
+    struct Responses(Arc<Mutex<HashMap<i32, Result<String, String>>>>);
+
+    impl Responses {
+        fn resolve(&self, key: i32, result: Result<String, String>) {
+            self.0.lock().unwrap().insert(key, result);
+        }
+
+        /// Future that stays pending until `key` is resolved
+        fn fetch(&self, key: i32) -> impl Future<Output = Result<String, String>> + Send {
+            let responses = self.0.clone();
+            std::future::poll_fn(move |_| match responses.lock().unwrap().remove(&key) {
+                Some(result) => Poll::Ready(result),
+                None => Poll::Pending,
+            })
+        }
+    }
+
+    fn setup(
+        source: &Signal<i32>,
+        responses: &Responses,
+        executor: &ManualExecutor,
+    ) -> Resource<String, String> {
+        let (source, responses) = (source.clone(), responses.clone());
+        Resource::with_executor(
+            move || source.get(),
+            move |key| responses.fetch(key),
+            Rc::new(executor.clone()),
+        )
+    }
+
+    #[test]
+    fn test_resource_loads_value() {
+        let source = Signal::new(1);
+        let responses = Responses::default();
+        let executor = ManualExecutor::new();
+        let resource = setup(&source, &responses, &executor);
+
+        assert!(resource.loading().get());
+        assert_eq!(resource.state(), ResourceState::Loading);
+
+        executor.run_until_stalled();
+        assert_eq!(executor.pending(), 1);
+
+        responses.resolve(1, Ok("one".to_string()));
+        assert_eq!(executor.run_until_stalled(), 1);
+        assert!(!resource.loading().get());
+        assert_eq!(resource.value().get(), Some("one".to_string()));
+        assert_eq!(resource.state(), ResourceState::Ready("one".to_string()));
+    }
+
+    #[test]
+    fn test_resource_error_keeps_previous_value() {
+        let source = Signal::new(1);
+        let responses = Responses::default();
+        let executor = ManualExecutor::new();
+        let resource = setup(&source, &responses, &executor);
+
+        responses.resolve(1, Ok("one".to_string()));
+        executor.run_until_stalled();
+
+        resource.refetch();
+        assert!(resource.loading().get());
+        responses.resolve(1, Err("offline".to_string()));
+        executor.run_until_stalled();
+
+        assert_eq!(resource.error().get(), Some("offline".to_string()));
+        assert_eq!(resource.value().get(), Some("one".to_string()));
+        assert_eq!(
+            resource.state(),
+            ResourceState::Error("offline".to_string())
+        );
+    }
+
+    #[test]
+    fn test_source_change_cancels_stale_fetch() {
+        let source = Signal::new(1);
+        let responses = Responses::default();
+        let executor = ManualExecutor::new();
+        let resource = setup(&source, &responses, &executor);
+
+        executor.run_until_stalled();
+        source.set(2);
+
+        // The first fetch was cancelled and is dropped on its next poll
+        executor.run_until_stalled();
+        assert_eq!(executor.pending(), 1);
+
+        responses.resolve(2, Ok("two".to_string()));
+        responses.resolve(1, Ok("one".to_string()));
+        executor.run_until_stalled();
+        assert_eq!(resource.value().get(), Some("two".to_string()));
+        assert!(!resource.loading().get());
+    }
+
+    #[test]
+    fn test_stale_result_is_ignored() {
+        let source = Signal::new(1);
+        let executor = ManualExecutor::new();
+
+        // Fetches that complete without ever being polled while pending
+        let resource = {
+            let source = source.clone();
+            Resource::with_executor(
+                move || source.get(),
+                |key| async move { Ok::<_, String>(key * 10) },
+                Rc::new(executor.clone()),
+            )
+        };
+        source.set(2);
+        executor.run_until_stalled();
+
+        assert_eq!(resource.value().get(), Some(20));
+        assert_eq!(executor.pending(), 0);
+    }
+
+    #[test]
+    fn test_disposing_scope_cancels_fetch() {
+        let source = Signal::new(1);
+        let responses = Responses::default();
+        let executor = ManualExecutor::new();
+
+        let (resource, root) = create_root(|root| (setup(&source, &responses, &executor), root));
+        root.dispose();
+
+        responses.resolve(1, Ok("one".to_string()));
+        executor.run_until_stalled();
+        assert_eq!(executor.pending(), 0);
+        assert_eq!(resource.value().get(), None);
+
+        // No refetch after disposal
+        source.set(2);
+        assert_eq!(executor.pending(), 0);
+    }
+
+    #[test]
+    fn test_cancel_wakes_the_fetch() {
+        struct CountingWaker(AtomicUsize);
+
+        impl Wake for CountingWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let cancel = CancelHandle::default();
+        let mut fetch = Box::pin(Cancellable {
+            inner: Box::pin(std::future::pending::<()>()),
+            cancel: cancel.clone(),
+        });
+
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+        assert!(fetch.as_mut().poll(&mut cx).is_pending());
+
+        cancel.cancel();
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(fetch.as_mut().poll(&mut cx), Poll::Ready(None));
+    }
+}
@@ -66,6 +66,9 @@ impl Route {
 }
 
 /// Router for managing routes and navigation
+///
+/// Clones share the same routes, history and listeners.
+#[derive(Clone)]
 pub struct Router {
     /// Registered routes
     routes: Arc<Mutex<Vec<Route>>>,
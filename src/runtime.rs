@@ -10,8 +10,10 @@
 //! Component State → Signal<T> → Update → Notify Runtime → Re-render → Patch DOM
 //! ```
 
-use crate::component::Component;
+use crate::component::{Component, ComponentRegistry};
 use crate::events::ComponentEventDispatcher;
+use crate::reactivity::{create_scope, Scope};
+use crate::reconciler::Reconciler;
 use crate::vdom::VNode;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -23,29 +25,89 @@ pub struct ComponentRuntime<C: Component> {
     #[allow(dead_code)]
     current_vnode: Rc<RefCell<Option<VNode>>>,
     event_dispatcher: Rc<RefCell<ComponentEventDispatcher>>,
+    /// Owns every effect, computed and cleanup created by the component
+    scope: Scope,
+    /// Child scope of the latest render, replaced on every re-render
+    render_scope: RefCell<Option<Scope>>,
+    /// Owns the child components, with the contexts provided by the latest
+    /// render
+    children: Scope,
+    /// Instances of the child components in the rendered tree
+    reconciler: RefCell<Option<Reconciler>>,
     #[cfg(target_arch = "wasm32")]
     root_element: Option<web_sys::Element>,
 }
 
 impl<C: Component + 'static> ComponentRuntime<C> {
     /// Create a new component runtime
+    ///
+    /// The runtime gets a reactive scope of its own, nested in the current
+    /// scope if there is one.
     pub fn new(component: C) -> Self {
+        let scope = create_scope(|scope| scope);
         Self {
             component: Rc::new(RefCell::new(component)),
             current_vnode: Rc::new(RefCell::new(None)),
             event_dispatcher: Rc::new(RefCell::new(ComponentEventDispatcher::new())),
+            children: scope.child(),
+            scope,
+            render_scope: RefCell::new(None),
+            reconciler: RefCell::new(None),
             #[cfg(target_arch = "wasm32")]
             root_element: None,
         }
     }
 
+    /// Expand child component nodes with the factories in `registry`
+    ///
+    /// Child instances live in the component's scope and are updated and
+    /// torn down on every render.
+    pub fn with_registry(self, registry: Rc<ComponentRegistry>) -> Self {
+        *self.reconciler.borrow_mut() = Some(Reconciler::new(registry));
+        self
+    }
+
+    /// Reactive scope owning the component's effects and cleanups
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Render the component inside a fresh render scope
+    ///
+    /// Effects created by the previous render are disposed first.
+    pub fn render(&self) -> VNode {
+        if let Some(previous) = self.render_scope.borrow_mut().take() {
+            previous.dispose();
+        }
+        let render_scope = self.scope.child();
+        let vnode = render_scope.run(|| self.component.borrow().render());
+        self.children.inherit_contexts(&render_scope);
+        *self.render_scope.borrow_mut() = Some(render_scope);
+        match self.reconciler.borrow_mut().as_mut() {
+            Some(reconciler) => self.children.run(|| reconciler.expand(&vnode)),
+            None => vnode,
+        }
+    }
+
+    /// Tear the component down, freeing its whole reactive subgraph
+    ///
+    /// Child components are cleaned up after the component itself.
+    pub fn unmount(&mut self) {
+        self.component.borrow_mut().cleanup();
+        if let Some(reconciler) = self.reconciler.borrow_mut().as_mut() {
+            reconciler.unmount();
+        }
+        self.scope.dispose();
+        *self.current_vnode.borrow_mut() = None;
+    }
+
     /// Mount the component to a DOM element
     #[cfg(target_arch = "wasm32")]
     pub fn mount(&mut self, target: web_sys::Element) -> Result<(), String> {
         use crate::renderer::WebRenderer;
 
         // Initial render
-        let vnode = self.component.borrow().render();
+        let vnode = self.render();
 
         // Create DOM from VNode
         let renderer = WebRenderer::new();
@@ -75,7 +137,7 @@ impl<C: Component + 'static> ComponentRuntime<C> {
         use crate::renderer::{Renderer, WebRenderer};
         use crate::vdom::diff;
 
-        let new_vnode = self.component.borrow().render();
+        let new_vnode = self.render();
 
         // Get the old VNode
         let old_vnode = self.current_vnode.borrow();
@@ -122,6 +184,12 @@ impl<C: Component + 'static> ComponentRuntime<C> {
     }
 }
 
+impl<C: Component> Drop for ComponentRuntime<C> {
+    fn drop(&mut self) {
+        self.scope.dispose();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,4 +230,143 @@ mod tests {
         runtime.dispatcher().borrow().dispatch("click").unwrap();
         assert!(*called.borrow());
     }
+
+    #[test]
+    fn test_render_scope_replaced_on_rerender() {
+        use crate::reactivity::on_cleanup;
+
+        struct CleanupComponent {
+            cleanups: Rc<RefCell<usize>>,
+        }
+
+        // SAFETY: test-only component used on a single thread
+        unsafe impl Send for CleanupComponent {}
+        unsafe impl Sync for CleanupComponent {}
+
+        impl Component for CleanupComponent {
+            fn render(&self) -> VNode {
+                let cleanups = self.cleanups.clone();
+                on_cleanup(move || *cleanups.borrow_mut() += 1);
+                VNode::Text(VText::new("row"))
+            }
+        }
+
+        let cleanups = Rc::new(RefCell::new(0));
+        let runtime = ComponentRuntime::new(CleanupComponent {
+            cleanups: cleanups.clone(),
+        });
+        runtime.render();
+        runtime.render();
+        assert_eq!(*cleanups.borrow(), 1);
+
+        drop(runtime);
+        assert_eq!(*cleanups.borrow(), 2);
+    }
+
+    #[test]
+    fn test_unmount_disposes_reactive_scope() {
+        use crate::reactivity::{on_cleanup, Effect, Signal};
+
+        let count = Signal::new(0);
+        let runs = Rc::new(RefCell::new(0));
+        let cleaned = Rc::new(RefCell::new(false));
+
+        let mut runtime = ComponentRuntime::new(TestComponent { count: 0 });
+        runtime.scope().run(|| {
+            let (count_c, runs_c) = (count.clone(), runs.clone());
+            Effect::new(move || {
+                let _ = count_c.get();
+                *runs_c.borrow_mut() += 1;
+            });
+            let cleaned_c = cleaned.clone();
+            on_cleanup(move || *cleaned_c.borrow_mut() = true);
+        });
+
+        count.set(1);
+        assert_eq!(*runs.borrow(), 2);
+
+        runtime.unmount();
+        assert!(*cleaned.borrow());
+        assert!(runtime.scope().is_disposed());
+        count.set(2);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_child_components_expand_and_unmount_with_parent() {
+        use crate::vdom::VComponent;
+        use std::sync::{Arc, Mutex};
+
+        struct Parent;
+
+        impl Component for Parent {
+            fn render(&self) -> VNode {
+                VElement::new("div")
+                    .child(VComponent::new("Child").into())
+                    .into()
+            }
+        }
+
+        struct Child(Arc<Mutex<Vec<&'static str>>>);
+
+        impl Component for Child {
+            fn init(&mut self) {
+                self.0.lock().unwrap().push("init");
+            }
+
+            fn cleanup(&mut self) {
+                self.0.lock().unwrap().push("cleanup");
+            }
+
+            fn render(&self) -> VNode {
+                VNode::Text(VText::new("child"))
+            }
+        }
+
+        let hooks = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = ComponentRegistry::new();
+        let hooks_c = hooks.clone();
+        registry.register("Child", move |_| Child(hooks_c.clone()));
+
+        let mut runtime = ComponentRuntime::new(Parent).with_registry(Rc::new(registry));
+        assert_eq!(
+            runtime.render(),
+            VElement::new("div").child("child".into()).into()
+        );
+        runtime.render();
+        assert_eq!(*hooks.lock().unwrap(), vec!["init"]);
+
+        runtime.unmount();
+        assert_eq!(*hooks.lock().unwrap(), vec!["init", "cleanup"]);
+    }
+
+    #[test]
+    fn test_child_components_read_context_provided_while_rendering() {
+        use crate::reactivity::{provide_context, use_context};
+        use crate::vdom::VComponent;
+
+        struct Parent;
+
+        impl Component for Parent {
+            fn render(&self) -> VNode {
+                provide_context("dark".to_string());
+                VComponent::new("Child").into()
+            }
+        }
+
+        struct Child;
+
+        impl Component for Child {
+            fn render(&self) -> VNode {
+                use_context::<String>().unwrap_or_default().into()
+            }
+        }
+
+        let mut registry = ComponentRegistry::new();
+        registry.register("Child", |_| Child);
+
+        let runtime = ComponentRuntime::new(Parent).with_registry(Rc::new(registry));
+        assert_eq!(runtime.render(), "dark".into());
+        assert_eq!(runtime.render(), "dark".into());
+    }
 }
@@ -0,0 +1,917 @@
+//! Timers and frame callbacks on every platform
+//!
+//! A [`Scheduler`] runs callbacks after a delay, at an interval or on the
+//! next frame, always on the UI thread:
+//! - web: `setTimeout`, `setInterval` and `requestAnimationFrame`
+//!   ([`WebScheduler`])
+//! - desktop: a helper thread or tokio task wakes the UI through a
+//!   [`SyncSignal`], and the callbacks run when the frame delivers sync
+//!   updates ([`NativeScheduler`])
+//! - tests: [`VirtualClock`], which only moves when told to
+//!
+//! The free functions use the thread's scheduler, which [`set_scheduler`]
+//! replaces. [`debounced`] and [`throttled`] build rate-limited signals on
+//! top of it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//! use std::time::Duration;
+//! use windjammer_ui::reactivity::Signal;
+//! use windjammer_ui::scheduler::{debounced, set_scheduler, VirtualClock};
+//!
+//! let clock = VirtualClock::new();
+//! set_scheduler(Rc::new(clock.clone()));
+//!
+//! let query = Signal::new(String::new());
+//! let search = debounced(&query, Duration::from_millis(250));
+//!
+//! query.set("wind".to_string());
+//! query.set("windjammer".to_string());
+//! clock.advance(Duration::from_millis(249));
+//! assert_eq!(search.get(), "");
+//! clock.advance(Duration::from_millis(1));
+//! assert_eq!(search.get(), "windjammer");
+//! ```
+//!
+//! [`SyncSignal`]: crate::sync_signal::SyncSignal
+
+use crate::reactivity::{on_cleanup, Effect, Signal};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Handle for cancelling a timer or frame callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(pub u64);
+
+/// Runs callbacks later on the current thread
+pub trait Scheduler {
+    /// Time on this scheduler's monotonic clock
+    fn now(&self) -> Duration;
+    /// Run `callback` once after `delay`
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId;
+    /// Run `callback` every `period` until cancelled
+    fn set_interval(&self, period: Duration, callback: Box<dyn FnMut()>) -> TimerId;
+    /// Run `callback` before the next frame is drawn
+    fn next_frame(&self, callback: Box<dyn FnOnce()>) -> TimerId;
+    /// Stop a timer or frame callback; does nothing if it already ran
+    fn cancel(&self, id: TimerId);
+}
+
+thread_local! {
+    static SCHEDULER: RefCell<Option<Rc<dyn Scheduler>>> = RefCell::new(None);
+}
+
+/// Use `scheduler` for timers started from now on, on this thread
+pub fn set_scheduler(scheduler: Rc<dyn Scheduler>) {
+    SCHEDULER.with(|slot| *slot.borrow_mut() = Some(scheduler));
+}
+
+/// Swap the thread's scheduler, returning the previous one
+///
+/// With `None` the default is created again on next use. Lets code that
+/// installs a scheduler temporarily, such as tests, put the old one back.
+pub fn replace_scheduler(scheduler: Option<Rc<dyn Scheduler>>) -> Option<Rc<dyn Scheduler>> {
+    SCHEDULER.with(|slot| std::mem::replace(&mut *slot.borrow_mut(), scheduler))
+}
+
+/// The thread's scheduler
+///
+/// Defaults to [`WebScheduler`] on wasm and [`NativeScheduler`] elsewhere.
+pub fn scheduler() -> Rc<dyn Scheduler> {
+    SCHEDULER.with(|slot| {
+        slot.borrow_mut()
+            .get_or_insert_with(default_scheduler)
+            .clone()
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default_scheduler() -> Rc<dyn Scheduler> {
+    Rc::new(WebScheduler::new())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_scheduler() -> Rc<dyn Scheduler> {
+    NativeScheduler::new()
+}
+
+/// Time on the thread's scheduler clock
+pub fn now() -> Duration {
+    scheduler().now()
+}
+
+/// Run `callback` once after `delay` on the thread's scheduler
+pub fn set_timeout(delay: Duration, callback: impl FnOnce() + 'static) -> TimerId {
+    scheduler().set_timeout(delay, Box::new(callback))
+}
+
+/// Run `callback` every `period` on the thread's scheduler
+pub fn set_interval(period: Duration, callback: impl FnMut() + 'static) -> TimerId {
+    scheduler().set_interval(period, Box::new(callback))
+}
+
+/// Run `callback` before the next frame on the thread's scheduler
+pub fn next_frame(callback: impl FnOnce() + 'static) -> TimerId {
+    scheduler().next_frame(Box::new(callback))
+}
+
+/// Cancel a timer started on the thread's scheduler
+pub fn cancel(id: TimerId) {
+    scheduler().cancel(id);
+}
+
+/// A signal that follows `source` once it has stopped changing for `delay`
+///
+/// Useful for search boxes: the query signal changes on every keystroke, the
+/// debounced one only when typing pauses.
+pub fn debounced<T: Clone + 'static>(source: &Signal<T>, delay: Duration) -> Signal<T> {
+    let output = Signal::new(source.get_untracked());
+    let scheduler = scheduler();
+    let first_run = Cell::new(true);
+    let (source, target) = (source.clone(), output.clone());
+    Effect::new(move || {
+        source.with(|_| ());
+        if first_run.replace(false) {
+            return;
+        }
+        let (source, target) = (source.clone(), target.clone());
+        let id = scheduler.set_timeout(delay, Box::new(move || target.set(source.get_untracked())));
+        // Cancelled when the source changes again or the owner is disposed
+        let scheduler = scheduler.clone();
+        on_cleanup(move || scheduler.cancel(id));
+    });
+    output
+}
+
+/// A signal that follows `source` at most once per `interval`
+///
+/// The first change goes through immediately; later changes within the
+/// interval are collapsed into one update when it ends.
+pub fn throttled<T: Clone + 'static>(source: &Signal<T>, interval: Duration) -> Signal<T> {
+    let output = Signal::new(source.get_untracked());
+    let throttle = Rc::new(Throttle {
+        source: source.clone(),
+        output: output.clone(),
+        interval,
+        scheduler: scheduler(),
+        window: Cell::new(None),
+        trailing: Cell::new(false),
+    });
+
+    let first_run = Cell::new(true);
+    let (source, effect_throttle) = (source.clone(), throttle.clone());
+    Effect::new(move || {
+        source.with(|_| ());
+        if first_run.replace(false) {
+            return;
+        }
+        if effect_throttle.window.get().is_some() {
+            effect_throttle.trailing.set(true);
+        } else {
+            effect_throttle.emit();
+        }
+    });
+    on_cleanup(move || {
+        if let Some(id) = throttle.window.take() {
+            throttle.scheduler.cancel(id);
+        }
+    });
+    output
+}
+
+struct Throttle<T: Clone + 'static> {
+    source: Signal<T>,
+    output: Signal<T>,
+    interval: Duration,
+    scheduler: Rc<dyn Scheduler>,
+    /// Timer closing the current interval, if one is open
+    window: Cell<Option<TimerId>>,
+    /// Whether the source changed during the current interval
+    trailing: Cell<bool>,
+}
+
+impl<T: Clone + 'static> Throttle<T> {
+    /// Copy the source to the output and open a new interval
+    fn emit(self: &Rc<Self>) {
+        self.output.set(self.source.get_untracked());
+        let throttle = self.clone();
+        let id = self.scheduler.set_timeout(
+            self.interval,
+            Box::new(move || {
+                throttle.window.set(None);
+                if throttle.trailing.replace(false) {
+                    throttle.emit();
+                }
+            }),
+        );
+        self.window.set(Some(id));
+    }
+}
+
+/// Timer callback stored in a [`TimerQueue`]
+enum Callback {
+    Once(Box<dyn FnOnce()>),
+    Repeat(Duration, Box<dyn FnMut()>),
+}
+
+/// Frame callback waiting in a [`TimerQueue`], with its id
+type Frame = (u64, Box<dyn FnOnce()>);
+
+/// Timers ordered by deadline, plus frame callbacks
+///
+/// Shared by [`NativeScheduler`] and [`VirtualClock`], which differ only in
+/// where the time comes from and what drives them.
+#[derive(Default)]
+struct TimerQueue {
+    next_id: Cell<u64>,
+    /// Keyed by deadline, then id so equal deadlines run in start order
+    timers: RefCell<BTreeMap<(Duration, u64), Callback>>,
+    /// Deadline of every live timer, including a running interval
+    deadlines: RefCell<HashMap<u64, Duration>>,
+    frames: RefCell<Vec<Frame>>,
+}
+
+impl TimerQueue {
+    fn next_id(&self) -> u64 {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        id
+    }
+
+    fn add(&self, deadline: Duration, callback: Callback) -> TimerId {
+        let id = self.next_id();
+        self.insert(id, deadline, callback);
+        TimerId(id)
+    }
+
+    fn insert(&self, id: u64, deadline: Duration, callback: Callback) {
+        self.timers.borrow_mut().insert((deadline, id), callback);
+        self.deadlines.borrow_mut().insert(id, deadline);
+    }
+
+    fn add_frame(&self, callback: Box<dyn FnOnce()>) -> TimerId {
+        let id = self.next_id();
+        self.frames.borrow_mut().push((id, callback));
+        TimerId(id)
+    }
+
+    fn cancel(&self, TimerId(id): TimerId) {
+        if let Some(deadline) = self.deadlines.borrow_mut().remove(&id) {
+            self.timers.borrow_mut().remove(&(deadline, id));
+        }
+        self.frames.borrow_mut().retain(|(frame, _)| *frame != id);
+    }
+
+    fn len(&self) -> usize {
+        self.deadlines.borrow().len() + self.frames.borrow().len()
+    }
+
+    /// Run the frame callbacks queued so far
+    fn run_frames(&self) -> usize {
+        let frames = self.frames.take();
+        let count = frames.len();
+        for (_, callback) in frames {
+            callback();
+        }
+        count
+    }
+
+    /// Run the earliest timer due at or before `limit`
+    ///
+    /// `set_now` is called with the timer's deadline before it runs. Returns
+    /// false when nothing is due.
+    fn run_next(&self, limit: Duration, set_now: impl FnOnce(Duration)) -> bool {
+        let next = {
+            let mut timers = self.timers.borrow_mut();
+            match timers.keys().next().copied() {
+                Some(key) if key.0 <= limit => timers.remove(&key).map(|callback| (key, callback)),
+                _ => None,
+            }
+        };
+        let Some(((deadline, id), callback)) = next else {
+            return false;
+        };
+        set_now(deadline);
+        match callback {
+            Callback::Once(callback) => {
+                self.deadlines.borrow_mut().remove(&id);
+                callback();
+            }
+            Callback::Repeat(period, mut callback) => {
+                callback();
+                // Unless the callback cancelled it
+                if self.deadlines.borrow().contains_key(&id) {
+                    self.insert(id, deadline + period, Callback::Repeat(period, callback));
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Intervals shorter than this would never let time advance
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+/// Scheduler driven by the host's frame loop on native targets
+///
+/// Callbacks run on the thread that created the scheduler, inside
+/// [`flush_sync_updates`]. When a timer is due, a helper (a tokio task if a
+/// runtime is running, otherwise one long-lived thread per scheduler) writes
+/// a [`SyncSignal`], which wakes the UI through the waker set with
+/// [`set_sync_waker`]; the desktop apps flush on every frame.
+///
+/// [`flush_sync_updates`]: crate::sync_signal::flush_sync_updates
+/// [`set_sync_waker`]: crate::sync_signal::set_sync_waker
+/// [`SyncSignal`]: crate::sync_signal::SyncSignal
+#[cfg(not(target_arch = "wasm32"))]
+pub struct NativeScheduler {
+    queue: TimerQueue,
+    start: std::time::Instant,
+    tick: crate::sync_signal::SyncSignal<u64>,
+    /// Earliest deadline a helper is already waiting for
+    wake_at: Cell<Option<Duration>>,
+    /// Thread waking the scheduler when no tokio runtime is running
+    helper: std::cell::OnceCell<WakeHelper>,
+}
+
+/// Deadline shared with the helper thread of a [`NativeScheduler`]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct WakeState {
+    deadline: Option<std::time::Instant>,
+    stopped: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Default)]
+struct WakeHelper(std::sync::Arc<(std::sync::Mutex<WakeState>, std::sync::Condvar)>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WakeHelper {
+    /// Start a thread calling `ring` at each deadline until stopped
+    fn spawn(ring: impl Fn() + Send + 'static) -> Self {
+        let helper = Self::default();
+        let shared = helper.clone();
+        std::thread::spawn(move || {
+            let condvar = &shared.0 .1;
+            let mut state = shared.lock();
+            while !state.stopped {
+                let now = std::time::Instant::now();
+                state = match state.deadline {
+                    Some(at) if at <= now => {
+                        state.deadline = None;
+                        drop(state);
+                        ring();
+                        shared.lock()
+                    }
+                    Some(at) => {
+                        condvar
+                            .wait_timeout(state, at - now)
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .0
+                    }
+                    None => condvar
+                        .wait(state)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner()),
+                };
+            }
+        });
+        helper
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, WakeState> {
+        self.0
+             .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Ring at `at`, or earlier if an earlier deadline is already set
+    fn wake_at(&self, at: std::time::Instant) {
+        let mut state = self.lock();
+        state.deadline = Some(state.deadline.map_or(at, |deadline| deadline.min(at)));
+        self.0 .1.notify_one();
+    }
+
+    fn stop(&self) {
+        self.lock().stopped = true;
+        self.0 .1.notify_one();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeScheduler {
+    /// Create a scheduler for the current thread
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Rc<Self> {
+        use crate::reactivity::{create_root, untrack};
+        use crate::sync_signal::SyncSignal;
+
+        let scheduler = Rc::new(Self {
+            queue: TimerQueue::default(),
+            start: std::time::Instant::now(),
+            tick: SyncSignal::new(0),
+            wake_at: Cell::new(None),
+            helper: std::cell::OnceCell::new(),
+        });
+        let weak = Rc::downgrade(&scheduler);
+        let tick = scheduler.tick.clone();
+        create_root(|_| {
+            Effect::new(move || {
+                if tick.get() == 0 {
+                    return;
+                }
+                if let Some(scheduler) = weak.upgrade() {
+                    untrack(|| scheduler.run_due());
+                }
+            });
+        });
+        scheduler
+    }
+
+    /// Run the pending frame callbacks and the timers that are due
+    ///
+    /// Called automatically when the helper wakes the UI thread.
+    pub fn run_due(&self) {
+        self.wake_at.set(None);
+        self.queue.run_frames();
+        while self.queue.run_next(self.now(), |_| {}) {}
+        let next_deadline = self.queue.timers.borrow().keys().next().map(|key| key.0);
+        if !self.queue.frames.borrow().is_empty() {
+            self.wake(Duration::ZERO);
+        } else if let Some(deadline) = next_deadline {
+            self.wake(deadline);
+        }
+    }
+
+    /// Make sure a helper wakes this thread at `deadline`
+    fn wake(&self, deadline: Duration) {
+        if self.wake_at.get().is_some_and(|at| at <= deadline) {
+            return;
+        }
+        self.wake_at.set(Some(deadline));
+        let tick = self.tick.clone();
+        let ring = move || tick.update(|tick| *tick += 1);
+
+        #[cfg(feature = "tokio")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let delay = deadline.saturating_sub(self.now());
+            handle.spawn(async move {
+                tokio::time::sleep(delay).await;
+                ring();
+            });
+            return;
+        }
+        self.helper
+            .get_or_init(|| WakeHelper::spawn(ring))
+            .wake_at(self.start + deadline);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for NativeScheduler {
+    fn drop(&mut self) {
+        if let Some(helper) = self.helper.get() {
+            helper.stop();
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Scheduler for NativeScheduler {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let deadline = self.now() + delay;
+        let id = self.queue.add(deadline, Callback::Once(callback));
+        self.wake(deadline);
+        id
+    }
+
+    fn set_interval(&self, period: Duration, callback: Box<dyn FnMut()>) -> TimerId {
+        let period = period.max(MIN_PERIOD);
+        let deadline = self.now() + period;
+        let id = self.queue.add(deadline, Callback::Repeat(period, callback));
+        self.wake(deadline);
+        id
+    }
+
+    fn next_frame(&self, callback: Box<dyn FnOnce()>) -> TimerId {
+        let id = self.queue.add_frame(callback);
+        self.wake(self.now());
+        id
+    }
+
+    fn cancel(&self, id: TimerId) {
+        self.queue.cancel(id);
+    }
+}
+
+/// Scheduler backed by the browser's timers
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+pub struct WebScheduler {
+    next_id: Cell<u64>,
+    timers: Rc<RefCell<HashMap<u64, WebTimer>>>,
+}
+
+/// Browser handle of a [`WebScheduler`] timer and the closure it calls
+///
+/// The closure is dropped when the timer finishes or is cancelled, which is
+/// safe even from inside the closure: the browser keeps it alive until the
+/// call returns.
+#[cfg(target_arch = "wasm32")]
+struct WebTimer {
+    kind: WebTimerKind,
+    handle: i32,
+    _closure: wasm_bindgen::closure::Closure<dyn FnMut()>,
+}
+
+#[cfg(target_arch = "wasm32")]
+enum WebTimerKind {
+    Timeout,
+    Interval,
+    Frame,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start(
+        &self,
+        kind: WebTimerKind,
+        callback: Box<dyn FnMut()>,
+        register: impl FnOnce(&web_sys::Window, &js_sys::Function) -> Result<i32, wasm_bindgen::JsValue>,
+    ) -> TimerId {
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        let repeat = matches!(kind, WebTimerKind::Interval);
+
+        let timers = Rc::downgrade(&self.timers);
+        let mut callback = callback;
+        let closure = Closure::wrap(Box::new(move || {
+            callback();
+            // Intervals keep running unless the callback cancelled them
+            if !repeat {
+                if let Some(timers) = timers.upgrade() {
+                    timers.borrow_mut().remove(&id);
+                }
+            }
+        }) as Box<dyn FnMut()>);
+
+        let window = web_sys::window().expect("no global window");
+        match register(&window, closure.as_ref().unchecked_ref()) {
+            Ok(handle) => {
+                self.timers.borrow_mut().insert(
+                    id,
+                    WebTimer {
+                        kind,
+                        handle,
+                        _closure: closure,
+                    },
+                );
+            }
+            Err(error) => {
+                crate::component_runtime::console_error(&format!(
+                    "failed to start timer: {:?}",
+                    error
+                ));
+            }
+        }
+        TimerId(id)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Scheduler for WebScheduler {
+    fn now(&self) -> Duration {
+        let millis = web_sys::window()
+            .and_then(|window| window.performance())
+            .map_or_else(js_sys::Date::now, |performance| performance.now());
+        Duration::from_secs_f64(millis / 1000.0)
+    }
+
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let mut callback = Some(callback);
+        self.start(
+            WebTimerKind::Timeout,
+            Box::new(move || {
+                if let Some(callback) = callback.take() {
+                    callback();
+                }
+            }),
+            |window, function| {
+                window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    function,
+                    delay.as_millis().min(i32::MAX as u128) as i32,
+                )
+            },
+        )
+    }
+
+    fn set_interval(&self, period: Duration, callback: Box<dyn FnMut()>) -> TimerId {
+        let period = period.max(MIN_PERIOD);
+        self.start(WebTimerKind::Interval, callback, |window, function| {
+            window.set_interval_with_callback_and_timeout_and_arguments_0(
+                function,
+                period.as_millis().min(i32::MAX as u128) as i32,
+            )
+        })
+    }
+
+    fn next_frame(&self, callback: Box<dyn FnOnce()>) -> TimerId {
+        let mut callback = Some(callback);
+        self.start(
+            WebTimerKind::Frame,
+            Box::new(move || {
+                if let Some(callback) = callback.take() {
+                    callback();
+                }
+            }),
+            |window, function| window.request_animation_frame(function),
+        )
+    }
+
+    fn cancel(&self, TimerId(id): TimerId) {
+        let Some(timer) = self.timers.borrow_mut().remove(&id) else {
+            return;
+        };
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        match timer.kind {
+            WebTimerKind::Timeout => window.clear_timeout_with_handle(timer.handle),
+            WebTimerKind::Interval => window.clear_interval_with_handle(timer.handle),
+            WebTimerKind::Frame => {
+                let _ = window.cancel_animation_frame(timer.handle);
+            }
+        }
+    }
+}
+
+/// Manually advanced clock for deterministic tests
+///
+/// Time starts at zero and only moves with [`advance`](Self::advance);
+/// timers run in deadline order, each seeing [`Scheduler::now`] equal to its
+/// deadline. Frame callbacks run on [`run_frame`](Self::run_frame). Clones
+/// share the same clock.
+#[derive(Clone, Default)]
+pub struct VirtualClock {
+    inner: Rc<ClockInner>,
+}
+
+#[derive(Default)]
+struct ClockInner {
+    now: Cell<Duration>,
+    queue: TimerQueue,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move time forward by `by`, running every timer that comes due
+    pub fn advance(&self, by: Duration) {
+        let target = self.inner.now.get() + by;
+        while self
+            .inner
+            .queue
+            .run_next(target, |deadline| self.inner.now.set(deadline))
+        {}
+        self.inner.now.set(target);
+    }
+
+    /// Run the frame callbacks queued so far; returns how many ran
+    pub fn run_frame(&self) -> usize {
+        self.inner.queue.run_frames()
+    }
+
+    /// Number of timers and frame callbacks waiting to run
+    pub fn pending(&self) -> usize {
+        self.inner.queue.len()
+    }
+}
+
+impl Scheduler for VirtualClock {
+    fn now(&self) -> Duration {
+        self.inner.now.get()
+    }
+
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        self.inner
+            .queue
+            .add(self.now() + delay, Callback::Once(callback))
+    }
+
+    fn set_interval(&self, period: Duration, callback: Box<dyn FnMut()>) -> TimerId {
+        let period = period.max(MIN_PERIOD);
+        self.inner
+            .queue
+            .add(self.now() + period, Callback::Repeat(period, callback))
+    }
+
+    fn next_frame(&self, callback: Box<dyn FnOnce()>) -> TimerId {
+        self.inner.queue.add_frame(callback)
+    }
+
+    fn cancel(&self, id: TimerId) {
+        self.inner.queue.cancel(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::create_root;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn log() -> Rc<RefCell<Vec<String>>> {
+        Rc::new(RefCell::new(Vec::new()))
+    }
+
+    #[test]
+    fn test_virtual_clock_runs_timers_in_deadline_order() {
+        let clock = VirtualClock::new();
+        let events = log();
+
+        for (name, delay) in [("b", 20), ("a", 10), ("c", 20)] {
+            let (events, clock_c) = (events.clone(), clock.clone());
+            clock.set_timeout(
+                ms(delay),
+                Box::new(move || {
+                    events
+                        .borrow_mut()
+                        .push(format!("{name}@{:?}", clock_c.now()))
+                }),
+            );
+        }
+        let cancelled = {
+            let events = events.clone();
+            clock.set_timeout(
+                ms(5),
+                Box::new(move || events.borrow_mut().push("x".into())),
+            )
+        };
+        clock.cancel(cancelled);
+
+        clock.advance(ms(15));
+        assert_eq!(*events.borrow(), vec!["a@10ms"]);
+        clock.advance(ms(100));
+        assert_eq!(*events.borrow(), vec!["a@10ms", "b@20ms", "c@20ms"]);
+        assert_eq!(clock.now(), ms(115));
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn test_intervals_repeat_until_cancelled() {
+        let clock = VirtualClock::new();
+        let ticks = Rc::new(Cell::new(0));
+        let id = Rc::new(Cell::new(None));
+
+        let interval = clock.set_interval(ms(10), {
+            let (ticks, id, clock) = (ticks.clone(), id.clone(), clock.clone());
+            Box::new(move || {
+                ticks.set(ticks.get() + 1);
+                if ticks.get() == 3 {
+                    clock.cancel(id.get().unwrap());
+                }
+            })
+        });
+        id.set(Some(interval));
+
+        clock.advance(ms(25));
+        assert_eq!(ticks.get(), 2);
+        clock.advance(ms(100));
+        assert_eq!(ticks.get(), 3);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn test_frame_callbacks_run_on_the_next_frame() {
+        let clock = VirtualClock::new();
+        let events = log();
+
+        let events_c = events.clone();
+        let clock_c = clock.clone();
+        clock.next_frame(Box::new(move || {
+            events_c.borrow_mut().push("first".into());
+            let events = events_c.clone();
+            clock_c.next_frame(Box::new(move || events.borrow_mut().push("second".into())));
+        }));
+        let skipped = clock.next_frame(Box::new(|| panic!("cancelled")));
+        clock.cancel(skipped);
+
+        assert_eq!(clock.run_frame(), 1);
+        assert_eq!(*events.borrow(), vec!["first"]);
+        assert_eq!(clock.run_frame(), 1);
+        assert_eq!(*events.borrow(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_debounced_waits_for_quiet() {
+        let clock = VirtualClock::new();
+        set_scheduler(Rc::new(clock.clone()));
+
+        let query = Signal::new(0);
+        let search = debounced(&query, ms(100));
+        query.set(1);
+        clock.advance(ms(60));
+        query.set(2);
+        clock.advance(ms(60));
+        assert_eq!(search.get(), 0);
+
+        clock.advance(ms(40));
+        assert_eq!(search.get(), 2);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn test_throttled_emits_leading_and_trailing() {
+        let clock = VirtualClock::new();
+        set_scheduler(Rc::new(clock.clone()));
+
+        let (scope, (position, throttled)) = create_root(|scope| {
+            let position = Signal::new(0);
+            let throttled = throttled(&position, ms(100));
+            (scope, (position, throttled))
+        });
+
+        position.set(1);
+        assert_eq!(throttled.get(), 1);
+        position.set(2);
+        position.set(3);
+        assert_eq!(throttled.get(), 1);
+
+        clock.advance(ms(100));
+        assert_eq!(throttled.get(), 3);
+        clock.advance(ms(100));
+        position.set(4);
+        assert_eq!(throttled.get(), 4);
+
+        position.set(5);
+        scope.dispose();
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn test_native_scheduler_wakes_through_sync_updates() {
+        use crate::sync_signal::flush_sync_updates;
+
+        let native = NativeScheduler::new();
+        let events = log();
+        let events_c = events.clone();
+        native.set_timeout(
+            ms(10),
+            Box::new(move || events_c.borrow_mut().push("timeout".into())),
+        );
+        let events_c = events.clone();
+        native.next_frame(Box::new(move || events_c.borrow_mut().push("frame".into())));
+        assert!(events.borrow().is_empty());
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while events.borrow().len() < 2 && std::time::Instant::now() < deadline {
+            std::thread::sleep(ms(1));
+            flush_sync_updates();
+        }
+        assert_eq!(*events.borrow(), vec!["frame", "timeout"]);
+    }
+
+    #[test]
+    fn test_native_helper_moves_to_earlier_deadlines() {
+        use crate::sync_signal::flush_sync_updates;
+
+        let native = NativeScheduler::new();
+        let events = log();
+        for (name, delay) in [("late", ms(2_000)), ("early", ms(5))] {
+            let events_c = events.clone();
+            native.set_timeout(
+                delay,
+                Box::new(move || events_c.borrow_mut().push(name.into())),
+            );
+        }
+
+        // The helper waiting for the late timer is woken for the early one
+        let deadline = std::time::Instant::now() + ms(1_000);
+        while events.borrow().is_empty() && std::time::Instant::now() < deadline {
+            std::thread::sleep(ms(1));
+            flush_sync_updates();
+        }
+        assert_eq!(*events.borrow(), vec!["early"]);
+    }
+}
@@ -0,0 +1,985 @@
+//! Reactive collections that report what changed
+//!
+//! A `Signal<Vec<T>>` can only say "the whole vector changed": readers clone
+//! it and lists rebuild every row. [`SignalVec`] and [`SignalMap`] keep their
+//! values in place and describe each mutation as a [`VecDiff`] or [`MapDiff`].
+//!
+//! Reads (`len`, `get`, `with`) are tracked like any signal read, so effects
+//! still re-run on any change. Listeners registered with `subscribe` receive
+//! the individual diffs instead. [`map_keyed`] builds on that to keep one
+//! rendered [`VNode`] per item and turn each diff into a keyed
+//! [`ListChange`], re-rendering only the rows that changed.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::signal_collections::{map_keyed, ListChange, SignalVec};
+//! use windjammer_ui::vdom::{VElement, VNode};
+//!
+//! let todos = SignalVec::from_vec(vec![(1, "write docs"), (2, "ship it")]);
+//! let rows = map_keyed(
+//!     &todos,
+//!     |todo| todo.0,
+//!     |todo| VElement::new("li").child(VNode::from(todo.1)).into(),
+//! );
+//!
+//! todos.push((3, "celebrate"));
+//! let changes = rows.take_changes();
+//! assert!(matches!(changes[0], ListChange::Insert { index: 2, key: 3, .. }));
+//! ```
+
+use crate::reactivity::{batch, create_root, on_cleanup, untrack, Scope, Signal};
+use crate::vdom::VNode;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::{Rc, Weak};
+
+/// Identifier returned by `subscribe`, used to unsubscribe
+pub type SubscriptionId = usize;
+
+/// A single change to a [`SignalVec`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum VecDiff<T> {
+    /// `value` was inserted at `index`
+    Insert { index: usize, value: T },
+    /// The item at `index` was removed
+    Remove { index: usize },
+    /// The item at `from` was moved so that it now sits at `to`
+    Move { from: usize, to: usize },
+    /// The item at `index` was replaced by `value`
+    Update { index: usize, value: T },
+    /// All items were removed
+    Clear,
+}
+
+/// A single change to a [`SignalMap`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapDiff<K, V> {
+    /// A new entry was added
+    Insert { key: K, value: V },
+    /// An existing entry got a new value
+    Update { key: K, value: V },
+    /// An entry was removed
+    Remove { key: K },
+    /// All entries were removed
+    Clear,
+}
+
+type Listener<D> = Rc<dyn Fn(&D)>;
+
+/// Diff listeners shared by both collections
+struct Listeners<D> {
+    next_id: Cell<SubscriptionId>,
+    list: RefCell<Vec<(SubscriptionId, Listener<D>)>>,
+}
+
+impl<D> Listeners<D> {
+    fn new() -> Self {
+        Self {
+            next_id: Cell::new(0),
+            list: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn add(&self, listener: Listener<D>) -> SubscriptionId {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.list.borrow_mut().push((id, listener));
+        id
+    }
+
+    fn remove(&self, id: SubscriptionId) {
+        self.list.borrow_mut().retain(|(other, _)| *other != id);
+    }
+
+    fn emit(&self, diff: &D) {
+        // Listeners may subscribe or unsubscribe while being notified
+        let listeners: Vec<_> = self
+            .list
+            .borrow()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        // Signals read by a listener must not become dependencies of the
+        // effect that happened to perform the mutation
+        untrack(|| {
+            for listener in listeners {
+                listener(diff);
+            }
+        });
+    }
+}
+
+/// Borrow a collection for a write, refusing it while the collection is
+/// being read by reference
+///
+/// A refused write is reported as [`ReactiveError::ReentrantWrite`] on the
+/// collection's trigger, like a write to a borrowed [`Signal`].
+///
+/// [`ReactiveError::ReentrantWrite`]: crate::reactivity::ReactiveError::ReentrantWrite
+fn write<C, R>(cell: &RefCell<C>, trigger: &Signal<()>, f: impl FnOnce(&mut C) -> R) -> Option<R> {
+    match cell.try_borrow_mut() {
+        Ok(mut value) => Some(f(&mut value)),
+        Err(_) => {
+            trigger.report_reentrant_write();
+            None
+        }
+    }
+}
+
+struct VecInner<T> {
+    values: RefCell<Vec<T>>,
+    trigger: Signal<()>,
+    listeners: Listeners<VecDiff<T>>,
+}
+
+/// Reactive vector that emits a [`VecDiff`] for every mutation
+pub struct SignalVec<T> {
+    inner: Rc<VecInner<T>>,
+}
+
+impl<T> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Clone + 'static> Default for SignalVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + 'static> SignalVec<T> {
+    /// Create an empty vector
+    pub fn new() -> Self {
+        Self::from_vec(Vec::new())
+    }
+
+    /// Create a vector holding `values`
+    pub fn from_vec(values: Vec<T>) -> Self {
+        Self {
+            inner: Rc::new(VecInner {
+                values: RefCell::new(values),
+                trigger: Signal::new(()).labeled("SignalVec"),
+                listeners: Listeners::new(),
+            }),
+        }
+    }
+
+    /// Number of items (tracks dependency)
+    pub fn len(&self) -> usize {
+        self.with(|values| values.len())
+    }
+
+    /// Whether the vector is empty (tracks dependency)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clone the item at `index` (tracks dependency)
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.with(|values| values.get(index).cloned())
+    }
+
+    /// Clone all items (tracks dependency)
+    pub fn to_vec(&self) -> Vec<T> {
+        self.with(|values| values.to_vec())
+    }
+
+    /// Read the items by reference (tracks dependency)
+    pub fn with<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.inner.trigger.get();
+        self.with_untracked(f)
+    }
+
+    /// Read the items by reference without tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        f(&self.inner.values.borrow())
+    }
+
+    /// Append an item
+    pub fn push(&self, value: T) {
+        let Some(index) = self.write(|values| {
+            values.push(value.clone());
+            values.len() - 1
+        }) else {
+            return;
+        };
+        self.emit(VecDiff::Insert { index, value });
+    }
+
+    /// Insert an item at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&self, index: usize, value: T) {
+        if self
+            .write(|values| values.insert(index, value.clone()))
+            .is_some()
+        {
+            self.emit(VecDiff::Insert { index, value });
+        }
+    }
+
+    /// Remove and return the last item
+    pub fn pop(&self) -> Option<T> {
+        let (index, value) = self.write(|values| {
+            let value = values.pop()?;
+            Some((values.len(), value))
+        })??;
+        self.emit(VecDiff::Remove { index });
+        Some(value)
+    }
+
+    /// Remove and return the item at `index`
+    ///
+    /// Returns `None` if the vector is being read and the write was refused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: usize) -> Option<T> {
+        let value = self.write(|values| values.remove(index))?;
+        self.emit(VecDiff::Remove { index });
+        Some(value)
+    }
+
+    /// Move the item at `from` so that it ends up at `to`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn move_item(&self, from: usize, to: usize) {
+        let moved = self.write(|values| {
+            assert!(
+                from < values.len() && to < values.len(),
+                "move_item({from}, {to}) out of bounds for length {}",
+                values.len()
+            );
+            if from == to {
+                return false;
+            }
+            let value = values.remove(from);
+            values.insert(to, value);
+            true
+        });
+        if moved == Some(true) {
+            self.emit(VecDiff::Move { from, to });
+        }
+    }
+
+    /// Replace the item at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, value: T) {
+        if self.write(|values| values[index] = value.clone()).is_some() {
+            self.emit(VecDiff::Update { index, value });
+        }
+    }
+
+    /// Modify the item at `index` in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn update(&self, index: usize, f: impl FnOnce(&mut T)) {
+        let Some(value) = self.write(|values| {
+            f(&mut values[index]);
+            values[index].clone()
+        }) else {
+            return;
+        };
+        self.emit(VecDiff::Update { index, value });
+    }
+
+    /// Remove all items
+    pub fn clear(&self) {
+        let cleared = self.write(|values| {
+            let had_items = !values.is_empty();
+            values.clear();
+            had_items
+        });
+        if cleared == Some(true) {
+            self.emit(VecDiff::Clear);
+        }
+    }
+
+    /// Replace all items, emitting a clear followed by one insert per item
+    pub fn replace(&self, values: Vec<T>) {
+        batch(|| {
+            self.clear();
+            for value in values {
+                self.push(value);
+            }
+        });
+    }
+
+    /// Keep only the items for which `keep` returns true
+    pub fn retain(&self, mut keep: impl FnMut(&T) -> bool) {
+        batch(|| {
+            let mut index = 0;
+            while index < self.inner.values.borrow().len() {
+                let keep_item = keep(&self.inner.values.borrow()[index]);
+                if keep_item {
+                    index += 1;
+                } else if self.remove(index).is_none() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Call `listener` with every future diff
+    ///
+    /// Inside a scope the listener is removed when the scope is disposed.
+    pub fn subscribe(&self, listener: impl Fn(&VecDiff<T>) + 'static) -> SubscriptionId {
+        let id = self.inner.listeners.add(Rc::new(listener));
+        let weak = Rc::downgrade(&self.inner);
+        on_cleanup(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.listeners.remove(id);
+            }
+        });
+        id
+    }
+
+    /// Stop delivering diffs to a listener
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.inner.listeners.remove(id);
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut Vec<T>) -> R) -> Option<R> {
+        write(&self.inner.values, &self.inner.trigger, f)
+    }
+
+    fn emit(&self, diff: VecDiff<T>) {
+        self.inner.listeners.emit(&diff);
+        self.inner.trigger.set(());
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SignalVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SignalVec")
+            .field(&*self.inner.values.borrow())
+            .finish()
+    }
+}
+
+struct MapInner<K, V> {
+    entries: RefCell<HashMap<K, V>>,
+    trigger: Signal<()>,
+    listeners: Listeners<MapDiff<K, V>>,
+}
+
+/// Reactive map that emits a [`MapDiff`] for every mutation
+pub struct SignalMap<K, V> {
+    inner: Rc<MapInner<K, V>>,
+}
+
+impl<K, V> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static, V: Clone + 'static> Default for SignalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static, V: Clone + 'static> SignalMap<K, V> {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(MapInner {
+                entries: RefCell::new(HashMap::new()),
+                trigger: Signal::new(()).labeled("SignalMap"),
+                listeners: Listeners::new(),
+            }),
+        }
+    }
+
+    /// Number of entries (tracks dependency)
+    pub fn len(&self) -> usize {
+        self.with(|entries| entries.len())
+    }
+
+    /// Whether the map is empty (tracks dependency)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clone the value for `key` (tracks dependency)
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.with(|entries| entries.get(key).cloned())
+    }
+
+    /// Whether `key` is present (tracks dependency)
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.with(|entries| entries.contains_key(key))
+    }
+
+    /// Read the entries by reference (tracks dependency)
+    pub fn with<R>(&self, f: impl FnOnce(&HashMap<K, V>) -> R) -> R {
+        self.inner.trigger.get();
+        self.with_untracked(f)
+    }
+
+    /// Read the entries by reference without tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&HashMap<K, V>) -> R) -> R {
+        f(&self.inner.entries.borrow())
+    }
+
+    /// Insert or overwrite an entry, returning the previous value
+    ///
+    /// Nothing is inserted while the map is being read.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let previous = self.write(|entries| entries.insert(key.clone(), value.clone()))?;
+        let diff = if previous.is_some() {
+            MapDiff::Update { key, value }
+        } else {
+            MapDiff::Insert { key, value }
+        };
+        self.emit(diff);
+        previous
+    }
+
+    /// Modify the value for `key` in place; returns false if it is missing
+    pub fn update(&self, key: &K, f: impl FnOnce(&mut V)) -> bool {
+        let updated = self.write(|entries| {
+            let value = entries.get_mut(key)?;
+            f(value);
+            Some(value.clone())
+        });
+        let Some(Some(value)) = updated else {
+            return false;
+        };
+        self.emit(MapDiff::Update {
+            key: key.clone(),
+            value,
+        });
+        true
+    }
+
+    /// Remove an entry, returning its value
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let value = self.write(|entries| entries.remove(key))??;
+        self.emit(MapDiff::Remove { key: key.clone() });
+        Some(value)
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        let cleared = self.write(|entries| {
+            let had_entries = !entries.is_empty();
+            entries.clear();
+            had_entries
+        });
+        if cleared == Some(true) {
+            self.emit(MapDiff::Clear);
+        }
+    }
+
+    /// Call `listener` with every future diff
+    ///
+    /// Inside a scope the listener is removed when the scope is disposed.
+    pub fn subscribe(&self, listener: impl Fn(&MapDiff<K, V>) + 'static) -> SubscriptionId {
+        let id = self.inner.listeners.add(Rc::new(listener));
+        let weak = Rc::downgrade(&self.inner);
+        on_cleanup(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.listeners.remove(id);
+            }
+        });
+        id
+    }
+
+    /// Stop delivering diffs to a listener
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.inner.listeners.remove(id);
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut HashMap<K, V>) -> R) -> Option<R> {
+        write(&self.inner.entries, &self.inner.trigger, f)
+    }
+
+    fn emit(&self, diff: MapDiff<K, V>) {
+        self.inner.listeners.emit(&diff);
+        self.inner.trigger.set(());
+    }
+}
+
+impl<K: std::fmt::Debug, V: std::fmt::Debug> std::fmt::Debug for SignalMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SignalMap")
+            .field(&*self.inner.entries.borrow())
+            .finish()
+    }
+}
+
+/// A keyed change to a mapped list, ready to apply to the rendered rows
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListChange<K> {
+    /// A row was rendered and inserted at `index`
+    Insert { index: usize, key: K, node: VNode },
+    /// The row at `index` was removed
+    Remove { index: usize, key: K },
+    /// The row at `from` moved to `to`
+    Move { from: usize, to: usize, key: K },
+    /// The row at `index` was re-rendered
+    Update { index: usize, key: K, node: VNode },
+    /// All rows were removed
+    Clear,
+}
+
+/// One rendered row; its scope owns whatever the row's render created
+struct Row<K> {
+    key: K,
+    node: VNode,
+    scope: Scope,
+}
+
+struct MappedInner<K> {
+    rows: RefCell<Vec<Row<K>>>,
+    changes: RefCell<Vec<ListChange<K>>>,
+    trigger: Signal<()>,
+    unsubscribe: RefCell<Option<Box<dyn FnOnce()>>>,
+}
+
+impl<K> Drop for MappedInner<K> {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+        for row in self.rows.take() {
+            row.scope.dispose();
+        }
+    }
+}
+
+/// Rendered rows of a [`SignalVec`], kept up to date diff by diff
+///
+/// Created by [`map_keyed`].
+pub struct MappedList<K> {
+    inner: Rc<MappedInner<K>>,
+}
+
+impl<K> Clone for MappedList<K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K: Clone + 'static> MappedList<K> {
+    /// Clone the rendered rows in order (tracks dependency)
+    pub fn nodes(&self) -> Vec<VNode> {
+        self.inner.trigger.get();
+        self.inner
+            .rows
+            .borrow()
+            .iter()
+            .map(|row| row.node.clone())
+            .collect()
+    }
+
+    /// Keys of the rendered rows in order (tracks dependency)
+    pub fn keys(&self) -> Vec<K> {
+        self.inner.trigger.get();
+        self.inner
+            .rows
+            .borrow()
+            .iter()
+            .map(|row| row.key.clone())
+            .collect()
+    }
+
+    /// Number of rows (tracks dependency)
+    pub fn len(&self) -> usize {
+        self.inner.trigger.get();
+        self.inner.rows.borrow().len()
+    }
+
+    /// Whether there are no rows (tracks dependency)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Take the changes accumulated since the last call (tracks dependency)
+    pub fn take_changes(&self) -> Vec<ListChange<K>> {
+        self.inner.trigger.get();
+        self.inner.changes.take()
+    }
+}
+
+/// Map a [`SignalVec`] to keyed rows, rendering only inserted or updated items
+///
+/// Each row renders in its own scope, so effects and cleanups created by
+/// `render` are disposed when the row is removed or re-rendered. The rows are
+/// owned by the scope current at the call.
+pub fn map_keyed<T, K, KF, RF>(list: &SignalVec<T>, key: KF, render: RF) -> MappedList<K>
+where
+    T: Clone + 'static,
+    K: Clone + 'static,
+    KF: Fn(&T) -> K + 'static,
+    RF: Fn(&T) -> VNode + 'static,
+{
+    let owner = Scope::current();
+    let render_row = move |value: &T| {
+        let scope = match &owner {
+            Some(owner) => owner.child(),
+            None => create_root(|scope| scope),
+        };
+        let node = scope.run(|| untrack(|| render(value)));
+        Row {
+            key: key(value),
+            node,
+            scope,
+        }
+    };
+
+    let rows = list.with_untracked(|values| values.iter().map(&render_row).collect());
+    let inner = Rc::new(MappedInner {
+        rows: RefCell::new(rows),
+        changes: RefCell::new(Vec::new()),
+        trigger: Signal::new(()),
+        unsubscribe: RefCell::new(None),
+    });
+
+    let weak: Weak<MappedInner<K>> = Rc::downgrade(&inner);
+    let id = list.inner.listeners.add(Rc::new(move |diff: &VecDiff<T>| {
+        let Some(inner) = weak.upgrade() else {
+            return;
+        };
+        // The rows are only borrowed between calls into user code, so
+        // `render` and cleanups can read the mapped list
+        let rows = &inner.rows;
+        let change = match diff {
+            VecDiff::Insert { index, value } => {
+                let row = render_row(value);
+                let change = ListChange::Insert {
+                    index: *index,
+                    key: row.key.clone(),
+                    node: row.node.clone(),
+                };
+                rows.borrow_mut().insert(*index, row);
+                change
+            }
+            VecDiff::Remove { index } => {
+                let row = rows.borrow_mut().remove(*index);
+                row.scope.dispose();
+                ListChange::Remove {
+                    index: *index,
+                    key: row.key,
+                }
+            }
+            VecDiff::Move { from, to } => {
+                let mut rows = rows.borrow_mut();
+                let row = rows.remove(*from);
+                let key = row.key.clone();
+                rows.insert(*to, row);
+                ListChange::Move {
+                    from: *from,
+                    to: *to,
+                    key,
+                }
+            }
+            VecDiff::Update { index, value } => {
+                let row = render_row(value);
+                let change = ListChange::Update {
+                    index: *index,
+                    key: row.key.clone(),
+                    node: row.node.clone(),
+                };
+                let old = std::mem::replace(&mut rows.borrow_mut()[*index], row);
+                old.scope.dispose();
+                change
+            }
+            VecDiff::Clear => {
+                let old = rows.take();
+                for row in old {
+                    row.scope.dispose();
+                }
+                ListChange::Clear
+            }
+        };
+        inner.changes.borrow_mut().push(change);
+        inner.trigger.set(());
+    }));
+
+    let source = Rc::downgrade(&list.inner);
+    *inner.unsubscribe.borrow_mut() = Some(Box::new(move || {
+        if let Some(source) = source.upgrade() {
+            source.listeners.remove(id);
+        }
+    }));
+
+    MappedList { inner }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::{create_root, Effect};
+    use crate::vdom::VText;
+
+    fn recorded<T: Clone + 'static>(list: &SignalVec<T>) -> Rc<RefCell<Vec<VecDiff<T>>>> {
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        let diffs_c = diffs.clone();
+        list.subscribe(move |diff| diffs_c.borrow_mut().push(diff.clone()));
+        diffs
+    }
+
+    #[test]
+    fn test_vec_emits_diffs() {
+        let list = SignalVec::from_vec(vec![1, 2, 3]);
+        let diffs = recorded(&list);
+
+        list.push(4);
+        list.insert(0, 0);
+        list.remove(1);
+        list.move_item(0, 2);
+        list.set(1, 30);
+        list.update(0, |v| *v *= 10);
+        assert_eq!(list.pop(), Some(4));
+
+        assert_eq!(
+            *diffs.borrow(),
+            vec![
+                VecDiff::Insert { index: 3, value: 4 },
+                VecDiff::Insert { index: 0, value: 0 },
+                VecDiff::Remove { index: 1 },
+                VecDiff::Move { from: 0, to: 2 },
+                VecDiff::Update {
+                    index: 1,
+                    value: 30
+                },
+                VecDiff::Update {
+                    index: 0,
+                    value: 20
+                },
+                VecDiff::Remove { index: 3 },
+            ]
+        );
+        assert_eq!(list.to_vec(), vec![20, 30, 0]);
+    }
+
+    #[test]
+    fn test_vec_retain_and_replace() {
+        let list = SignalVec::from_vec(vec![1, 2, 3, 4]);
+        let diffs = recorded(&list);
+
+        list.retain(|v| v % 2 == 0);
+        assert_eq!(list.to_vec(), vec![2, 4]);
+        list.replace(vec![7]);
+
+        assert_eq!(
+            *diffs.borrow(),
+            vec![
+                VecDiff::Remove { index: 0 },
+                VecDiff::Remove { index: 1 },
+                VecDiff::Clear,
+                VecDiff::Insert { index: 0, value: 7 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vec_reads_are_tracked() {
+        let list = SignalVec::new();
+        let lengths = Rc::new(RefCell::new(Vec::new()));
+        let (list_c, lengths_c) = (list.clone(), lengths.clone());
+        Effect::new(move || lengths_c.borrow_mut().push(list_c.len()));
+
+        list.push("a");
+        // Several diffs inside one batch re-run the effect once
+        list.replace(vec!["b", "c", "d"]);
+        assert_eq!(*lengths.borrow(), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn test_writes_during_reads_are_reported() {
+        use crate::reactivity::{on_reactive_error, ReactiveError};
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_c = errors.clone();
+        on_reactive_error(move |error| errors_c.borrow_mut().push(error.clone()));
+
+        let list = SignalVec::from_vec(vec![1, 2]);
+        let diffs = recorded(&list);
+        list.with_untracked(|_| {
+            list.push(3);
+            assert_eq!(list.remove(0), None);
+            list.retain(|_| false);
+        });
+        let map = SignalMap::new();
+        map.insert("a", 1);
+        map.with_untracked(|_| assert_eq!(map.remove(&"a"), None));
+
+        assert_eq!(list.to_vec(), vec![1, 2]);
+        assert!(diffs.borrow().is_empty());
+        assert_eq!(map.get(&"a"), Some(1));
+        assert_eq!(errors.borrow().len(), 4);
+        assert!(errors.borrow().iter().all(|error| matches!(
+            error,
+            ReactiveError::ReentrantWrite { signal } if signal.label.is_some()
+        )));
+    }
+
+    #[test]
+    fn test_subscription_ends_with_scope() {
+        let list = SignalVec::new();
+        let count = Rc::new(Cell::new(0));
+        let scope = create_root(|scope| {
+            let count = count.clone();
+            list.subscribe(move |_| count.set(count.get() + 1));
+            scope
+        });
+
+        list.push(1);
+        scope.dispose();
+        list.push(2);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn test_map_emits_diffs() {
+        let map = SignalMap::new();
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        let diffs_c = diffs.clone();
+        map.subscribe(move |diff| diffs_c.borrow_mut().push(diff.clone()));
+
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("a", 2), Some(1));
+        assert!(map.update(&"a", |v| *v += 1));
+        assert!(!map.update(&"missing", |v| *v += 1));
+        assert_eq!(map.remove(&"a"), Some(3));
+        assert_eq!(map.remove(&"a"), None);
+
+        assert_eq!(
+            *diffs.borrow(),
+            vec![
+                MapDiff::Insert { key: "a", value: 1 },
+                MapDiff::Update { key: "a", value: 2 },
+                MapDiff::Update { key: "a", value: 3 },
+                MapDiff::Remove { key: "a" },
+            ]
+        );
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_mapped_list_renders_only_changed_rows() {
+        let list = SignalVec::from_vec(vec![(1, "a"), (2, "b")]);
+        let renders = Rc::new(Cell::new(0));
+        let renders_c = renders.clone();
+        let rows = map_keyed(
+            &list,
+            |item| item.0,
+            move |item| {
+                renders_c.set(renders_c.get() + 1);
+                VText::new(item.1).into()
+            },
+        );
+        assert_eq!(renders.get(), 2);
+
+        list.push((3, "c"));
+        list.move_item(2, 0);
+        list.set(1, (1, "A"));
+        list.remove(2);
+        assert_eq!(renders.get(), 4);
+
+        assert_eq!(
+            rows.take_changes(),
+            vec![
+                ListChange::Insert {
+                    index: 2,
+                    key: 3,
+                    node: VNode::from("c")
+                },
+                ListChange::Move {
+                    from: 2,
+                    to: 0,
+                    key: 3
+                },
+                ListChange::Update {
+                    index: 1,
+                    key: 1,
+                    node: VNode::from("A")
+                },
+                ListChange::Remove { index: 2, key: 2 },
+            ]
+        );
+        assert_eq!(rows.keys(), vec![3, 1]);
+        assert_eq!(rows.nodes(), vec![VNode::from("c"), VNode::from("A")]);
+        assert!(rows.take_changes().is_empty());
+    }
+
+    #[test]
+    fn test_mapped_rows_dispose_their_scope() {
+        let list = SignalVec::from_vec(vec![1, 2]);
+        let cleaned = Rc::new(RefCell::new(Vec::new()));
+        let cleaned_c = cleaned.clone();
+        let rows = map_keyed(
+            &list,
+            |v| *v,
+            move |v| {
+                let (cleaned, v) = (cleaned_c.clone(), *v);
+                on_cleanup(move || cleaned.borrow_mut().push(v));
+                VNode::Empty
+            },
+        );
+
+        list.remove(0);
+        assert_eq!(*cleaned.borrow(), vec![1]);
+        list.set(0, 3);
+        assert_eq!(*cleaned.borrow(), vec![1, 2]);
+        drop(rows);
+        assert_eq!(*cleaned.borrow(), vec![1, 2, 3]);
+
+        // The dropped list no longer listens to the source
+        list.push(4);
+        assert_eq!(list.inner.listeners.list.borrow().len(), 0);
+    }
+
+    #[test]
+    fn test_rows_can_read_the_mapped_list() {
+        let list = SignalVec::from_vec(vec![1]);
+        let mapped: Rc<RefCell<Option<MappedList<i32>>>> = Rc::new(RefCell::new(None));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (mapped_c, seen_c) = (mapped.clone(), seen.clone());
+        let rows = map_keyed(
+            &list,
+            |v| *v,
+            move |v| {
+                if let Some(mapped) = mapped_c.borrow().as_ref() {
+                    seen_c.borrow_mut().push(mapped.keys());
+                    let (mapped, seen) = (mapped.clone(), seen_c.clone());
+                    on_cleanup(move || seen.borrow_mut().push(mapped.keys()));
+                }
+                VText::new(v.to_string()).into()
+            },
+        );
+        *mapped.borrow_mut() = Some(rows.clone());
+
+        list.push(2);
+        list.set(1, 3);
+        list.clear();
+        assert_eq!(
+            *seen.borrow(),
+            vec![vec![1], vec![1, 2], vec![1, 3], vec![]]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Simple UI renderer for basic elements
 //! This provides a minimal but functional UI rendering system
 
+use crate::html::HtmlWriter;
 use crate::vdom::{VElement, VNode};
 
 /// Simple UI renderer that converts VNodes to console output
@@ -31,10 +32,26 @@ impl SimpleRenderer {
                 self.output
                     .push(format!("{}Text: {}", indent, text.content));
             }
+            VNode::Dynamic(binding) => {
+                self.output
+                    .push(format!("{}Text: {}", indent, binding.get()));
+            }
             VNode::Component(component) => {
                 self.output
                     .push(format!("{}Component: {}", indent, component.name));
             }
+            VNode::Fragment(children) => {
+                for child in children {
+                    self.render_node(child, depth);
+                }
+            }
+            VNode::Portal { target, children } => {
+                self.output.push(format!("{}Portal -> {}", indent, target));
+                for child in children {
+                    self.render_node(child, depth + 1);
+                }
+            }
+            VNode::Memo(memo) => self.render_node(&memo.node, depth),
             VNode::Empty => {
                 // Empty nodes don't render anything
             }
@@ -48,7 +65,7 @@ impl SimpleRenderer {
         let mut tag_str = format!("{}<{}", indent, element.tag);
 
         // Add attributes
-        for (key, value) in &element.attrs {
+        for (key, value) in &element.current_attrs() {
             tag_str.push_str(&format!(" {}=\"{}\"", key, value));
         }
         tag_str.push('>');
@@ -81,6 +98,50 @@ impl Default for SimpleRenderer {
     }
 }
 
+/// Render a VNode to an HTML string (for WASM/web rendering)
+///
+/// Bound values are written as they are now; event handlers are left out.
+pub fn render_to_html(vnode: &crate::simple_vnode::VNode) -> String {
+    let mut html = HtmlWriter::new();
+    write_html(&mut html, vnode);
+    html.finish()
+}
+
+fn write_html(html: &mut HtmlWriter, vnode: &crate::simple_vnode::VNode) {
+    use crate::simple_vnode::{VAttr, VNode};
+
+    match vnode {
+        VNode::Element {
+            tag,
+            attrs,
+            children,
+        } => {
+            let values: Vec<(&str, String)> = attrs
+                .iter()
+                .filter_map(|(key, value)| match value {
+                    VAttr::Static(value) => Some((key.as_str(), value.clone())),
+                    VAttr::Dynamic(binding) => Some((key.as_str(), binding.get())),
+                    // Skip event handlers in HTML rendering
+                    VAttr::Event(_) => None,
+                })
+                .collect();
+            let values = values.iter().map(|(key, value)| (*key, value.as_str()));
+            html.element(tag, values, |html| {
+                for child in children {
+                    write_html(html, child);
+                }
+            });
+        }
+        VNode::Text(text) => html.text(text),
+        VNode::Dynamic(binding) => html.text(&binding.get()),
+        VNode::Fragment(children) | VNode::Portal { children, .. } => {
+            for child in children {
+                write_html(html, child);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,6 +166,9 @@ mod tests {
             tag: "div".to_string(),
             attrs: HashMap::new(),
             children: vec![VNode::Text(VText::new("Content"))],
+            key: None,
+            events: HashMap::new(),
+            bindings: HashMap::new(),
         };
 
         renderer.render(&VNode::Element(element));
@@ -128,37 +192,45 @@ mod tests {
 
         assert!(output.contains("Component: Counter"));
     }
-}
 
-/// Render a VNode to an HTML string (for WASM/web rendering)
-#[cfg(target_arch = "wasm32")]
-pub fn render_to_html(vnode: &crate::simple_vnode::VNode) -> String {
-    use crate::simple_vnode::{VAttr, VNode};
-
-    match vnode {
-        VNode::Element {
-            tag,
-            attrs,
-            children,
-        } => {
-            let mut html = format!("<{}", tag);
-            for (key, value) in attrs {
-                match value {
-                    VAttr::Static(v) | VAttr::Dynamic(v) => {
-                        html.push_str(&format!(" {}=\"{}\"", key, v));
-                    }
-                    VAttr::Event(_) => {
-                        // Skip event handlers in HTML rendering
-                    }
-                }
-            }
-            html.push('>');
-            for child in children {
-                html.push_str(&render_to_html(child));
-            }
-            html.push_str(&format!("</{}>", tag));
+    #[test]
+    fn test_render_to_html_matches_ssr() {
+        use crate::simple_vnode::{VAttr, VNode as SimpleNode};
+        use crate::ssr::StreamingSSRRenderer;
+
+        let simple = SimpleNode::element(
+            "label",
+            vec![
+                ("title", VAttr::Static("<Terms & conditions>".to_string())),
+                ("class", VAttr::Dynamic("terms".into())),
+                (
+                    "on_click",
+                    VAttr::Event(std::rc::Rc::new(std::cell::RefCell::new(|| {}))),
+                ),
+            ],
+            vec![
+                SimpleNode::element(
+                    "input",
+                    vec![
+                        ("type", VAttr::Static("checkbox".to_string())),
+                        ("checked", VAttr::Static("true".to_string())),
+                    ],
+                    vec![],
+                ),
+                SimpleNode::text("I agree <b>"),
+            ],
+        );
+
+        let html = render_to_html(&simple);
+        assert_eq!(
+            html,
+            "<label class=\"terms\" title=\"&lt;Terms &amp; conditions&gt;\">\
+             <input checked type=\"checkbox\" />I agree &lt;b&gt;</label>"
+        );
+        let node = VNode::from(simple);
+        assert_eq!(
+            StreamingSSRRenderer::new(64).render_vnode(&node).concat(),
             html
-        }
-        VNode::Text(text) => text.clone(),
+        );
     }
 }
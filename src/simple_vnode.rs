@@ -2,7 +2,16 @@
 //!
 //! This is a minimal virtual DOM implementation specifically for compiled components.
 //! It's designed to be simple and transparent, not a full-featured virtual DOM.
+//!
+//! New code should build [`crate::vdom::VNode`] trees, which every renderer
+//! accepts; trees from this module convert into them with `From`.
+//!
+//! [`VNode::Dynamic`] text and [`VAttr::Dynamic`] attributes are bound to
+//! signals: once rendered to the DOM, a change updates just that node,
+//! without re-rendering the tree. Bindings and event handlers are released
+//! with [`crate::renderer::unmount`].
 
+use crate::binding::Binding;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -11,6 +20,11 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use web_sys::{Document, Element, Event};
 
+#[cfg(target_arch = "wasm32")]
+fn to_js(error: String) -> JsValue {
+    JsValue::from_str(&error)
+}
+
 /// A simple virtual DOM node
 #[derive(Clone)]
 pub enum VNode {
@@ -20,13 +34,23 @@ pub enum VNode {
         children: Vec<VNode>,
     },
     Text(String),
+    /// A text node bound to a reactive value
+    Dynamic(Binding),
+    /// Children rendered in place without a wrapper element
+    Fragment(Vec<VNode>),
+    /// Children rendered into the element matching a CSS selector
+    Portal {
+        target: String,
+        children: Vec<VNode>,
+    },
 }
 
 /// Attribute value
 #[derive(Clone)]
 pub enum VAttr {
     Static(String),
-    Dynamic(String),
+    /// Bound to a reactive value
+    Dynamic(Binding),
     Event(Rc<RefCell<dyn FnMut()>>),
 }
 
@@ -45,6 +69,24 @@ impl VNode {
         VNode::Text(content.to_string())
     }
 
+    /// Create a text node that follows `value`, e.g. a `Signal`
+    pub fn dynamic(value: impl Into<Binding>) -> Self {
+        VNode::Dynamic(value.into())
+    }
+
+    /// Create a fragment node
+    pub fn fragment(children: Vec<VNode>) -> Self {
+        VNode::Fragment(children)
+    }
+
+    /// Create a portal into the element matching `target`
+    pub fn portal(target: &str, children: Vec<VNode>) -> Self {
+        VNode::Portal {
+            target: target.to_string(),
+            children,
+        }
+    }
+
     /// Render this VNode to the DOM
     #[cfg(target_arch = "wasm32")]
     pub fn render(&self, document: &Document) -> Result<web_sys::Node, JsValue> {
@@ -59,7 +101,7 @@ impl VNode {
                 // Set attributes
                 for (name, value) in attrs {
                     match value {
-                        VAttr::Static(v) | VAttr::Dynamic(v) => {
+                        VAttr::Static(v) => {
                             if name == "class" {
                                 element.set_attribute("class", v)?;
                             } else if name.starts_with("on_") {
@@ -68,6 +110,11 @@ impl VNode {
                                 element.set_attribute(name, v)?;
                             }
                         }
+                        VAttr::Dynamic(binding) => {
+                            // Patched in place until the element is unmounted
+                            crate::renderer::bind_attribute(&element, name, binding)
+                                .map_err(to_js)?;
+                        }
                         VAttr::Event(handler) => {
                             if let Some(event_name) = name.strip_prefix("on_") {
                                 let handler_clone = handler.clone();
@@ -76,11 +123,9 @@ impl VNode {
                                 })
                                     as Box<dyn FnMut(Event)>);
 
-                                element.add_event_listener_with_callback(
-                                    event_name,
-                                    closure.as_ref().unchecked_ref(),
-                                )?;
-                                closure.forget(); // Keep the closure alive
+                                // Kept until the element is unmounted
+                                crate::renderer::add_callback(&element, event_name, closure)
+                                    .map_err(to_js)?;
                             }
                         }
                     }
@@ -98,10 +143,36 @@ impl VNode {
                 let text_node = document.create_text_node(content);
                 Ok(text_node.into())
             }
+            VNode::Dynamic(binding) => {
+                let text_node = document.create_text_node("");
+                let node = text_node.clone();
+                crate::renderer::bind(&text_node, "", binding, move |content| {
+                    node.set_data(&content)
+                })
+                .map_err(to_js)?;
+                Ok(text_node.into())
+            }
+            VNode::Fragment(children) => {
+                let fragment = document.create_document_fragment();
+                for child in children {
+                    fragment.append_child(&child.render(document)?)?;
+                }
+                Ok(fragment.into())
+            }
+            VNode::Portal { target, children } => {
+                let content = document.create_document_fragment();
+                for child in children {
+                    content.append_child(&child.render(document)?)?;
+                }
+                crate::renderer::mount_portal(document, target, &content).map_err(to_js)
+            }
         }
     }
 
     /// Mount this VNode to a parent element
+    ///
+    /// Pass the mounted nodes to [`crate::renderer::unmount`] before
+    /// removing them.
     #[cfg(target_arch = "wasm32")]
     pub fn mount(&self, parent: &Element) -> Result<(), JsValue> {
         let document = parent
@@ -113,6 +184,106 @@ impl VNode {
     }
 }
 
+/// Convert to the diffable tree
+///
+/// `on_<event>` handlers become element listeners for `<event>`; dynamic
+/// text and attributes stay bound.
+impl From<VNode> for crate::vdom::VNode {
+    fn from(node: VNode) -> Self {
+        use crate::vdom::{Listener, VElement, VText};
+
+        match node {
+            VNode::Element {
+                tag,
+                attrs,
+                children,
+            } => {
+                let mut element = VElement::new(tag);
+                for (name, value) in attrs {
+                    match value {
+                        VAttr::Static(value) => {
+                            element.attrs.insert(name, value);
+                        }
+                        VAttr::Dynamic(binding) => {
+                            element.bindings.insert(name, binding);
+                        }
+                        VAttr::Event(handler) => {
+                            let event = match name.strip_prefix("on_") {
+                                Some(event) => event.to_string(),
+                                None => name,
+                            };
+                            element.events.insert(event, Listener(handler));
+                        }
+                    }
+                }
+                element.children = children.into_iter().map(Into::into).collect();
+                crate::vdom::VNode::Element(element)
+            }
+            VNode::Text(content) => crate::vdom::VNode::Text(VText::new(content)),
+            VNode::Dynamic(binding) => crate::vdom::VNode::Dynamic(binding),
+            VNode::Fragment(children) => {
+                crate::vdom::VNode::Fragment(children.into_iter().map(Into::into).collect())
+            }
+            VNode::Portal { target, children } => crate::vdom::VNode::Portal {
+                target,
+                children: children.into_iter().map(Into::into).collect(),
+            },
+        }
+    }
+}
+
+/// Convert for renderers that still take this tree, such as the desktop
+/// renderer
+///
+/// Attributes are sorted by name; component nodes must be expanded first
+/// and, like empty nodes, become empty fragments.
+impl From<crate::vdom::VNode> for VNode {
+    fn from(node: crate::vdom::VNode) -> Self {
+        use crate::vdom::VNode as Node;
+
+        match node {
+            Node::Element(element) => {
+                let bindings = element.bindings;
+                let mut attrs: Vec<(String, VAttr)> = element
+                    .attrs
+                    .into_iter()
+                    .filter(|(name, _)| !bindings.contains_key(name))
+                    .map(|(name, value)| (name, VAttr::Static(value)))
+                    .collect();
+                attrs.extend(
+                    bindings
+                        .into_iter()
+                        .map(|(name, binding)| (name, VAttr::Dynamic(binding))),
+                );
+                attrs.sort_by(|a, b| a.0.cmp(&b.0));
+                let mut events: Vec<_> = element.events.into_iter().collect();
+                events.sort_by(|a, b| a.0.cmp(&b.0));
+                attrs.extend(
+                    events.into_iter().map(|(event, listener)| {
+                        (format!("on_{}", event), VAttr::Event(listener.0))
+                    }),
+                );
+                VNode::Element {
+                    tag: element.tag,
+                    attrs,
+                    children: element.children.into_iter().map(Into::into).collect(),
+                }
+            }
+            Node::Text(text) => VNode::Text(text.content),
+            Node::Dynamic(binding) => VNode::Dynamic(binding),
+            Node::Fragment(children) => {
+                VNode::Fragment(children.into_iter().map(Into::into).collect())
+            }
+            Node::Portal { target, children } => VNode::Portal {
+                target,
+                children: children.into_iter().map(Into::into).collect(),
+            },
+            Node::Memo(memo) => VNode::from((*memo.node).clone()),
+            Node::Component(_) | Node::Empty => VNode::Fragment(Vec::new()),
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl VNode {
     pub fn render(&self) -> Result<String, String> {
@@ -130,6 +301,88 @@ impl VNode {
                 Ok(html)
             }
             VNode::Text(content) => Ok(content.clone()),
+            VNode::Dynamic(binding) => Ok(binding.get()),
+            VNode::Fragment(children) | VNode::Portal { children, .. } => {
+                children.iter().map(VNode::render).collect()
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vdom;
+
+    #[test]
+    fn test_conversion_keeps_event_handlers() {
+        let clicks = Rc::new(RefCell::new(0));
+        let clicks_c = clicks.clone();
+        let handler: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(move || {
+            *clicks_c.borrow_mut() += 1;
+        }));
+        let legacy = VNode::element(
+            "button",
+            vec![
+                ("class", VAttr::Static("primary".to_string())),
+                ("title", VAttr::Dynamic("Save".into())),
+                ("on_click", VAttr::Event(handler)),
+            ],
+            vec![VNode::fragment(vec![VNode::text("Save")])],
+        );
+
+        let node = vdom::VNode::from(legacy);
+        let vdom::VNode::Element(button) = &node else {
+            panic!("expected an element");
+        };
+        assert_eq!(button.attrs["class"], "primary");
+        assert_eq!(button.bindings["title"].get_untracked(), "Save");
+        assert_eq!(
+            button.children,
+            vec![vdom::VNode::fragment(vec!["Save".into()])]
+        );
+        button.events["click"].call();
+        assert_eq!(*clicks.borrow(), 1);
+
+        // And back, with the same handler
+        let VNode::Element { attrs, .. } = VNode::from(node.clone()) else {
+            panic!("expected an element");
+        };
+        let names: Vec<_> = attrs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["class", "title", "on_click"]);
+        let VAttr::Event(handler) = &attrs[2].1 else {
+            panic!("expected a handler");
+        };
+        assert!(Rc::ptr_eq(handler, &button.events["click"].0));
+    }
+
+    #[test]
+    fn test_conversion_keeps_bindings_untracked() {
+        use crate::binding::Binding;
+        use crate::reactivity::{Effect, Signal};
+
+        let count = Signal::new(1);
+        let label = Binding::from(count.clone());
+        let legacy = VNode::element(
+            "output",
+            vec![("data-count", VAttr::Dynamic(label.clone()))],
+            vec![VNode::Dynamic(label.clone())],
+        );
+
+        let rendered = Rc::new(RefCell::new(Vec::new()));
+        let rendered_c = rendered.clone();
+        Effect::new(move || {
+            rendered_c
+                .borrow_mut()
+                .push(vdom::VNode::from(legacy.clone()))
+        });
+
+        // Bound nodes update on their own, so the render does not re-run
+        count.set(2);
+        let expected: vdom::VNode = vdom::VElement::new("output")
+            .bind("data-count", label.clone())
+            .child(vdom::VNode::Dynamic(label))
+            .into();
+        assert_eq!(*rendered.borrow(), vec![expected]);
+    }
+}
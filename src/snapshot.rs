@@ -0,0 +1,563 @@
+//! Snapshot testing for rendered output
+//!
+//! [`vnode`], [`simple_vnode`] and [`html`] print a tree in one stable,
+//! indented format: attributes sorted, one node per line, whitespace in text
+//! collapsed. [`assert_snapshot!`](crate::assert_snapshot) compares the
+//! result with a file in `tests/snapshots/`.
+//!
+//! Review workflow:
+//! - a missing or different snapshot fails the test, prints a line diff and
+//!   writes the new output next to it as `<name>.snap.new`
+//! - running the tests with `UPDATE_SNAPSHOTS=1` writes `<name>.snap`
+//!   instead; review the change with `git diff`
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::snapshot;
+//! use windjammer_ui::vdom::{VElement, VNode};
+//!
+//! let node: VNode = VElement::new("p")
+//!     .attr("id", "intro")
+//!     .attr("class", "lead")
+//!     .child("Hello".into())
+//!     .into();
+//! let html = "<p id='intro'   class=\"lead\">\n  Hello\n</p>";
+//!
+//! assert_eq!(snapshot::vnode(&node), snapshot::html(html));
+//! assert_eq!(
+//!     snapshot::vnode(&node),
+//!     "<p class=\"lead\" id=\"intro\">\n  \"Hello\"\n</p>\n"
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Environment variable that makes [`assert_snapshot()`] write snapshots
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// Attributes sorted by name, with their values as printed; `None` for
+/// attributes without a value
+type Attrs = BTreeMap<String, Option<String>>;
+
+/// An element being parsed: tag, attributes and children so far
+type OpenElement = (String, Attrs, Vec<Node>);
+
+/// Tree every format is converted to before printing
+enum Node {
+    Element {
+        tag: String,
+        attrs: Attrs,
+        children: Vec<Node>,
+    },
+    Text(String),
+    /// Verbatim lines, e.g. comments and `<script>` content
+    Raw(String),
+}
+
+/// Print a [`crate::vdom::VNode`]
+///
+/// Handlers show as `on:<event>` attributes and keys as `key`; memo nodes
+/// print as their content, and components as `<Name ...props />`.
+pub fn vnode(node: &crate::vdom::VNode) -> String {
+    let mut nodes = Vec::new();
+    from_vnode(node, &mut nodes);
+    print(&nodes)
+}
+
+/// Print a [`crate::simple_vnode::VNode`]
+///
+/// Bound text and attributes show their current value in braces, e.g.
+/// `title={"Save"}`, and `on_<event>` handlers print like vdom ones.
+pub fn simple_vnode(node: &crate::simple_vnode::VNode) -> String {
+    let mut nodes = Vec::new();
+    from_simple(node, &mut nodes);
+    print(&nodes)
+}
+
+/// Parse and print HTML, e.g. the output of `Renderable::render()`
+///
+/// Attribute quoting is normalized and runs of whitespace in text become
+/// one space; text that is only whitespace is dropped. `<script>`, `<style>`
+/// and comments are kept as written.
+pub fn html(html: &str) -> String {
+    print(&parse(html))
+}
+
+/// Compare `actual` with the snapshot `<dir>/<name>.snap`
+///
+/// Panics with a line diff on mismatch; see the [module docs](self) for the
+/// review workflow. Usually called through
+/// [`assert_snapshot!`](crate::assert_snapshot).
+pub fn assert_snapshot(dir: impl AsRef<Path>, name: &str, actual: &str) {
+    let dir = dir.as_ref();
+    let path = dir.join(format!("{}.snap", name));
+    let pending = dir.join(format!("{}.snap.new", name));
+    let expected = std::fs::read_to_string(&path).ok();
+
+    if expected.as_deref() == Some(actual) {
+        let _ = std::fs::remove_file(&pending);
+        return;
+    }
+
+    let update = std::env::var(UPDATE_VAR).is_ok_and(|value| !value.is_empty() && value != "0");
+    let target = if update { &path } else { &pending };
+    if let Err(e) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(target, actual)) {
+        panic!("Failed to write snapshot {}: {}", target.display(), e);
+    }
+    if update {
+        let _ = std::fs::remove_file(&pending);
+        return;
+    }
+
+    match expected {
+        Some(expected) => panic!(
+            "Snapshot {} does not match (- snapshot, + actual):\n{}\n\
+             The new output is in {}; rerun with {}=1 to accept it",
+            name,
+            line_diff(&expected, actual),
+            pending.display(),
+            UPDATE_VAR
+        ),
+        None => panic!(
+            "No snapshot {}; the output is in {}:\n{}\n\
+             Rerun with {}=1 to accept it",
+            path.display(),
+            pending.display(),
+            actual,
+            UPDATE_VAR
+        ),
+    }
+}
+
+/// Compare a value with the snapshot `tests/snapshots/<name>.snap` of the
+/// calling crate
+///
+/// ```rust,no_run
+/// use windjammer_ui::{assert_snapshot, snapshot};
+/// use windjammer_ui::vdom::VElement;
+///
+/// assert_snapshot!("empty_list", snapshot::vnode(&VElement::new("ul").into()));
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::snapshot::assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"),
+            $name,
+            &$actual,
+        )
+    };
+}
+
+fn from_vnode(node: &crate::vdom::VNode, out: &mut Vec<Node>) {
+    use crate::vdom::VNode;
+
+    match node {
+        VNode::Element(element) => {
+            let mut attrs: Attrs = element
+                .attrs
+                .iter()
+                .map(|(key, value)| (key.clone(), Some(quote(value))))
+                .collect();
+            attrs.extend(element.bindings.iter().map(|(key, binding)| {
+                (
+                    key.clone(),
+                    Some(format!("{{{:?}}}", binding.get_untracked())),
+                )
+            }));
+            attrs.extend(
+                element
+                    .events
+                    .keys()
+                    .map(|event| (format!("on:{}", event), None)),
+            );
+            if let Some(key) = &element.key {
+                attrs.insert("key".to_string(), Some(quote(key)));
+            }
+            let mut children = Vec::new();
+            for child in &element.children {
+                from_vnode(child, &mut children);
+            }
+            out.push(Node::Element {
+                tag: element.tag.clone(),
+                attrs,
+                children,
+            });
+        }
+        VNode::Text(text) => out.push(Node::Text(text.content.clone())),
+        VNode::Dynamic(binding) => {
+            out.push(Node::Raw(format!("{{{:?}}}", binding.get_untracked())))
+        }
+        VNode::Component(component) => out.push(Node::Element {
+            tag: component.name.clone(),
+            attrs: component
+                .props
+                .iter()
+                .map(|(key, value)| (key.clone(), Some(quote(value))))
+                .collect(),
+            children: Vec::new(),
+        }),
+        VNode::Fragment(children) => {
+            for child in children {
+                from_vnode(child, out);
+            }
+        }
+        VNode::Portal { target, children } => {
+            let mut content = Vec::new();
+            for child in children {
+                from_vnode(child, &mut content);
+            }
+            out.push(Node::Element {
+                tag: "portal".to_string(),
+                attrs: BTreeMap::from([("target".to_string(), Some(quote(target)))]),
+                children: content,
+            });
+        }
+        VNode::Memo(memo) => from_vnode(&memo.node, out),
+        VNode::Empty => {}
+    }
+}
+
+fn from_simple(node: &crate::simple_vnode::VNode, out: &mut Vec<Node>) {
+    use crate::simple_vnode::{VAttr, VNode};
+
+    match node {
+        VNode::Element {
+            tag,
+            attrs,
+            children,
+        } => {
+            let attrs = attrs
+                .iter()
+                .map(|(name, value)| match value {
+                    VAttr::Static(value) => (name.clone(), Some(quote(value))),
+                    VAttr::Dynamic(binding) => (
+                        name.clone(),
+                        Some(format!("{{{:?}}}", binding.get_untracked())),
+                    ),
+                    VAttr::Event(_) => match name.strip_prefix("on_") {
+                        Some(event) => (format!("on:{}", event), None),
+                        None => (name.clone(), None),
+                    },
+                })
+                .collect();
+            let mut nodes = Vec::new();
+            for child in children {
+                from_simple(child, &mut nodes);
+            }
+            out.push(Node::Element {
+                tag: tag.clone(),
+                attrs,
+                children: nodes,
+            });
+        }
+        VNode::Text(text) => out.push(Node::Text(text.clone())),
+        VNode::Dynamic(binding) => {
+            out.push(Node::Raw(format!("{{{:?}}}", binding.get_untracked())))
+        }
+        VNode::Fragment(children) => {
+            for child in children {
+                from_simple(child, out);
+            }
+        }
+        VNode::Portal { target, children } => {
+            let mut content = Vec::new();
+            for child in children {
+                from_simple(child, &mut content);
+            }
+            out.push(Node::Element {
+                tag: "portal".to_string(),
+                attrs: BTreeMap::from([("target".to_string(), Some(quote(target)))]),
+                children: content,
+            });
+        }
+    }
+}
+
+/// Parse HTML leniently: unclosed elements end with their parent, stray
+/// end tags are ignored
+fn parse(html: &str) -> Vec<Node> {
+    // Open elements, innermost last; the first entry collects the roots
+    let mut stack: Vec<OpenElement> = vec![(String::new(), BTreeMap::new(), Vec::new())];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let children = &mut stack.last_mut().expect("root stays on the stack").2;
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").unwrap_or(comment.len());
+            children.push(Node::Raw(format!("<!--{}-->", &comment[..end])));
+            rest = comment.get(end + 3..).unwrap_or("");
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            let name = tag[..end].trim().to_ascii_lowercase();
+            rest = tag.get(end + 1..).unwrap_or("");
+            if let Some(open) = stack.iter().rposition(|(tag, ..)| *tag == name) {
+                while stack.len() > open.max(1) {
+                    close(&mut stack);
+                }
+            }
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let end = tag_end(rest);
+            let (name, attrs, self_closing) = parse_tag(&rest[1..end]);
+            rest = rest.get(end + 1..).unwrap_or("");
+            if self_closing || crate::html::is_void_element(&name) {
+                children.push(Node::Element {
+                    tag: name,
+                    attrs,
+                    children: Vec::new(),
+                });
+            } else if crate::html::is_raw_text_element(&name) {
+                let close_tag = format!("</{}", name);
+                let end = rest.find(&close_tag).unwrap_or(rest.len());
+                let content = rest[..end].trim();
+                let children = if content.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![Node::Raw(content.to_string())]
+                };
+                children_push(&mut stack, name.clone(), attrs, children);
+                rest = &rest[end..];
+                rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+            } else {
+                stack.push((name, attrs, Vec::new()));
+            }
+        } else {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '<')
+                .map_or(rest.len(), |(i, _)| i);
+            let text = rest[..end].split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                children.push(Node::Text(text));
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack.pop().map(|(.., roots)| roots).unwrap_or_default()
+}
+
+/// Pop the innermost open element into its parent
+fn close(stack: &mut Vec<OpenElement>) {
+    if let Some((tag, attrs, children)) = stack.pop() {
+        children_push(stack, tag, attrs, children);
+    }
+}
+
+fn children_push(stack: &mut [OpenElement], tag: String, attrs: Attrs, children: Vec<Node>) {
+    if let Some((.., parent)) = stack.last_mut() {
+        parent.push(Node::Element {
+            tag,
+            attrs,
+            children,
+        });
+    }
+}
+
+/// Index of the `>` ending the tag that starts `html`, skipping quoted
+/// attribute values
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in html.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return i,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Name, attributes and whether it ends with `/>`, from the inside of a tag
+fn parse_tag(tag: &str) -> (String, Attrs, bool) {
+    let self_closing = tag.trim_end().ends_with('/');
+    let tag = tag.trim_end().trim_end_matches('/');
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+
+    let mut attrs = BTreeMap::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_string();
+        rest = rest[key_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                let (parsed, remaining) = match value.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let end = value[1..].find(q).map_or(value.len(), |i| i + 1);
+                        (&value[1..end], value.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        (&value[..end], &value[end..])
+                    }
+                };
+                rest = remaining.trim_start();
+                Some(quote(parsed))
+            }
+            None => None,
+        };
+        attrs.insert(key, value);
+    }
+    (name, attrs, self_closing)
+}
+
+fn quote(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+fn print(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        print_node(node, 0, &mut out);
+    }
+    out
+}
+
+fn print_node(node: &Node, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match node {
+        Node::Element {
+            tag,
+            attrs,
+            children,
+        } => {
+            out.push_str(&indent);
+            out.push('<');
+            out.push_str(tag);
+            for (key, value) in attrs {
+                match value {
+                    Some(value) => out.push_str(&format!(" {}={}", key, value)),
+                    None => out.push_str(&format!(" {}", key)),
+                }
+            }
+            if children.is_empty() {
+                out.push_str(" />\n");
+                return;
+            }
+            out.push_str(">\n");
+            for child in children {
+                print_node(child, depth + 1, out);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, tag));
+        }
+        Node::Text(text) => {
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                out.push_str(&format!("{}{:?}\n", indent, text));
+            }
+        }
+        Node::Raw(raw) => {
+            for line in raw.lines() {
+                out.push_str(&format!("{}{}\n", indent, line.trim()));
+            }
+        }
+    }
+}
+
+/// Line diff of `old` and `new`: removed lines start with `-`, added ones
+/// with `+`, unchanged ones with two spaces
+fn line_diff(old: &str, new: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+
+    // Longest common subsequence lengths of the suffixes
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vdom::{VElement, VNode};
+
+    #[test]
+    fn test_html_normalizes_quotes_order_and_whitespace() {
+        let html = "<div style='color: red' class=\"box\"   hidden>\n   Hello\n   world <br> <img src=a.png/></div>";
+        assert_eq!(
+            super::html(html),
+            "<div class=\"box\" hidden style=\"color: red\">\n  \"Hello world\"\n  <br />\n  <img src=\"a.png\" />\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_html_keeps_raw_text_and_recovers_from_unclosed_tags() {
+        let html = "<ul><li>One<li>Two</ul><script>if (a < b) { go(); }</script><!-- done -->";
+        assert_eq!(
+            super::html(html),
+            "<ul>\n  <li>\n    \"One\"\n    <li>\n      \"Two\"\n    </li>\n  </li>\n</ul>\n\
+             <script>\n  if (a < b) { go(); }\n</script>\n<!-- done -->\n"
+        );
+    }
+
+    #[test]
+    fn test_vnode_and_simple_vnode_print_alike() {
+        use crate::simple_vnode::{VAttr, VNode as SimpleNode};
+
+        let node: VNode = VElement::new("button")
+            .attr("type", "submit")
+            .on("click", || {})
+            .child(VNode::fragment(vec!["Save".into()]))
+            .into();
+        let simple = SimpleNode::element(
+            "button",
+            vec![
+                ("type", VAttr::Static("submit".to_string())),
+                (
+                    "on_click",
+                    VAttr::Event(std::rc::Rc::new(std::cell::RefCell::new(|| {}))),
+                ),
+            ],
+            vec![SimpleNode::text("Save")],
+        );
+        assert_eq!(vnode(&node), simple_vnode(&simple));
+        assert_eq!(
+            vnode(&node),
+            "<button on:click type=\"submit\">\n  \"Save\"\n</button>\n"
+        );
+    }
+
+    #[test]
+    fn test_line_diff_marks_changed_lines() {
+        assert_eq!(
+            line_diff("<p>\n  \"Hi\"\n</p>", "<p>\n  \"Hello\"\n</p>"),
+            "  <p>\n-   \"Hi\"\n+   \"Hello\"\n  </p>\n"
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! Server-Side Rendering (SSR) for web targets
 
-use crate::component::Component;
-use crate::vdom::{VElement, VNode, VText};
+use crate::component::{Component, ComponentRegistry};
+use crate::html::{escape_attribute, escape_text, HtmlWriter};
+use crate::reconciler::Reconciler;
+use crate::vdom::{VElement, VNode};
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// SSR renderer that converts VNodes to HTML strings
 pub struct SSRRenderer {
@@ -12,6 +15,10 @@ pub struct SSRRenderer {
     state: HashMap<String, String>,
     /// Hydration script
     hydration_script: String,
+    /// Rendered portal content by target selector
+    portals: Vec<(String, String)>,
+    /// Factories used to expand component nodes
+    registry: Option<Rc<ComponentRegistry>>,
 }
 
 impl SSRRenderer {
@@ -21,13 +28,26 @@ impl SSRRenderer {
             html: String::new(),
             state: HashMap::new(),
             hydration_script: String::new(),
+            portals: Vec::new(),
+            registry: None,
         }
     }
 
     /// Render a component to HTML string
+    ///
+    /// With a registry set, child components are expanded and cleaned up
+    /// again once the HTML is generated.
     pub fn render_to_string<C: Component>(&mut self, component: C) -> String {
         let vnode = component.render();
-        self.render_vnode(&vnode);
+        match self.registry.clone() {
+            Some(registry) => {
+                let mut reconciler = Reconciler::new(registry);
+                let expanded = reconciler.expand(&vnode);
+                self.render_vnode(&expanded);
+                reconciler.unmount();
+            }
+            None => self.render_vnode(&vnode),
+        }
         self.html.clone()
     }
 
@@ -47,19 +67,30 @@ impl SSRRenderer {
     </script>
 </head>
 <body>
-    <div id="app">{}</div>
+    <div id="app">{}</div>{}
     <script>
         {}
     </script>
 </body>
 </html>"#,
-            Self::escape_html(title),
-            serde_json::to_string(&self.state).unwrap_or_default(),
+            escape_text(title),
+            self.state_json(),
             body_html,
+            self.body_portals(),
             self.get_hydration_script()
         )
     }
 
+    /// Hydration state as JSON that can be embedded in a `<script>`
+    ///
+    /// `<` only occurs inside JSON strings, where `\u003c` means the same, so
+    /// the state can neither close the element nor open a comment in it.
+    fn state_json(&self) -> String {
+        serde_json::to_string(&self.state)
+            .unwrap_or_default()
+            .replace('<', "\\u003c")
+    }
+
     /// Get the hydration script
     pub fn get_hydration_script(&self) -> String {
         if !self.hydration_script.is_empty() {
@@ -86,92 +117,65 @@ impl SSRRenderer {
 
     /// Render a VNode to HTML
     fn render_vnode(&mut self, vnode: &VNode) {
+        let mut html = HtmlWriter::new();
+        self.write_vnode(&mut html, vnode);
+        self.html.push_str(&html.finish());
+    }
+
+    fn write_vnode(&mut self, html: &mut HtmlWriter, vnode: &VNode) {
         match vnode {
-            VNode::Element(element) => self.render_element(element),
-            VNode::Text(text) => self.render_text(text),
+            VNode::Element(element) => self.write_element(html, element),
+            VNode::Text(text) => html.text(&text.content),
+            VNode::Dynamic(binding) => html.text(&binding.get()),
             VNode::Component(_) => {
-                // Components should be expanded before SSR
-                self.html.push_str("<!-- Component not expanded -->");
+                // Not registered, or no registry set
+                html.comment(" Component not expanded ");
             }
+            VNode::Fragment(children) => {
+                for child in children {
+                    self.write_vnode(html, child);
+                }
+            }
+            VNode::Portal { target, children } => {
+                // The placeholder keeps sibling positions stable for hydration;
+                // the content is rendered separately
+                html.comment("portal");
+                let mut content = HtmlWriter::new();
+                for child in children {
+                    self.write_vnode(&mut content, child);
+                }
+                self.portals.push((target.clone(), content.finish()));
+            }
+            VNode::Memo(memo) => self.write_vnode(html, &memo.node),
             VNode::Empty => {}
         }
     }
 
-    /// Render an element
-    fn render_element(&mut self, element: &VElement) {
-        // Opening tag
-        self.html.push('<');
-        self.html.push_str(&element.tag);
-
-        // Attributes
-        for (key, value) in &element.attrs {
-            self.html.push(' ');
-            self.html.push_str(&Self::escape_attribute(key));
-            self.html.push_str("=\"");
-            self.html.push_str(&Self::escape_attribute(value));
-            self.html.push('"');
-        }
-
-        // Self-closing tags
-        if element.children.is_empty() && Self::is_void_element(&element.tag) {
-            self.html.push_str(" />");
-            return;
-        }
-
-        self.html.push('>');
-
-        // Children
-        for child in &element.children {
-            self.render_vnode(child);
-        }
-
-        // Closing tag
-        self.html.push_str("</");
-        self.html.push_str(&element.tag);
-        self.html.push('>');
-    }
-
-    /// Render text
-    fn render_text(&mut self, text: &VText) {
-        self.html.push_str(&Self::escape_html(&text.content));
-    }
-
-    /// Check if element is self-closing (void element)
-    fn is_void_element(tag: &str) -> bool {
-        matches!(
-            tag,
-            "area"
-                | "base"
-                | "br"
-                | "col"
-                | "embed"
-                | "hr"
-                | "img"
-                | "input"
-                | "link"
-                | "meta"
-                | "param"
-                | "source"
-                | "track"
-                | "wbr"
-        )
+    /// Portal content rendered so far, as `(target selector, html)`
+    ///
+    /// Content for `"body"` is added to [`render_to_document`] output; place
+    /// the rest into the page template by selector.
+    ///
+    /// [`render_to_document`]: Self::render_to_document
+    pub fn portals(&self) -> &[(String, String)] {
+        &self.portals
     }
 
-    /// Escape HTML special characters
-    fn escape_html(text: &str) -> String {
-        text.replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-            .replace('\'', "&#x27;")
+    fn body_portals(&self) -> String {
+        self.portals
+            .iter()
+            .filter(|(target, _)| target == "body")
+            .map(|(target, html)| portal_container(target, html))
+            .collect()
     }
 
-    /// Escape attribute values
-    fn escape_attribute(text: &str) -> String {
-        text.replace('&', "&amp;")
-            .replace('"', "&quot;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
+    fn write_element(&mut self, html: &mut HtmlWriter, element: &VElement) {
+        let attrs = element.current_attrs();
+        html.element(&element.tag, attributes(&attrs), |html| {
+            for child in &element.children {
+                self.write_vnode(html, child);
+            }
+        });
     }
 
     /// Add component state for hydration
@@ -183,6 +187,11 @@ impl SSRRenderer {
     pub fn set_hydration_script(&mut self, script: String) {
         self.hydration_script = script;
     }
+
+    /// Expand component nodes with the factories in `registry`
+    pub fn set_registry(&mut self, registry: Rc<ComponentRegistry>) {
+        self.registry = Some(registry);
+    }
 }
 
 impl Default for SSRRenderer {
@@ -207,58 +216,80 @@ impl StreamingSSRRenderer {
     }
 
     /// Render a VNode and return chunks
+    ///
+    /// Portal content follows the rest of the page.
     pub fn render_vnode(&mut self, vnode: &VNode) -> Vec<String> {
         self.chunks.clear();
-        let mut buffer = String::new();
-
-        self.render_vnode_to_buffer(vnode, &mut buffer);
-
-        // Split into chunks
-        for chunk in buffer.as_bytes().chunks(self.chunk_size) {
-            self.chunks.push(String::from_utf8_lossy(chunk).to_string());
+        let mut html = HtmlWriter::new();
+        let mut portals = String::new();
+
+        self.render_vnode_to_buffer(vnode, &mut html, &mut portals);
+        let mut buffer = html.finish();
+        buffer.push_str(&portals);
+
+        // Split into chunks, never inside a character
+        let mut rest = buffer.as_str();
+        while !rest.is_empty() {
+            let mut end = self.chunk_size.clamp(1, rest.len());
+            while !rest.is_char_boundary(end) {
+                end += 1;
+            }
+            self.chunks.push(rest[..end].to_string());
+            rest = &rest[end..];
         }
 
         self.chunks.clone()
     }
 
     #[allow(clippy::only_used_in_recursion)]
-    fn render_vnode_to_buffer(&self, vnode: &VNode, buffer: &mut String) {
+    fn render_vnode_to_buffer(&self, vnode: &VNode, html: &mut HtmlWriter, portals: &mut String) {
         match vnode {
             VNode::Element(element) => {
-                buffer.push('<');
-                buffer.push_str(&element.tag);
-
-                for (key, value) in &element.attrs {
-                    buffer.push(' ');
-                    buffer.push_str(key);
-                    buffer.push_str("=\"");
-                    buffer.push_str(&SSRRenderer::escape_attribute(value));
-                    buffer.push('"');
-                }
-
-                if element.children.is_empty() && SSRRenderer::is_void_element(&element.tag) {
-                    buffer.push_str(" />");
-                } else {
-                    buffer.push('>');
+                let attrs = element.current_attrs();
+                html.element(&element.tag, attributes(&attrs), |html| {
                     for child in &element.children {
-                        self.render_vnode_to_buffer(child, buffer);
+                        self.render_vnode_to_buffer(child, html, portals);
                     }
-                    buffer.push_str("</");
-                    buffer.push_str(&element.tag);
-                    buffer.push('>');
-                }
+                });
             }
-            VNode::Text(text) => {
-                buffer.push_str(&SSRRenderer::escape_html(&text.content));
+            VNode::Text(text) => html.text(&text.content),
+            VNode::Dynamic(binding) => html.text(&binding.get()),
+            VNode::Component(_) => html.comment(" Component "),
+            VNode::Fragment(children) => {
+                for child in children {
+                    self.render_vnode_to_buffer(child, html, portals);
+                }
             }
-            VNode::Component(_) => {
-                buffer.push_str("<!-- Component -->");
+            VNode::Portal { target, children } => {
+                html.comment("portal");
+                let mut content = HtmlWriter::new();
+                for child in children {
+                    self.render_vnode_to_buffer(child, &mut content, portals);
+                }
+                portals.push_str(&portal_container(target, &content.finish()));
             }
+            VNode::Memo(memo) => self.render_vnode_to_buffer(&memo.node, html, portals),
             VNode::Empty => {}
         }
     }
 }
 
+/// Attributes as the serializer takes them
+fn attributes(attrs: &HashMap<String, String>) -> impl Iterator<Item = (&str, &str)> {
+    attrs
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+}
+
+/// The element holding a portal's content, as the web renderer creates it
+fn portal_container(target: &str, html: &str) -> String {
+    format!(
+        "<div data-portal=\"{}\">{}</div>",
+        escape_attribute(target),
+        html
+    )
+}
+
 /// Hydration helper for client-side
 pub struct Hydration {
     state: HashMap<String, String>,
@@ -305,6 +336,7 @@ impl Hydration {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vdom::VText;
 
     #[test]
     fn test_ssr_render_simple_element() {
@@ -426,4 +458,138 @@ mod tests {
         assert!(renderer.html.contains("&quot;"));
         assert!(renderer.html.contains("&lt;script&gt;"));
     }
+
+    #[test]
+    fn test_ssr_output_is_deterministic_and_shared_with_streaming() {
+        let vnode = VNode::Element(
+            VElement::new("form")
+                .attr("novalidate", "true")
+                .attr("method", "post")
+                .attr("action", "/save")
+                .attr("x\" onload=\"alert(1)", "1")
+                .child(VNode::Element(
+                    VElement::new("script").child("if (a < b) { end('</script>'); }".into()),
+                ))
+                .child(VNode::Element(
+                    VElement::new("svg")
+                        .attr("viewBox", "0 0 8 8")
+                        .child(VNode::Element(VElement::new("circle").attr("r", "4"))),
+                )),
+        );
+        let expected = "<form action=\"/save\" method=\"post\" novalidate>\
+                        <script>if (a < b) { end('<\\/script>'); }</script>\
+                        <svg viewBox=\"0 0 8 8\"><circle r=\"4\" /></svg></form>";
+
+        for _ in 0..5 {
+            let mut renderer = SSRRenderer::new();
+            renderer.render_vnode(&vnode);
+            assert_eq!(renderer.html, expected);
+        }
+        assert_eq!(
+            StreamingSSRRenderer::new(7).render_vnode(&vnode).concat(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_streaming_chunks_keep_characters_whole() {
+        let vnode = VNode::Element(VElement::new("p").child("héllo wörld".into()));
+        let chunks = StreamingSSRRenderer::new(4).render_vnode(&vnode);
+        assert!(chunks.iter().all(|chunk| !chunk.contains('\u{FFFD}')));
+        assert_eq!(chunks.concat(), "<p>héllo wörld</p>");
+    }
+
+    #[test]
+    fn test_ssr_fragments_and_portals() {
+        let vnode = VNode::Element(
+            VElement::new("tr")
+                .child(VNode::fragment(vec![
+                    VNode::Element(VElement::new("td").child("a".into())),
+                    VNode::Element(VElement::new("td").child("b".into())),
+                ]))
+                .child(VNode::portal(
+                    "body",
+                    vec![VNode::Element(VElement::new("div").attr("class", "modal"))],
+                )),
+        );
+
+        let mut renderer = SSRRenderer::new();
+        renderer.render_vnode(&vnode);
+        assert_eq!(renderer.html, "<tr><td>a</td><td>b</td><!--portal--></tr>");
+        assert_eq!(
+            renderer.portals(),
+            &[(
+                "body".to_string(),
+                "<div class=\"modal\"></div>".to_string()
+            )]
+        );
+        assert!(renderer
+            .body_portals()
+            .contains("<div data-portal=\"body\"><div class=\"modal\"></div></div>"));
+
+        let chunks = StreamingSSRRenderer::new(1024).render_vnode(&vnode);
+        assert_eq!(
+            chunks.concat(),
+            "<tr><td>a</td><td>b</td><!--portal--></tr><div data-portal=\"body\"><div class=\"modal\"></div></div>"
+        );
+    }
+
+    #[test]
+    fn test_ssr_expands_registered_components() {
+        use crate::vdom::VComponent;
+
+        struct Badge(String);
+
+        impl Component for Badge {
+            fn render(&self) -> VNode {
+                VNode::Element(VElement::new("span").child(self.0.as_str().into()))
+            }
+        }
+
+        struct Page;
+
+        impl Component for Page {
+            fn render(&self) -> VNode {
+                VNode::Element(
+                    VElement::new("main")
+                        .child(VComponent::new("Badge").prop("label", "new").into())
+                        .child(VComponent::new("Unknown").into()),
+                )
+            }
+        }
+
+        let mut registry = ComponentRegistry::new();
+        registry.register("Badge", |props| Badge(props["label"].clone()));
+
+        let mut renderer = SSRRenderer::new();
+        renderer.set_registry(Rc::new(registry));
+        assert_eq!(
+            renderer.render_to_string(Page),
+            "<main><span>new</span><!-- Component not expanded --></main>"
+        );
+    }
+
+    #[test]
+    fn test_document_state_cannot_close_its_script() {
+        struct Empty;
+
+        impl Component for Empty {
+            fn render(&self) -> VNode {
+                VNode::Empty
+            }
+        }
+
+        let payload = "</script><script>alert(1)</script><!--";
+        let mut renderer = SSRRenderer::new();
+        renderer.add_state("note".to_string(), payload.to_string());
+        let document = renderer.render_to_document(Empty, "Notes");
+
+        assert!(!document.contains("</script><script>alert"));
+        assert!(!document.contains("<!--"));
+
+        let start = document.find("type=\"application/json\">").unwrap() + 24;
+        let end = start + document[start..].find("</script>").unwrap();
+        let hydration = Hydration::from_state(document[start..end].trim()).unwrap();
+        assert_eq!(hydration.get("note"), Some(&payload.to_string()));
+    }
 }
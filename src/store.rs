@@ -0,0 +1,404 @@
+//! Reactive store for a single state struct with per-field lenses
+//!
+//! A [`Store`] holds one plain struct. [`Store::at`] focuses on part of it
+//! and returns a [`Lens`], which reads and writes like a signal. Writes only
+//! notify subscribers of the path that was written and of the paths that
+//! contain or are contained in it, so an effect reading `selection` does not
+//! re-run when `zoom` changes.
+//!
+//! `#[derive(Lenses)]` generates one lens method per field, so
+//! `store.at(|s| &s.zoom, |s| &mut s.zoom)` can be written `store.zoom()`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::reactivity::Effect;
+//! use windjammer_ui::store::Store;
+//!
+//! #[derive(Clone, Default)]
+//! struct Editor {
+//!     selection: Vec<u32>,
+//!     zoom: f32,
+//! }
+//!
+//! let store = Store::new(Editor::default());
+//! let selection = store.at(|s| &s.selection, |s| &mut s.selection);
+//! let zoom = store.at(|s| &s.zoom, |s| &mut s.zoom);
+//!
+//! Effect::new(move || println!("{} selected", selection.with(|s| s.len())));
+//!
+//! zoom.set(2.0); // The effect does not re-run
+//! ```
+
+use crate::reactivity::{batch, Signal};
+use std::cell::RefCell;
+use std::mem::size_of;
+use std::rc::Rc;
+
+/// Byte range of a field inside the store's root value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Path {
+    start: usize,
+    end: usize,
+}
+
+impl Path {
+    /// The whole value; also used for parts that live outside it (behind a
+    /// `Box` or `Vec`), whose position cannot be compared
+    fn root<T>() -> Self {
+        Self {
+            start: 0,
+            end: size_of::<T>().max(1),
+        }
+    }
+
+    fn overlaps(&self, other: &Path) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+struct StoreInner<T> {
+    value: RefCell<T>,
+    /// One trigger per path that has been read through
+    paths: RefCell<Vec<(Path, Signal<()>)>>,
+}
+
+/// Reactive container for a state struct
+pub struct Store<T> {
+    inner: Rc<StoreInner<T>>,
+}
+
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: 'static> Store<T> {
+    /// Create a store holding `value`
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Rc::new(StoreInner {
+                value: RefCell::new(value),
+                paths: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Get a clone of the whole value (tracks every path)
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Read the whole value by reference (tracks every path)
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.trigger(Path::root::<T>()).get();
+        self.with_untracked(f)
+    }
+
+    /// Read the whole value by reference without tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.value.borrow())
+    }
+
+    /// Replace the whole value and notify every path
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Modify the whole value and notify every path
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        self.notify(Path::root::<T>());
+    }
+
+    /// Focus on part of the value
+    ///
+    /// `read` and `write` must always return the same field; the path it
+    /// points to is recorded when the lens is created.
+    pub fn at<F: 'static>(
+        &self,
+        read: impl Fn(&T) -> &F + 'static,
+        write: impl Fn(&mut T) -> &mut F + 'static,
+    ) -> Lens<T, F> {
+        let read: Rc<dyn Fn(&T) -> &F> = Rc::new(read);
+        let path = self.locate(&*read);
+        Lens {
+            store: self.clone(),
+            read,
+            write: Rc::new(write),
+            path,
+        }
+    }
+
+    fn locate<F>(&self, read: &dyn Fn(&T) -> &F) -> Path {
+        let value = self.inner.value.borrow();
+        let root = &*value as *const T as usize;
+        let field = read(&value) as *const F as usize;
+        match field.checked_sub(root) {
+            Some(start) if start + size_of::<F>() <= size_of::<T>() => Path {
+                start,
+                end: start + size_of::<F>().max(1),
+            },
+            _ => Path::root::<T>(),
+        }
+    }
+
+    fn trigger(&self, path: Path) -> Signal<()> {
+        let mut paths = self.inner.paths.borrow_mut();
+        if let Some((_, trigger)) = paths.iter().find(|(p, _)| *p == path) {
+            return trigger.clone();
+        }
+        let trigger = Signal::new(());
+        paths.push((path, trigger.clone()));
+        trigger
+    }
+
+    /// Notify every path overlapping the one that was written
+    fn notify(&self, written: Path) {
+        let triggers: Vec<Signal<()>> = self
+            .inner
+            .paths
+            .borrow()
+            .iter()
+            .filter(|(path, _)| path.overlaps(&written))
+            .map(|(_, trigger)| trigger.clone())
+            .collect();
+        batch(|| {
+            for trigger in triggers {
+                trigger.set(());
+            }
+        });
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Store<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Store")
+            .field(&*self.inner.value.borrow())
+            .finish()
+    }
+}
+
+/// Read/write view of one part of a [`Store`]
+pub struct Lens<T, F> {
+    store: Store<T>,
+    read: Rc<dyn Fn(&T) -> &F>,
+    write: Rc<dyn Fn(&mut T) -> &mut F>,
+    path: Path,
+}
+
+impl<T, F> Clone for Lens<T, F> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            read: Rc::clone(&self.read),
+            write: Rc::clone(&self.write),
+            path: self.path,
+        }
+    }
+}
+
+impl<T: 'static, F: 'static> Lens<T, F> {
+    /// Get a clone of the focused value (tracks this path)
+    pub fn get(&self) -> F
+    where
+        F: Clone,
+    {
+        self.with(F::clone)
+    }
+
+    /// Get a clone of the focused value without tracking
+    pub fn get_untracked(&self) -> F
+    where
+        F: Clone,
+    {
+        self.with_untracked(F::clone)
+    }
+
+    /// Read the focused value by reference (tracks this path)
+    pub fn with<R>(&self, f: impl FnOnce(&F) -> R) -> R {
+        self.store.trigger(self.path).get();
+        self.with_untracked(f)
+    }
+
+    /// Read the focused value by reference without tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&F) -> R) -> R {
+        f((self.read)(&self.store.inner.value.borrow()))
+    }
+
+    /// Replace the focused value and notify its path
+    pub fn set(&self, value: F) {
+        self.update(|current| *current = value);
+    }
+
+    /// Modify the focused value and notify its path
+    pub fn update(&self, f: impl FnOnce(&mut F)) {
+        {
+            let mut value = self.store.inner.value.borrow_mut();
+            f((self.write)(&mut value));
+        }
+        self.store.notify(self.path);
+    }
+
+    /// Focus further into the value
+    pub fn at<G: 'static>(
+        &self,
+        read: impl Fn(&F) -> &G + 'static,
+        write: impl Fn(&mut F) -> &mut G + 'static,
+    ) -> Lens<T, G> {
+        let (outer_read, outer_write) = (Rc::clone(&self.read), Rc::clone(&self.write));
+        self.store.at(
+            move |root| read(outer_read(root)),
+            move |root| write(outer_write(root)),
+        )
+    }
+
+    /// The store this lens reads from
+    pub fn store(&self) -> &Store<T> {
+        &self.store
+    }
+}
+
+impl<T: 'static, F: std::fmt::Debug + 'static> std::fmt::Debug for Lens<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with_untracked(|value| f.debug_tuple("Lens").field(value).finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::Effect;
+    use std::cell::Cell;
+
+    #[derive(Clone, Default, Debug, PartialEq)]
+    struct Selection {
+        start: usize,
+        end: usize,
+    }
+
+    #[derive(Clone, Default)]
+    struct Editor {
+        selection: Selection,
+        zoom: f32,
+        layers: Vec<String>,
+    }
+
+    fn runs<F: 'static>(read: impl Fn() -> F + 'static) -> Rc<Cell<usize>> {
+        let runs = Rc::new(Cell::new(0));
+        let runs_c = runs.clone();
+        Effect::new(move || {
+            read();
+            runs_c.set(runs_c.get() + 1);
+        });
+        runs
+    }
+
+    #[test]
+    fn test_field_writes_only_notify_their_path() {
+        let store = Store::new(Editor::default());
+        let selection = store.at(|s| &s.selection, |s| &mut s.selection);
+        let zoom = store.at(|s| &s.zoom, |s| &mut s.zoom);
+
+        let selection_runs = runs({
+            let selection = selection.clone();
+            move || selection.get()
+        });
+        let zoom_runs = runs({
+            let zoom = zoom.clone();
+            move || zoom.get()
+        });
+
+        zoom.set(2.0);
+        assert_eq!((selection_runs.get(), zoom_runs.get()), (1, 2));
+
+        selection.update(|s| s.end = 4);
+        assert_eq!((selection_runs.get(), zoom_runs.get()), (2, 2));
+        assert_eq!(store.get().selection, Selection { start: 0, end: 4 });
+    }
+
+    #[test]
+    fn test_nested_paths_notify_parents_and_children() {
+        let store = Store::new(Editor::default());
+        let selection = store.at(|s| &s.selection, |s| &mut s.selection);
+        let start = selection.at(|s| &s.start, |s| &mut s.start);
+        let end = store.at(|s| &s.selection.end, |s| &mut s.selection.end);
+
+        let selection_runs = runs({
+            let selection = selection.clone();
+            move || selection.get()
+        });
+        let start_runs = runs({
+            let start = start.clone();
+            move || start.get()
+        });
+        let end_runs = runs({
+            let end = end.clone();
+            move || end.get()
+        });
+        let root_runs = runs({
+            let store = store.clone();
+            move || store.with(|s| s.zoom)
+        });
+
+        // A child write reaches its parents but not its siblings
+        start.set(3);
+        assert_eq!(selection_runs.get(), 2);
+        assert_eq!(start_runs.get(), 2);
+        assert_eq!(end_runs.get(), 1);
+        assert_eq!(root_runs.get(), 2);
+
+        // A parent write reaches all of its children
+        selection.set(Selection { start: 1, end: 2 });
+        assert_eq!(start_runs.get(), 3);
+        assert_eq!(end_runs.get(), 2);
+
+        // A whole-store write reaches everything
+        store.update(|s| s.zoom = 0.5);
+        assert_eq!(
+            (selection_runs.get(), start_runs.get(), end_runs.get()),
+            (4, 4, 3)
+        );
+    }
+
+    #[test]
+    fn test_reads_can_nest() {
+        let store = Store::new(Editor::default());
+        let selection = store.at(|s| &s.selection, |s| &mut s.selection);
+        let zoom = store.at(|s| &s.zoom, |s| &mut s.zoom);
+        zoom.set(1.5);
+
+        let (end, zoom) = selection.with(|s| (s.end, zoom.get()));
+        assert_eq!((end, zoom), (0, 1.5));
+        let start = store.with(|_| selection.at(|s| &s.start, |s| &mut s.start).get());
+        assert_eq!(start, 0);
+    }
+
+    #[test]
+    fn test_heap_paths_fall_back_to_the_whole_store() {
+        let store = Store::new(Editor {
+            layers: vec!["background".to_string()],
+            ..Default::default()
+        });
+        let first_layer = store.at(|s| &s.layers[0], |s| &mut s.layers[0]);
+        let zoom = store.at(|s| &s.zoom, |s| &mut s.zoom);
+
+        let layer_runs = runs({
+            let first_layer = first_layer.clone();
+            move || first_layer.get()
+        });
+
+        // Its position is unknown, so any write may have changed it
+        zoom.set(3.0);
+        assert_eq!(layer_runs.get(), 2);
+        first_layer.set("sky".to_string());
+        assert_eq!(layer_runs.get(), 3);
+        assert_eq!(store.get().layers, vec!["sky".to_string()]);
+    }
+}
@@ -0,0 +1,416 @@
+//! Thread-safe signals for feeding background work into the UI
+//!
+//! [`Signal`] is `Rc`-based and must stay on the UI thread. A [`SyncSignal`]
+//! can be cloned into background tasks (file indexing, asset loading, tokio
+//! tasks on desktop) and written from any thread.
+//!
+//! Reads on the thread that created the signal (the UI thread) are tracked
+//! like any other signal. Writes from other threads are queued and the UI is
+//! woken through the waker registered with [`set_sync_waker`]; the UI thread
+//! then calls [`flush_sync_updates`], which runs the dependent effects and
+//! requests a repaint on the right thread. The desktop apps register the
+//! waker at startup and flush on every frame; repeated writes to a signal
+//! are delivered once.
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::reactivity::Effect;
+//! use windjammer_ui::sync_signal::{flush_sync_updates, SyncSignal};
+//!
+//! let progress = SyncSignal::new(0u32);
+//!
+//! Effect::new({
+//!     let progress = progress.clone();
+//!     move || println!("indexed {} files", progress.get())
+//! });
+//!
+//! let worker = {
+//!     let progress = progress.clone();
+//!     std::thread::spawn(move || progress.set(42))
+//! };
+//! worker.join().unwrap();
+//!
+//! flush_sync_updates(); // Prints: "indexed 42 files"
+//! ```
+
+use crate::reactivity::{batch, Signal};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, RwLock};
+use std::thread::{self, ThreadId};
+
+/// Unique identifier for sync signals
+pub type SyncSignalId = usize;
+
+/// Callback waking the UI thread after a cross-thread write
+type SyncWaker = Arc<dyn Fn() + Send + Sync>;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes from other threads waiting for their home thread to flush them,
+/// one entry per signal however often it is written
+static PENDING: LazyLock<Mutex<HashMap<(ThreadId, SyncSignalId), PendingUpdate>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Threads that created sync signals and are still running
+static HOMES: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+
+static WAKER: Mutex<Option<SyncWaker>> = Mutex::new(None);
+
+thread_local! {
+    /// Per-signal triggers on the home thread; reading one tracks the signal
+    static TRIGGERS: RefCell<HashMap<SyncSignalId, Signal<()>>> = RefCell::new(HashMap::new());
+    /// Registers the thread in [`HOMES`] until it exits
+    static HOME: Home = Home::register();
+}
+
+/// Work queued for a signal's home thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingUpdate {
+    Changed,
+    Dropped,
+}
+
+/// Marks the current thread as a home thread while it runs
+struct Home(ThreadId);
+
+impl Home {
+    fn register() -> Self {
+        let id = thread::current().id();
+        lock(&HOMES).push(id);
+        Self(id)
+    }
+}
+
+impl Drop for Home {
+    fn drop(&mut self) {
+        // Nobody is left to flush writes for this thread
+        lock(&HOMES).retain(|home| *home != self.0);
+        lock(&PENDING).retain(|(home, _), _| *home != self.0);
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct SyncInner<T> {
+    id: SyncSignalId,
+    value: RwLock<T>,
+    /// Thread whose effects observe this signal
+    home: ThreadId,
+}
+
+impl<T> Drop for SyncInner<T> {
+    fn drop(&mut self) {
+        if thread::current().id() == self.home {
+            // The thread-local may already be gone while the thread shuts down
+            let _ = TRIGGERS.try_with(|triggers| triggers.borrow_mut().remove(&self.id));
+        } else {
+            enqueue(self.home, self.id, PendingUpdate::Dropped);
+        }
+    }
+}
+
+/// Signal that can be shared with and written from any thread
+///
+/// Notifications are always delivered on the thread that created the signal.
+pub struct SyncSignal<T> {
+    inner: Arc<SyncInner<T>>,
+}
+
+impl<T> Clone for SyncSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> SyncSignal<T> {
+    /// Create a signal whose effects run on the current thread
+    pub fn new(value: T) -> Self {
+        HOME.with(|_| ());
+        Self {
+            inner: Arc::new(SyncInner {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                value: RwLock::new(value),
+                home: thread::current().id(),
+            }),
+        }
+    }
+
+    /// Get the current value (tracks dependency on the home thread)
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Get the current value without tracking
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with_untracked(T::clone)
+    }
+
+    /// Read the value by reference (tracks dependency on the home thread)
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        if self.is_home_thread() {
+            self.trigger().get();
+        }
+        self.with_untracked(f)
+    }
+
+    /// Read the value by reference without tracking
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let value = self
+            .inner
+            .value
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&value)
+    }
+
+    /// Set a new value and notify subscribers on the home thread
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Update the value using a function and notify subscribers on the home thread
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        {
+            let mut value = self
+                .inner
+                .value
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&mut value);
+        }
+        self.notify();
+    }
+
+    /// Get the signal's unique ID
+    pub fn id(&self) -> SyncSignalId {
+        self.inner.id
+    }
+
+    fn is_home_thread(&self) -> bool {
+        thread::current().id() == self.inner.home
+    }
+
+    fn notify(&self) {
+        if self.is_home_thread() {
+            notify_local(self.inner.id);
+        } else {
+            enqueue(self.inner.home, self.inner.id, PendingUpdate::Changed);
+            let waker = WAKER.lock().ok().and_then(|waker| waker.clone());
+            if let Some(waker) = waker {
+                waker();
+            }
+        }
+    }
+
+    fn trigger(&self) -> Signal<()> {
+        TRIGGERS.with(|triggers| {
+            triggers
+                .borrow_mut()
+                .entry(self.inner.id)
+                .or_insert_with(|| Signal::new(()))
+                .clone()
+        })
+    }
+}
+
+impl<T: Send + Sync + std::fmt::Debug> std::fmt::Debug for SyncSignal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self
+            .inner
+            .value
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f.debug_struct("SyncSignal")
+            .field("id", &self.inner.id)
+            .field("value", &*value)
+            .finish()
+    }
+}
+
+/// Queue `update` for `home`, unless that thread has already exited
+fn enqueue(home: ThreadId, id: SyncSignalId, update: PendingUpdate) {
+    let mut pending = lock(&PENDING);
+    if !lock(&HOMES).contains(&home) {
+        return;
+    }
+    // A drop supersedes any change nobody will observe anymore
+    let entry = pending.entry((home, id)).or_insert(update);
+    if update == PendingUpdate::Dropped {
+        *entry = update;
+    }
+}
+
+fn notify_local(id: SyncSignalId) {
+    // A signal nobody has read on this thread has no trigger and no observers
+    let trigger = TRIGGERS.with(|triggers| triggers.borrow().get(&id).cloned());
+    if let Some(trigger) = trigger {
+        trigger.set(());
+    }
+}
+
+/// Register the callback that wakes the UI thread after a cross-thread write
+///
+/// On desktop this is `egui::Context::request_repaint`, which is safe to call
+/// from any thread.
+pub fn set_sync_waker<F: Fn() + Send + Sync + 'static>(waker: F) {
+    if let Ok(mut slot) = WAKER.lock() {
+        *slot = Some(Arc::new(waker));
+    }
+}
+
+/// Remove the waker (for cleanup)
+pub fn clear_sync_waker() {
+    if let Ok(mut slot) = WAKER.lock() {
+        *slot = None;
+    }
+}
+
+/// Deliver queued cross-thread writes to effects on the current thread
+///
+/// All effects run in a single batch, so the UI re-renders at most once.
+/// Returns the number of changed signals delivered.
+pub fn flush_sync_updates() -> usize {
+    let current = thread::current().id();
+    let updates: Vec<(SyncSignalId, PendingUpdate)> = {
+        let mut pending = lock(&PENDING);
+        let mine: Vec<_> = pending
+            .keys()
+            .filter(|(home, _)| *home == current)
+            .copied()
+            .collect();
+        mine.into_iter()
+            .filter_map(|key| pending.remove(&key).map(|update| (key.1, update)))
+            .collect()
+    };
+
+    let mut delivered = 0;
+    batch(|| {
+        for (id, update) in updates {
+            match update {
+                PendingUpdate::Changed => {
+                    notify_local(id);
+                    delivered += 1;
+                }
+                PendingUpdate::Dropped => {
+                    TRIGGERS.with(|triggers| triggers.borrow_mut().remove(&id));
+                }
+            }
+        }
+    });
+    delivered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactivity::Effect;
+    use std::rc::Rc;
+
+    fn counting_effect(signal: &SyncSignal<i32>) -> Rc<RefCell<Vec<i32>>> {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (signal, seen_c) = (signal.clone(), seen.clone());
+        Effect::new(move || seen_c.borrow_mut().push(signal.get()));
+        seen
+    }
+
+    #[test]
+    fn test_home_thread_writes_notify_immediately() {
+        let signal = SyncSignal::new(1);
+        let seen = counting_effect(&signal);
+
+        signal.set(2);
+        signal.update(|v| *v += 1);
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_background_writes_are_marshalled() {
+        let signal = SyncSignal::new(0);
+        let seen = counting_effect(&signal);
+
+        let worker = {
+            let signal = signal.clone();
+            thread::spawn(move || {
+                for i in 1..=3 {
+                    signal.set(i);
+                }
+            })
+        };
+        worker.join().unwrap();
+
+        // The value is visible right away, effects wait for the flush
+        assert_eq!(signal.get_untracked(), 3);
+        assert_eq!(*seen.borrow(), vec![0]);
+
+        // Three queued writes collapse into one update and one effect run
+        assert_eq!(flush_sync_updates(), 1);
+        assert_eq!(*seen.borrow(), vec![0, 3]);
+        assert_eq!(flush_sync_updates(), 0);
+    }
+
+    #[test]
+    fn test_flush_only_delivers_own_signals() {
+        let signal = SyncSignal::new(0);
+        let seen = counting_effect(&signal);
+
+        // A signal homed on another thread stays queued for that thread
+        let (signal_tx, signal_rx) = std::sync::mpsc::channel();
+        let (exit_tx, exit_rx) = std::sync::mpsc::channel::<()>();
+        let home = thread::spawn(move || {
+            signal_tx.send(SyncSignal::new(0)).unwrap();
+            let _ = exit_rx.recv();
+        });
+        let foreign = signal_rx.recv().unwrap();
+        let home_id = home.thread().id();
+        let queued = || lock(&PENDING).contains_key(&(home_id, foreign.id()));
+        foreign.set(5);
+        foreign.set(6);
+
+        assert_eq!(flush_sync_updates(), 0);
+        assert_eq!(*seen.borrow(), vec![0]);
+        assert!(queued());
+
+        // Its writes are discarded once the home thread is gone
+        exit_tx.send(()).unwrap();
+        home.join().unwrap();
+        assert!(!queued());
+        foreign.set(7);
+        assert!(!queued());
+    }
+
+    #[test]
+    fn test_waker_called_for_cross_thread_writes() {
+        let woken = Arc::new(AtomicUsize::new(0));
+        let woken_c = woken.clone();
+        set_sync_waker(move || {
+            woken_c.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let signal = SyncSignal::new(String::new());
+        let worker = {
+            let signal = signal.clone();
+            thread::spawn(move || signal.set("loaded".to_string()))
+        };
+        worker.join().unwrap();
+        clear_sync_waker();
+
+        assert!(woken.load(Ordering::SeqCst) >= 1);
+        flush_sync_updates();
+        assert_eq!(signal.get(), "loaded");
+    }
+}
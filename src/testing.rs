@@ -0,0 +1,827 @@
+//! Headless testing of rendered components
+//!
+//! [`Screen`] mounts a render function into a [`MemoryRenderer`] and keeps it
+//! up to date: the render runs in an effect, so a signal it read triggers a
+//! new render, which is diffed and patched into the in-memory DOM like in a
+//! browser. Tests find elements the way a user would (by text, role, label
+//! or test id), fire events at them, and read the updated tree.
+//!
+//! Events bubble from the target to its ancestors, and the matching
+//! [`Event`] is available to handlers through [`current_event`]. Timers run
+//! on a [`VirtualClock`] that only moves with [`Screen::advance`] and
+//! [`Screen::wait_for`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use windjammer_ui::reactivity::Signal;
+//! use windjammer_ui::testing::Screen;
+//! use windjammer_ui::vdom::{VElement, VNode};
+//!
+//! let count = Signal::new(0);
+//! let count_c = count.clone();
+//! let screen = Screen::render(move || -> VNode {
+//!     let count = count_c.clone();
+//!     VElement::new("div")
+//!         .child(format!("Clicked {} times", count.get()).into())
+//!         .child(
+//!             VElement::new("button")
+//!                 .on("click", move || count.update(|c| *c += 1))
+//!                 .child("Add".into())
+//!                 .into(),
+//!         )
+//!         .into()
+//! });
+//!
+//! screen.click(&screen.get_by_role("button"));
+//! screen.get_by_text("Clicked 1 times");
+//! ```
+//!
+//! [`current_event`]: crate::events::current_event
+
+use crate::component::ComponentRegistry;
+use crate::events::{with_current_event, Event, Modifiers, MouseButton};
+use crate::memory_dom::{DomElement, DomNode, MemoryRenderer};
+use crate::reactivity::{create_root, Effect, Scope};
+use crate::reconciler::Reconciler;
+use crate::renderer::Renderer;
+use crate::scheduler::{replace_scheduler, Scheduler, VirtualClock};
+use crate::vdom::{diff, VNode};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Virtual time [`Screen::wait_for`] gives up after
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Virtual time between two checks of [`Screen::wait_for`]
+const WAIT_STEP: Duration = Duration::from_millis(1);
+
+/// How to find an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum By<'a> {
+    /// Elements whose own text nodes, joined and trimmed, equal the given
+    /// text; text inside child elements does not count
+    Text(&'a str),
+    /// Elements with this ARIA role, explicit or implied by the tag
+    Role(&'a str),
+    /// Elements whose `data-testid` attribute equals the given id
+    TestId(&'a str),
+    /// Form controls labelled by a `<label>` with this text, or with this
+    /// `aria-label`
+    Label(&'a str),
+}
+
+impl fmt::Display for By<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            By::Text(text) => write!(f, "text {:?}", text),
+            By::Role(role) => write!(f, "role {:?}", role),
+            By::TestId(id) => write!(f, "test id {:?}", id),
+            By::Label(label) => write!(f, "label {:?}", label),
+        }
+    }
+}
+
+/// An element found on a [`Screen`], as it was when found
+///
+/// `path` uses the same indices as [`crate::vdom::Patch`] paths, except that
+/// a portal's content continues the path of its placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Found {
+    pub path: Vec<usize>,
+    pub element: DomElement,
+}
+
+impl Found {
+    /// Concatenated text of the element and its descendants
+    pub fn text(&self) -> String {
+        self.element
+            .children
+            .iter()
+            .map(DomNode::text_content)
+            .collect()
+    }
+
+    /// Attribute or property value
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.element
+            .attributes
+            .get(name)
+            .or_else(|| self.element.properties.get(name))
+            .map(String::as_str)
+    }
+}
+
+/// A render function mounted headlessly for tests
+///
+/// Dropping the screen stops rendering and unmounts its components.
+pub struct Screen {
+    dom: Rc<RefCell<MemoryRenderer>>,
+    reconciler: Rc<RefCell<Option<Reconciler>>>,
+    renders: Rc<Cell<usize>>,
+    clock: VirtualClock,
+    /// Scheduler to restore when the screen is dropped
+    previous_scheduler: Option<Rc<dyn Scheduler>>,
+    scope: Scope,
+}
+
+impl Screen {
+    /// Mount `render` and render it for the first time
+    ///
+    /// Installs a fresh [`VirtualClock`] as the thread's scheduler until the
+    /// screen is dropped; timers started from then on run as the screen
+    /// advances it.
+    pub fn render<V, F>(render: F) -> Self
+    where
+        V: Into<VNode>,
+        F: Fn() -> V + 'static,
+    {
+        Self::mount(None, render)
+    }
+
+    /// Like [`Screen::render`], expanding the components of `registry`
+    pub fn render_with_registry<V, F>(registry: Rc<ComponentRegistry>, render: F) -> Self
+    where
+        V: Into<VNode>,
+        F: Fn() -> V + 'static,
+    {
+        Self::mount(Some(Reconciler::new(registry)), render)
+    }
+
+    fn mount<V, F>(reconciler: Option<Reconciler>, render: F) -> Self
+    where
+        V: Into<VNode>,
+        F: Fn() -> V + 'static,
+    {
+        let clock = VirtualClock::new();
+        let previous_scheduler = replace_scheduler(Some(Rc::new(clock.clone())));
+
+        let dom = Rc::new(RefCell::new(MemoryRenderer::new()));
+        dom.borrow_mut()
+            .init()
+            .expect("memory renderer initializes");
+        let reconciler = Rc::new(RefCell::new(reconciler));
+        let renders = Rc::new(Cell::new(0));
+
+        let (dom_c, reconciler_c, renders_c) = (dom.clone(), reconciler.clone(), renders.clone());
+        let scope = create_root(|scope| {
+            // Components outlive the effect's runs, so their scopes hang off
+            // the screen instead of the effect
+            let components = scope.child();
+            let previous = RefCell::new(None::<VNode>);
+            Effect::new_labeled("Screen::render", move || {
+                let mut node = render().into();
+                if let Some(reconciler) = reconciler_c.borrow_mut().as_mut() {
+                    if let Some(run_scope) = Scope::current() {
+                        components.inherit_contexts(&run_scope);
+                    }
+                    node = components.run(|| reconciler.expand(&node));
+                }
+                renders_c.set(renders_c.get() + 1);
+
+                let mut dom = dom_c.borrow_mut();
+                let result = match previous.borrow().as_ref() {
+                    Some(old) => dom.patch(&diff(old, &node)),
+                    None => dom.render(&node),
+                };
+                if let Err(e) = result {
+                    panic!("Failed to render: {}", e);
+                }
+                *previous.borrow_mut() = Some(node);
+            });
+            scope
+        });
+
+        Self {
+            dom,
+            reconciler,
+            renders,
+            clock,
+            previous_scheduler,
+            scope,
+        }
+    }
+
+    /// Number of times the render function has run
+    pub fn renders(&self) -> usize {
+        self.renders.get()
+    }
+
+    /// The rendered DOM
+    pub fn dom(&self) -> MemoryRenderer {
+        self.dom.borrow().clone()
+    }
+
+    /// All rendered text
+    pub fn text(&self) -> String {
+        self.dom
+            .borrow()
+            .roots()
+            .iter()
+            .map(DomNode::text_content)
+            .collect()
+    }
+
+    /// Every element matching `by`, in document order
+    pub fn get_all(&self, by: By) -> Vec<Found> {
+        let mut elements = Vec::new();
+        collect(&self.resolved(), &mut Vec::new(), &mut elements);
+
+        match by {
+            By::Text(text) => elements
+                .into_iter()
+                .filter(|found| own_text(&found.element).trim() == text)
+                .collect(),
+            By::Role(role) => elements
+                .into_iter()
+                .filter(|found| role_of(&found.element).as_deref() == Some(role))
+                .collect(),
+            By::TestId(id) => elements
+                .into_iter()
+                .filter(|found| found.attr("data-testid") == Some(id))
+                .collect(),
+            By::Label(label) => {
+                let labelled: Vec<Vec<usize>> = elements
+                    .iter()
+                    .filter(|found| found.element.tag == "label" && found.text().trim() == label)
+                    .flat_map(|found| labelled_by(found, &elements))
+                    .collect();
+                elements
+                    .into_iter()
+                    .filter(|found| {
+                        found.attr("aria-label") == Some(label) || labelled.contains(&found.path)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// The only element matching `by`, if any
+    ///
+    /// Panics if several elements match.
+    pub fn query(&self, by: By) -> Option<Found> {
+        let mut found = self.get_all(by);
+        if found.len() > 1 {
+            panic!(
+                "Found {} elements with {}:\n{}",
+                found.len(),
+                by,
+                self.pretty()
+            );
+        }
+        found.pop()
+    }
+
+    /// The only element matching `by`
+    ///
+    /// Panics, showing the rendered DOM, unless exactly one element matches.
+    pub fn get(&self, by: By) -> Found {
+        self.query(by)
+            .unwrap_or_else(|| panic!("No element with {}:\n{}", by, self.pretty()))
+    }
+
+    /// The only element whose text equals `text`, see [`By::Text`]
+    pub fn get_by_text(&self, text: &str) -> Found {
+        self.get(By::Text(text))
+    }
+
+    /// The only element with `role`, see [`By::Role`]
+    pub fn get_by_role(&self, role: &str) -> Found {
+        self.get(By::Role(role))
+    }
+
+    /// The only element with `data-testid="id"`
+    pub fn get_by_test_id(&self, id: &str) -> Found {
+        self.get(By::TestId(id))
+    }
+
+    /// The only form control labelled `label`, see [`By::Label`]
+    pub fn get_by_label(&self, label: &str) -> Found {
+        self.get(By::Label(label))
+    }
+
+    /// Fire `name` at `target` and let it bubble up to the root
+    ///
+    /// Each handler runs with `event` as the current event. Returns false if
+    /// no handler ran.
+    pub fn fire(&self, target: &Found, name: &str, event: Event) -> bool {
+        let mut handled = false;
+        for depth in (1..=target.path.len()).rev() {
+            // Looked up again each time: a handler may have re-rendered
+            let listener = self
+                .element_at(&target.path[..depth])
+                .and_then(|element| element.listeners.get(name).cloned());
+            if let Some(listener) = listener {
+                with_current_event(event.clone(), || listener.call());
+                handled = true;
+            }
+        }
+        handled
+    }
+
+    /// Click `target`
+    pub fn click(&self, target: &Found) -> bool {
+        self.fire(
+            target,
+            "click",
+            Event::Click {
+                x: 0.0,
+                y: 0.0,
+                button: MouseButton::Left,
+            },
+        )
+    }
+
+    /// Type `value` into `target`: set its `value` and fire `input`
+    pub fn input(&self, target: &Found, value: &str) -> bool {
+        self.with_element_mut(&target.path, |element| {
+            element
+                .properties
+                .insert("value".to_string(), value.to_string());
+        });
+        self.fire(
+            target,
+            "input",
+            Event::Input {
+                value: value.to_string(),
+            },
+        )
+    }
+
+    /// Press `key` on `target`, firing `keydown`
+    pub fn key_down(&self, target: &Found, key: &str) -> bool {
+        self.fire(
+            target,
+            "keydown",
+            Event::KeyDown {
+                key: key.to_string(),
+                modifiers: Modifiers::default(),
+            },
+        )
+    }
+
+    /// Move the virtual clock forward, running the timers that come due
+    pub fn advance(&self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    /// Deliver pending cross-thread writes and frame callbacks, then move
+    /// the virtual clock until `condition` holds
+    ///
+    /// Panics, showing the rendered DOM, if it still does not hold after
+    /// five seconds of virtual time.
+    pub fn wait_for(&self, condition: impl Fn(&Screen) -> bool) {
+        let mut waited = Duration::ZERO;
+        loop {
+            crate::sync_signal::flush_sync_updates();
+            self.clock.run_frame();
+            if condition(self) {
+                return;
+            }
+            if waited >= WAIT_TIMEOUT {
+                panic!(
+                    "Condition still false after {:?}:\n{}",
+                    WAIT_TIMEOUT,
+                    self.pretty()
+                );
+            }
+            self.clock.advance(WAIT_STEP);
+            waited += WAIT_STEP;
+        }
+    }
+
+    /// The rendered DOM as indented markup, for failure messages
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        for node in self.resolved() {
+            write_pretty(&node, 0, &mut out);
+        }
+        out
+    }
+
+    /// The rendered nodes with bindings at their current values
+    fn resolved(&self) -> Vec<DomNode> {
+        self.dom
+            .borrow()
+            .roots()
+            .iter()
+            .map(DomNode::resolved)
+            .collect()
+    }
+
+    fn element_at(&self, path: &[usize]) -> Option<DomElement> {
+        match node_at(&self.resolved(), path)? {
+            DomNode::Element(element) => Some(element.clone()),
+            _ => None,
+        }
+    }
+
+    fn with_element_mut(&self, path: &[usize], f: impl FnOnce(&mut DomElement)) {
+        let mut dom = self.dom.borrow_mut();
+        if let Some(DomNode::Element(element)) = node_at_mut(dom.roots_mut(), path) {
+            f(element);
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if let Some(reconciler) = self.reconciler.borrow_mut().as_mut() {
+            reconciler.unmount();
+        }
+        self.scope.dispose();
+        replace_scheduler(self.previous_scheduler.take());
+    }
+}
+
+/// Collect every element under `nodes`, portal content included
+fn collect(nodes: &[DomNode], path: &mut Vec<usize>, out: &mut Vec<Found>) {
+    for (index, node) in nodes.iter().enumerate() {
+        path.push(index);
+        match node {
+            DomNode::Element(element) => {
+                out.push(Found {
+                    path: path.clone(),
+                    element: element.clone(),
+                });
+                collect(&element.children, path, out);
+            }
+            DomNode::Portal { children, .. } => collect(children, path, out),
+            DomNode::Text(_) | DomNode::Dynamic(_) => {}
+        }
+        path.pop();
+    }
+}
+
+fn node_at<'a>(nodes: &'a [DomNode], path: &[usize]) -> Option<&'a DomNode> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get(*first)?;
+    if rest.is_empty() {
+        return Some(node);
+    }
+    match node {
+        DomNode::Element(DomElement { children, .. }) | DomNode::Portal { children, .. } => {
+            node_at(children, rest)
+        }
+        DomNode::Text(_) | DomNode::Dynamic(_) => None,
+    }
+}
+
+fn node_at_mut<'a>(nodes: &'a mut [DomNode], path: &[usize]) -> Option<&'a mut DomNode> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get_mut(*first)?;
+    if rest.is_empty() {
+        return Some(node);
+    }
+    match node {
+        DomNode::Element(DomElement { children, .. }) | DomNode::Portal { children, .. } => {
+            node_at_mut(children, rest)
+        }
+        DomNode::Text(_) | DomNode::Dynamic(_) => None,
+    }
+}
+
+/// Text of the element's own text nodes
+fn own_text(element: &DomElement) -> String {
+    element
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            DomNode::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Paths of the controls a `<label>` labels: the one named by its `for`
+/// attribute, or the controls inside it
+fn labelled_by(label: &Found, elements: &[Found]) -> Vec<Vec<usize>> {
+    match label.attr("for") {
+        Some(id) => elements
+            .iter()
+            .filter(|found| found.attr("id") == Some(id))
+            .map(|found| found.path.clone())
+            .collect(),
+        None => elements
+            .iter()
+            .filter(|found| {
+                found.path.len() > label.path.len()
+                    && found.path.starts_with(&label.path)
+                    && matches!(
+                        found.element.tag.as_str(),
+                        "input" | "textarea" | "select" | "button"
+                    )
+            })
+            .map(|found| found.path.clone())
+            .collect(),
+    }
+}
+
+/// The explicit `role`, or the one implied by the tag
+fn role_of(element: &DomElement) -> Option<String> {
+    if let Some(role) = element.attributes.get("role") {
+        return Some(role.clone());
+    }
+    let input_type = element.attributes.get("type").map(String::as_str);
+    let role = match element.tag.as_str() {
+        "button" => "button",
+        "a" if element.attributes.contains_key("href") => "link",
+        "input" => match input_type {
+            Some("button" | "submit" | "reset") => "button",
+            Some("checkbox") => "checkbox",
+            Some("radio") => "radio",
+            Some("range") => "slider",
+            Some("number") => "spinbutton",
+            Some("search") => "searchbox",
+            Some("hidden") => return None,
+            _ => "textbox",
+        },
+        "textarea" => "textbox",
+        "select" => "combobox",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "ul" | "ol" => "list",
+        "li" => "listitem",
+        "img" => "img",
+        "nav" => "navigation",
+        "main" => "main",
+        "form" => "form",
+        "dialog" => "dialog",
+        "table" => "table",
+        "tr" => "row",
+        "td" => "cell",
+        "th" => "columnheader",
+        "progress" => "progressbar",
+        _ => return None,
+    };
+    Some(role.to_string())
+}
+
+fn write_pretty(node: &DomNode, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match node {
+        DomNode::Text(text) => out.push_str(&format!("{}{:?}\n", indent, text)),
+        DomNode::Dynamic(binding) => {
+            out.push_str(&format!("{}{:?}\n", indent, binding.to_string()))
+        }
+        DomNode::Element(element) => {
+            out.push_str(&format!("{}<{}", indent, element.tag));
+            for (key, value) in element.attributes.iter().chain(&element.properties) {
+                out.push_str(&format!(" {}={:?}", key, value));
+            }
+            out.push_str(">\n");
+            for child in &element.children {
+                write_pretty(child, depth + 1, out);
+            }
+        }
+        DomNode::Portal { target, children } => {
+            out.push_str(&format!("{}<portal target={:?}>\n", indent, target));
+            for child in children {
+                write_pretty(child, depth + 1, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::current_event;
+    use crate::reactivity::Signal;
+    use crate::scheduler::set_timeout;
+    use crate::vdom::VElement;
+
+    fn todo_app(items: Signal<Vec<String>>) -> impl Fn() -> VNode {
+        let draft = Signal::new(String::new());
+        move || {
+            let (draft_c, items_c) = (draft.clone(), items.clone());
+            let add = move || {
+                let text = draft_c.get_untracked();
+                if !text.is_empty() {
+                    items_c.update(|items| items.push(text));
+                    draft_c.set(String::new());
+                }
+            };
+            let add_key = add.clone();
+            let draft_input = draft.clone();
+            VElement::new("form")
+                .child(
+                    VElement::new("label")
+                        .attr("for", "new-todo")
+                        .child("New todo".into())
+                        .into(),
+                )
+                .child(
+                    VElement::new("input")
+                        .attr("id", "new-todo")
+                        .attr("value", draft.get())
+                        .on("input", move || {
+                            if let Some(Event::Input { value }) = current_event() {
+                                draft_input.set(value);
+                            }
+                        })
+                        .on("keydown", move || {
+                            if let Some(Event::KeyDown { key, .. }) = current_event() {
+                                if key == "Enter" {
+                                    add_key();
+                                }
+                            }
+                        })
+                        .into(),
+                )
+                .child(
+                    VElement::new("button")
+                        .on("click", add)
+                        .child("Add".into())
+                        .into(),
+                )
+                .child(
+                    VElement::new("ul")
+                        .attr("data-testid", "todos")
+                        .children(
+                            items
+                                .get()
+                                .into_iter()
+                                .map(|item| VElement::new("li").child(item.into()).into())
+                                .collect(),
+                        )
+                        .into(),
+                )
+                .into()
+        }
+    }
+
+    #[test]
+    fn test_queries_find_elements_like_a_user() {
+        let screen = Screen::render(todo_app(Signal::new(vec!["Milk".to_string()])));
+
+        assert_eq!(screen.get_by_role("button").text(), "Add");
+        assert_eq!(screen.get_by_label("New todo").element.tag, "input");
+        assert_eq!(screen.get_by_text("Milk").element.tag, "li");
+        assert_eq!(screen.get_by_test_id("todos").element.tag, "ul");
+        assert_eq!(screen.get_all(By::Role("listitem")).len(), 1);
+        assert!(screen.query(By::Text("Eggs")).is_none());
+    }
+
+    #[test]
+    fn test_events_update_the_rendered_tree() {
+        let items = Signal::new(Vec::new());
+        let screen = Screen::render(todo_app(items.clone()));
+
+        let input = screen.get_by_label("New todo");
+        assert!(screen.input(&input, "Eggs"));
+        assert_eq!(screen.get_by_label("New todo").attr("value"), Some("Eggs"));
+        assert!(screen.click(&screen.get_by_role("button")));
+        screen.input(&screen.get_by_label("New todo"), "Bread");
+        screen.key_down(&screen.get_by_label("New todo"), "Enter");
+
+        assert_eq!(items.get_untracked(), vec!["Eggs", "Bread"]);
+        let texts: Vec<String> = screen
+            .get_all(By::Role("listitem"))
+            .iter()
+            .map(Found::text)
+            .collect();
+        assert_eq!(texts, vec!["Eggs", "Bread"]);
+        assert_eq!(screen.get_by_label("New todo").attr("value"), Some(""));
+    }
+
+    #[test]
+    fn test_events_bubble_to_ancestors() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (outer, inner) = (log.clone(), log.clone());
+        let screen = Screen::render(move || -> VNode {
+            let (outer, inner) = (outer.clone(), inner.clone());
+            VElement::new("div")
+                .on("click", move || outer.borrow_mut().push("div"))
+                .child(
+                    VElement::new("button")
+                        .on("click", move || inner.borrow_mut().push("button"))
+                        .child("Go".into())
+                        .into(),
+                )
+                .into()
+        });
+
+        assert!(screen.click(&screen.get_by_text("Go")));
+        assert!(!screen.key_down(&screen.get_by_text("Go"), "a"));
+        assert_eq!(*log.borrow(), vec!["button", "div"]);
+    }
+
+    #[test]
+    fn test_wait_for_runs_timers_until_the_tree_updates() {
+        let status = Signal::new("Idle");
+        let status_c = status.clone();
+        let screen = Screen::render(move || -> VNode {
+            let status = status_c.clone();
+            VElement::new("button")
+                .on("click", move || {
+                    let status = status.clone();
+                    set_timeout(Duration::from_millis(300), move || status.set("Saved"));
+                })
+                .child(status_c.get().into())
+                .into()
+        });
+        let renders = screen.renders();
+
+        screen.click(&screen.get_by_role("button"));
+        assert!(screen.query(By::Text("Saved")).is_none());
+        screen.wait_for(|screen| screen.query(By::Text("Saved")).is_some());
+        assert_eq!(screen.renders(), renders + 1);
+    }
+
+    #[test]
+    fn test_dropping_the_screen_restores_the_scheduler() {
+        use crate::scheduler::{now, set_scheduler};
+
+        let outer = VirtualClock::new();
+        outer.advance(Duration::from_secs(10));
+        set_scheduler(Rc::new(outer));
+
+        let screen = Screen::render(|| VElement::new("main"));
+        assert_eq!(now(), Duration::ZERO);
+        drop(screen);
+        assert_eq!(now(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_simple_vnode_handlers_and_bindings() {
+        use crate::simple_vnode::{VAttr, VNode as SimpleNode};
+
+        let count = Signal::new(0);
+        let count_c = count.clone();
+        let screen = Screen::render(move || {
+            let count = count_c.clone();
+            let increment: Rc<RefCell<dyn FnMut()>> =
+                Rc::new(RefCell::new(move || count.update(|c| *c += 1)));
+            SimpleNode::element(
+                "button",
+                vec![
+                    ("data-testid", VAttr::Static("counter".to_string())),
+                    ("on_click", VAttr::Event(increment)),
+                ],
+                vec![SimpleNode::dynamic(count_c.clone())],
+            )
+        });
+
+        screen.click(&screen.get_by_test_id("counter"));
+        screen.click(&screen.get_by_test_id("counter"));
+        assert_eq!(screen.get_by_role("button").text(), "2");
+        // The bound text updated in place
+        assert_eq!(screen.renders(), 1);
+    }
+
+    #[test]
+    fn test_components_outlive_rerenders_until_the_screen_drops() {
+        use crate::component::Component;
+        use crate::reactivity::on_cleanup;
+        use crate::vdom::VComponent;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Child(Arc<AtomicUsize>);
+
+        impl Component for Child {
+            fn init(&mut self) {
+                let cleanups = self.0.clone();
+                on_cleanup(move || {
+                    cleanups.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+
+            fn render(&self) -> VNode {
+                let cleanups = self.0.clone();
+                on_cleanup(move || {
+                    cleanups.fetch_add(1, Ordering::SeqCst);
+                });
+                "child".into()
+            }
+        }
+
+        let cleanups = Arc::new(AtomicUsize::new(0));
+        let mut registry = ComponentRegistry::new();
+        let cleanups_c = cleanups.clone();
+        registry.register("Child", move |_| Child(cleanups_c.clone()));
+
+        let count = Signal::new(0);
+        let count_c = count.clone();
+        let screen = Screen::render_with_registry(Rc::new(registry), move || {
+            VElement::new("main")
+                .attr("data-count", count_c.get().to_string())
+                .child(VComponent::new("Child").into())
+        });
+        count.set(1);
+        assert_eq!(screen.renders(), 2);
+        // Only the first render of the child was cleaned up
+        assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+
+        drop(screen);
+        assert_eq!(cleanups.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "No element with role \"link\"")]
+    fn test_get_panics_with_the_rendered_tree() {
+        let screen = Screen::render(|| VElement::new("main").child("Home".into()));
+        screen.get_by_role("link");
+    }
+}
@@ -35,7 +35,6 @@ impl ToVNode for &str {
 // Implement for Vec<VNode> (fragment)
 impl ToVNode for Vec<VNode> {
     fn to_vnode(self) -> VNode {
-        // Wrap in a fragment div
-        VNode::element("div", vec![], self)
+        VNode::Fragment(self)
     }
 }
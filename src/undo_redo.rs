@@ -370,7 +370,8 @@ pub struct UndoRedoManager {
     redo_stack: VecDeque<Box<dyn Command>>,
     max_history: usize,
     merge_time_window_ms: u128, // Time window for merging commands
-    last_command_time: Option<std::time::Instant>,
+    /// When the last command ran, on the thread's [`scheduler`](crate::scheduler) clock
+    last_command_time: Option<std::time::Duration>,
 }
 
 impl UndoRedoManager {
@@ -402,9 +403,9 @@ impl UndoRedoManager {
         command.execute()?;
 
         // Try to merge with the last command if within time window
-        let now = std::time::Instant::now();
+        let now = crate::scheduler::now();
         let should_merge = if let Some(last_time) = self.last_command_time {
-            now.duration_since(last_time).as_millis() < self.merge_time_window_ms
+            now.saturating_sub(last_time).as_millis() < self.merge_time_window_ms
         } else {
             false
         };
@@ -735,4 +736,30 @@ mod tests {
 
         assert_eq!(manager.undo_count(), 3);
     }
+
+    #[test]
+    fn test_merge_window_uses_the_scheduler_clock() {
+        use crate::scheduler::{set_scheduler, VirtualClock};
+        use std::time::Duration;
+
+        let clock = VirtualClock::new();
+        set_scheduler(std::rc::Rc::new(clock.clone()));
+        let mut manager = UndoRedoManager::new();
+        let nudge = || {
+            Box::new(TransformCommand::new(
+                "Player".to_string(),
+                [0.0; 9],
+                [1.0; 9],
+            ))
+        };
+
+        manager.execute(nudge()).unwrap();
+        clock.advance(Duration::from_millis(499));
+        manager.execute(nudge()).unwrap();
+        assert_eq!(manager.undo_count(), 1);
+
+        clock.advance(Duration::from_millis(500));
+        manager.execute(nudge()).unwrap();
+        assert_eq!(manager.undo_count(), 2);
+    }
 }
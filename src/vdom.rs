@@ -1,6 +1,23 @@
 //! Virtual DOM implementation
+//!
+//! [`VNode`] is the tree every renderer accepts: the web renderer, SSR,
+//! the in-memory DOM, the mobile renderer and (through
+//! `DesktopRenderer::render_node`) egui. Trees built with
+//! [`simple_vnode`](crate::simple_vnode) or the handle based builders of the
+//! generated components convert into it with `From`.
+//!
+//! [`VNode::Dynamic`] text and [`VElement::bind`] attributes follow a
+//! [`Binding`]: the web and in-memory renderers update just that node when it
+//! changes, without a re-render; the others read its current value.
 
-use std::collections::HashMap;
+use crate::binding::Binding;
+use crate::event_handler::EventHandler;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 /// A virtual DOM node
 #[derive(Debug, Clone, PartialEq)]
@@ -9,18 +26,180 @@ pub enum VNode {
     Element(VElement),
     /// A text node
     Text(VText),
+    /// A text node bound to a reactive value
+    Dynamic(Binding),
     /// A component node
     Component(VComponent),
+    /// Several nodes without a wrapper; its children become children of
+    /// the parent
+    Fragment(Vec<VNode>),
+    /// Children rendered into another part of the document, e.g. `"body"`
+    /// for modals and tooltips that must escape their parent's overflow box
+    ///
+    /// `target` is a CSS selector. The portal keeps one placeholder slot
+    /// among its siblings, and events from its children bubble to its
+    /// logical parent.
+    Portal {
+        target: String,
+        children: Vec<VNode>,
+    },
+    /// A shared subtree that [`diff`] skips while its key and props hash
+    /// stay the same
+    Memo(VMemo),
     /// An empty node
     Empty,
 }
 
+impl VNode {
+    /// Group nodes without a wrapper element
+    pub fn fragment(children: Vec<VNode>) -> Self {
+        VNode::Fragment(children)
+    }
+
+    /// A text node that follows `value`, e.g. a `Signal`
+    pub fn dynamic(value: impl Into<Binding>) -> Self {
+        VNode::Dynamic(value.into())
+    }
+
+    /// Render `children` into the element matching `target`
+    pub fn portal(target: impl Into<String>, children: Vec<VNode>) -> Self {
+        VNode::Portal {
+            target: target.into(),
+            children,
+        }
+    }
+
+    /// Memoize the subtree rendered from `props`
+    ///
+    /// `render` still runs; use a [`MemoCache`] to skip it as well.
+    pub fn memo<P: Hash + ?Sized>(
+        key: impl Into<String>,
+        props: &P,
+        render: impl FnOnce() -> VNode,
+    ) -> Self {
+        VNode::Memo(VMemo {
+            key: key.into(),
+            props_hash: hash_props(props),
+            node: Rc::new(render()),
+        })
+    }
+
+    /// The node a memo wraps, or the node itself
+    pub fn unmemo(&self) -> &VNode {
+        match self {
+            VNode::Memo(memo) => memo.node.unmemo(),
+            node => node,
+        }
+    }
+}
+
+/// A memoized subtree
+///
+/// Two memos with equal `key` and `props_hash` are assumed to render the
+/// same tree, so diffing them is O(1). Cloning shares the subtree.
+#[derive(Debug, Clone)]
+pub struct VMemo {
+    /// What the memo renders, e.g. a component name and instance id
+    pub key: String,
+    pub props_hash: u64,
+    pub node: Rc<VNode>,
+}
+
+impl VMemo {
+    /// Whether diffing against `other` can be skipped
+    pub fn same_as(&self, other: &VMemo) -> bool {
+        self.key == other.key && self.props_hash == other.props_hash
+    }
+}
+
+impl PartialEq for VMemo {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+            && self.props_hash == other.props_hash
+            && (Rc::ptr_eq(&self.node, &other.node) || self.node == other.node)
+    }
+}
+
+fn hash_props<P: Hash + ?Sized>(props: &P) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    props.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Keeps memo subtrees between renders so unchanged ones are not rendered
+/// again
+///
+/// Entries not used since the previous [`MemoCache::sweep`] are dropped by
+/// it; call it once per render.
+#[derive(Debug, Default)]
+pub struct MemoCache {
+    entries: RefCell<HashMap<String, VMemo>>,
+    used: RefCell<HashSet<String>>,
+}
+
+impl MemoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The subtree from the previous render under `key` if `props` are
+    /// unchanged, otherwise the result of `render`
+    pub fn memo<P: Hash + ?Sized>(
+        &self,
+        key: impl Into<String>,
+        props: &P,
+        render: impl FnOnce() -> VNode,
+    ) -> VNode {
+        let key = key.into();
+        let props_hash = hash_props(props);
+        self.used.borrow_mut().insert(key.clone());
+        if let Some(memo) = self.entries.borrow().get(&key) {
+            if memo.props_hash == props_hash {
+                return VNode::Memo(memo.clone());
+            }
+        }
+        let memo = VMemo {
+            key: key.clone(),
+            props_hash,
+            node: Rc::new(render()),
+        };
+        self.entries.borrow_mut().insert(key, memo.clone());
+        VNode::Memo(memo)
+    }
+
+    /// Drop the entries not used since the last sweep
+    pub fn sweep(&self) {
+        let used = std::mem::take(&mut *self.used.borrow_mut());
+        self.entries
+            .borrow_mut()
+            .retain(|key, _| used.contains(key));
+    }
+
+    /// Number of cached subtrees
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+}
+
 /// A virtual element
 #[derive(Debug, Clone, PartialEq)]
 pub struct VElement {
     pub tag: String,
     pub attrs: HashMap<String, String>,
     pub children: Vec<VNode>,
+    /// Identity among its siblings; keyed children are matched by key
+    /// instead of position when diffing
+    pub key: Option<String>,
+    /// Handlers by event name without the `on` prefix, e.g. `"click"`
+    pub events: HashMap<String, Listener>,
+    /// Attributes and properties bound to reactive values; a binding takes
+    /// the place of an entry in `attrs` with the same key
+    pub bindings: HashMap<String, Binding>,
 }
 
 impl VElement {
@@ -30,15 +209,42 @@ impl VElement {
             tag: tag.into(),
             attrs: HashMap::new(),
             children: Vec::new(),
+            key: None,
+            events: HashMap::new(),
+            bindings: HashMap::new(),
         }
     }
 
+    /// Set the key identifying this element among its siblings
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
     /// Add an attribute
     pub fn attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.attrs.insert(key.into(), value.into());
         self
     }
 
+    /// Bind an attribute, or a property (see [`is_property`]), to `value`
+    pub fn bind(mut self, key: impl Into<String>, value: impl Into<Binding>) -> Self {
+        self.bindings.insert(key.into(), value.into());
+        self
+    }
+
+    /// Every attribute with its current value, bound ones included
+    ///
+    /// For renderers that don't keep nodes; the reads are tracked, so a
+    /// surrounding effect renders again when a bound value changes.
+    pub fn current_attrs(&self) -> HashMap<String, String> {
+        let mut attrs = self.attrs.clone();
+        for (key, binding) in &self.bindings {
+            attrs.insert(key.clone(), binding.get());
+        }
+        attrs
+    }
+
     /// Add children
     pub fn children(mut self, children: Vec<VNode>) -> Self {
         self.children = children;
@@ -50,6 +256,49 @@ impl VElement {
         self.children.push(child);
         self
     }
+
+    /// Handle an event, e.g. `"click"`
+    pub fn on(mut self, event: impl Into<String>, handler: impl FnMut() + 'static) -> Self {
+        self.events.insert(event.into(), Listener::new(handler));
+        self
+    }
+}
+
+/// An event handler attached to an element
+///
+/// Two listeners are equal only if they share the same handler, so a
+/// re-render that creates a new closure patches the handler.
+#[derive(Clone)]
+pub struct Listener(pub EventHandler);
+
+impl Listener {
+    /// Wrap a closure
+    pub fn new(handler: impl FnMut() + 'static) -> Self {
+        Self(Rc::new(std::cell::RefCell::new(handler)))
+    }
+
+    /// Run the handler
+    pub fn call(&self) {
+        (self.0.borrow_mut())();
+    }
+}
+
+impl From<EventHandler> for Listener {
+    fn from(handler: EventHandler) -> Self {
+        Self(handler)
+    }
+}
+
+impl PartialEq for Listener {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Listener({:p})", Rc::as_ptr(&self.0))
+    }
 }
 
 /// A virtual text node
@@ -92,21 +341,59 @@ impl From<&str> for VNode {
 }
 
 /// A virtual component node
+///
+/// `name` is looked up in a
+/// [`ComponentRegistry`](crate::component::ComponentRegistry) when the tree
+/// is expanded by a [`Reconciler`](crate::reconciler::Reconciler).
 #[derive(Debug, Clone, PartialEq)]
 pub struct VComponent {
     pub name: String,
     pub props: HashMap<String, String>,
 }
 
+impl VComponent {
+    /// Create a component node without props
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            props: HashMap::new(),
+        }
+    }
+
+    /// Add a prop
+    pub fn prop(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.props.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl From<VComponent> for VNode {
+    fn from(component: VComponent) -> Self {
+        VNode::Component(component)
+    }
+}
+
 /// Diff two virtual DOM trees and produce a list of patches
+///
+/// Patches are applied in order; each path addresses the tree as left by
+/// the patches before it. Paths start at the root node (`[0]`), and an empty
+/// path is the mount point, whose children a root fragment adds to.
 pub fn diff(old: &VNode, new: &VNode) -> Vec<Patch> {
     let mut patches = Vec::new();
-    diff_recursive(old, new, &mut patches, vec![0]);
+    diff_children(
+        std::slice::from_ref(old),
+        std::slice::from_ref(new),
+        &mut patches,
+        &[],
+    );
     patches
 }
 
 fn diff_recursive(old: &VNode, new: &VNode, patches: &mut Vec<Patch>, path: Vec<usize>) {
     match (old, new) {
+        (VNode::Memo(old_memo), VNode::Memo(new_memo)) if old_memo.same_as(new_memo) => {}
+        (VNode::Memo(old_memo), _) => diff_recursive(&old_memo.node, new, patches, path),
+        (_, VNode::Memo(new_memo)) => diff_recursive(old, &new_memo.node, patches, path),
         (VNode::Text(old_text), VNode::Text(new_text)) => {
             if old_text.content != new_text.content {
                 patches.push(Patch::UpdateText {
@@ -116,7 +403,7 @@ fn diff_recursive(old: &VNode, new: &VNode, patches: &mut Vec<Patch>, path: Vec<
             }
         }
         (VNode::Element(old_el), VNode::Element(new_el)) => {
-            if old_el.tag != new_el.tag {
+            if old_el.tag != new_el.tag || old_el.key != new_el.key {
                 patches.push(Patch::Replace {
                     path: path.clone(),
                     node: VNode::Element(new_el.clone()),
@@ -125,39 +412,88 @@ fn diff_recursive(old: &VNode, new: &VNode, patches: &mut Vec<Patch>, path: Vec<
             }
 
             // Diff attributes
+            let old_keys: HashSet<&String> =
+                old_el.attrs.keys().chain(old_el.bindings.keys()).collect();
+            for key in old_keys {
+                if !new_el.attrs.contains_key(key) && !new_el.bindings.contains_key(key) {
+                    patches.push(Patch::RemoveAttribute {
+                        path: path.clone(),
+                        key: key.clone(),
+                    });
+                }
+            }
             for (key, new_value) in &new_el.attrs {
-                if old_el.attrs.get(key) != Some(new_value) {
-                    patches.push(Patch::SetAttribute {
+                if new_el.bindings.contains_key(key) {
+                    continue;
+                }
+                if old_el.attrs.get(key) != Some(new_value) || old_el.bindings.contains_key(key) {
+                    let (path, key, value) = (path.clone(), key.clone(), new_value.clone());
+                    patches.push(if is_property(&key) {
+                        Patch::SetProperty { path, key, value }
+                    } else {
+                        Patch::SetAttribute { path, key, value }
+                    });
+                }
+            }
+            for (key, binding) in &new_el.bindings {
+                if old_el.bindings.get(key) != Some(binding) {
+                    patches.push(Patch::BindAttribute {
                         path: path.clone(),
                         key: key.clone(),
-                        value: new_value.clone(),
+                        binding: binding.clone(),
                     });
                 }
             }
 
-            // Diff children
-            let max_len = old_el.children.len().max(new_el.children.len());
-            for i in 0..max_len {
-                let mut child_path = path.clone();
-                child_path.push(i);
-
-                match (old_el.children.get(i), new_el.children.get(i)) {
-                    (Some(old_child), Some(new_child)) => {
-                        diff_recursive(old_child, new_child, patches, child_path);
-                    }
-                    (None, Some(new_child)) => {
-                        patches.push(Patch::Append {
-                            path: path.clone(),
-                            node: new_child.clone(),
-                        });
-                    }
-                    (Some(_), None) => {
-                        patches.push(Patch::Remove { path: child_path });
-                    }
-                    (None, None) => unreachable!(),
+            // Diff event handlers
+            for event in old_el.events.keys() {
+                if !new_el.events.contains_key(event) {
+                    patches.push(Patch::RemoveEvent {
+                        path: path.clone(),
+                        event: event.clone(),
+                    });
                 }
             }
+            for (event, listener) in &new_el.events {
+                if old_el.events.get(event) != Some(listener) {
+                    patches.push(Patch::SetEvent {
+                        path: path.clone(),
+                        event: event.clone(),
+                        listener: listener.clone(),
+                    });
+                }
+            }
+
+            diff_children(&old_el.children, &new_el.children, patches, &path);
+        }
+        (
+            VNode::Portal {
+                target: old_target,
+                children: old_children,
+            },
+            VNode::Portal {
+                target: new_target,
+                children: new_children,
+            },
+        ) if old_target == new_target => {
+            let mut inner = Vec::new();
+            diff_children(old_children, new_children, &mut inner, &[]);
+            if !inner.is_empty() {
+                patches.push(Patch::Portal {
+                    path,
+                    patches: inner,
+                });
+            }
         }
+        (VNode::Dynamic(old_binding), VNode::Dynamic(new_binding)) => {
+            if old_binding != new_binding {
+                patches.push(Patch::BindText {
+                    path,
+                    binding: new_binding.clone(),
+                });
+            }
+        }
+        (VNode::Empty, VNode::Empty) => {}
         _ => {
             // Different node types, replace
             patches.push(Patch::Replace {
@@ -168,6 +504,195 @@ fn diff_recursive(old: &VNode, new: &VNode, patches: &mut Vec<Patch>, path: Vec<
     }
 }
 
+/// Diff the children of the node at `path`, with fragments flattened
+fn diff_children(old: &[VNode], new: &[VNode], patches: &mut Vec<Patch>, path: &[usize]) {
+    let (old, new) = (flatten(old), flatten(new));
+    if is_keyed(&old) && is_keyed(&new) {
+        diff_keyed_children(&old, &new, patches, path);
+        return;
+    }
+    for (i, (old_child, new_child)) in old.iter().zip(&new).enumerate() {
+        let mut child_path = path.to_vec();
+        child_path.push(i);
+        diff_recursive(old_child, new_child, patches, child_path);
+    }
+    for new_child in new.iter().skip(old.len()) {
+        patches.push(Patch::Append {
+            path: path.to_vec(),
+            node: (*new_child).clone(),
+        });
+    }
+    // Remove from the end so earlier indices stay valid
+    for i in (new.len()..old.len()).rev() {
+        let mut child_path = path.to_vec();
+        child_path.push(i);
+        patches.push(Patch::Remove { path: child_path });
+    }
+}
+
+/// The nodes that take a slot among their siblings, with fragments
+/// replaced by their children
+pub fn flatten(nodes: &[VNode]) -> Vec<&VNode> {
+    let mut flat = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match node {
+            VNode::Fragment(children) => flat.extend(flatten(children)),
+            // A memoized fragment still takes one slot per child
+            VNode::Memo(memo) if matches!(memo.node.unmemo(), VNode::Fragment(_)) => {
+                flat.extend(flatten(std::slice::from_ref(memo.node.unmemo())))
+            }
+            node => flat.push(node),
+        }
+    }
+    flat
+}
+
+/// Whether `key` is a live DOM property rather than an attribute
+///
+/// Attributes such as `value` and `checked` only set the initial state of a
+/// form control; once the user has typed or clicked, only the property
+/// reflects what is shown, so controlled inputs must write the property.
+pub fn is_property(key: &str) -> bool {
+    matches!(key, "value" | "checked" | "selected")
+}
+
+fn key_of(node: &VNode) -> Option<&str> {
+    match node.unmemo() {
+        VNode::Element(element) => element.key.as_deref(),
+        _ => None,
+    }
+}
+
+/// Whether every child has a key, and no two share one (an empty list
+/// counts as keyed)
+///
+/// Lists with duplicate keys are diffed by position instead, since a key no
+/// longer identifies one child.
+fn is_keyed(children: &[&VNode]) -> bool {
+    let mut keys = HashSet::with_capacity(children.len());
+    children
+        .iter()
+        .all(|child| key_of(child).is_some_and(|key| keys.insert(key)))
+}
+
+/// Reconcile keyed children of the element at `path`
+///
+/// Removes children whose key is gone, then moves only the children outside
+/// the longest run that is already in order, inserts new ones, and finally
+/// diffs the matched pairs at their new positions.
+fn diff_keyed_children(old: &[&VNode], new: &[&VNode], patches: &mut Vec<Patch>, path: &[usize]) {
+    let new_keys: HashMap<&str, usize> = new
+        .iter()
+        .enumerate()
+        .filter_map(|(i, child)| key_of(child).map(|key| (key, i)))
+        .collect();
+
+    // Remove from the end so earlier indices stay valid
+    for (i, child) in old.iter().enumerate().rev() {
+        if !key_of(child).is_some_and(|key| new_keys.contains_key(key)) {
+            let mut child_path = path.to_vec();
+            child_path.push(i);
+            patches.push(Patch::Remove { path: child_path });
+        }
+    }
+
+    // Keys in their current order, and the old child for each kept key
+    let mut current: Vec<&str> = Vec::new();
+    let mut old_by_key: HashMap<&str, &VNode> = HashMap::new();
+    for child in old {
+        if let Some(key) = key_of(child).filter(|key| new_keys.contains_key(key)) {
+            current.push(key);
+            old_by_key.insert(key, *child);
+        }
+    }
+
+    // Children whose relative order is unchanged stay where they are
+    let positions: HashMap<&str, usize> =
+        current.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+    let kept: Vec<(usize, usize)> = new
+        .iter()
+        .enumerate()
+        .filter_map(|(i, child)| Some((i, *positions.get(key_of(child)?)?)))
+        .collect();
+    let sequence: Vec<usize> = kept.iter().map(|(_, position)| *position).collect();
+    let stable: HashSet<usize> = longest_increasing_subsequence(&sequence)
+        .into_iter()
+        .map(|i| kept[i].0)
+        .collect();
+
+    // Place every other child right after its new predecessor
+    for (i, child) in new.iter().enumerate() {
+        let key = key_of(child).expect("keyed children");
+        if stable.contains(&i) {
+            continue;
+        }
+        let after = match i {
+            0 => 0,
+            _ => {
+                let previous = key_of(new[i - 1]).expect("keyed children");
+                current.iter().position(|k| *k == previous).expect("placed") + 1
+            }
+        };
+        match current.iter().position(|k| *k == key) {
+            Some(from) => {
+                let to = if from < after { after - 1 } else { after };
+                current.remove(from);
+                current.insert(to, key);
+                if from != to {
+                    patches.push(Patch::Move {
+                        path: path.to_vec(),
+                        from,
+                        to,
+                    });
+                }
+            }
+            None => {
+                current.insert(after, key);
+                patches.push(Patch::Insert {
+                    path: path.to_vec(),
+                    index: after,
+                    node: (*child).clone(),
+                });
+            }
+        }
+    }
+
+    for (i, child) in new.iter().enumerate() {
+        if let Some(old_child) = key_of(child).and_then(|key| old_by_key.get(key)) {
+            let mut child_path = path.to_vec();
+            child_path.push(i);
+            diff_recursive(old_child, child, patches, child_path);
+        }
+    }
+}
+
+/// Indices of one longest strictly increasing subsequence of `values`
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // tails[k]: index of the smallest tail of an increasing run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![usize::MAX; values.len()];
+    for (i, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&t| values[t] < *value);
+        if length > 0 {
+            previous[i] = tails[length - 1];
+        }
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+
+    let mut result = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        result.push(i);
+        next = (previous[i] != usize::MAX).then_some(previous[i]);
+    }
+    result.reverse();
+    result
+}
+
 /// A patch to apply to the DOM
 #[derive(Debug, Clone, PartialEq)]
 pub enum Patch {
@@ -175,16 +700,59 @@ pub enum Patch {
     Replace { path: Vec<usize>, node: VNode },
     /// Update text content
     UpdateText { path: Vec<usize>, content: String },
+    /// Bind a dynamic text node to another value
+    BindText { path: Vec<usize>, binding: Binding },
     /// Set an attribute
     SetAttribute {
         path: Vec<usize>,
         key: String,
         value: String,
     },
+    /// Remove an attribute, or reset a property (see [`is_property`])
+    RemoveAttribute { path: Vec<usize>, key: String },
+    /// Set a DOM property such as an input's `value` (see [`is_property`])
+    SetProperty {
+        path: Vec<usize>,
+        key: String,
+        value: String,
+    },
+    /// Bind an attribute or property to `binding`, replacing its value or
+    /// previous binding; setting or removing `key` drops the binding again
+    BindAttribute {
+        path: Vec<usize>,
+        key: String,
+        binding: Binding,
+    },
+    /// Attach or replace the handler for `event`
+    SetEvent {
+        path: Vec<usize>,
+        event: String,
+        listener: Listener,
+    },
+    /// Detach the handler for `event`
+    RemoveEvent { path: Vec<usize>, event: String },
     /// Append a child
     Append { path: Vec<usize>, node: VNode },
+    /// Insert a child of the node at `path` before the child at `index`
+    Insert {
+        path: Vec<usize>,
+        index: usize,
+        node: VNode,
+    },
+    /// Move a child of the node at `path`; `to` is its index once moved
+    Move {
+        path: Vec<usize>,
+        from: usize,
+        to: usize,
+    },
     /// Remove a node
     Remove { path: Vec<usize> },
+    /// Apply `patches` to the children of the portal at `path`; their paths
+    /// start at the portal's container (`[]`)
+    Portal {
+        path: Vec<usize>,
+        patches: Vec<Patch>,
+    },
 }
 
 #[cfg(test)]
@@ -208,6 +776,33 @@ mod tests {
         assert_eq!(text.content, "Hello, World!");
     }
 
+    #[test]
+    fn test_diff_rebinds_only_new_bindings() {
+        let (title, other) = (Binding::from("a"), Binding::from("b"));
+        let view = |title: &Binding| -> VNode {
+            VElement::new("p")
+                .bind("title", title.clone())
+                .child(VNode::Dynamic(title.clone()))
+                .into()
+        };
+
+        assert!(diff(&view(&title), &view(&title)).is_empty());
+        assert_eq!(
+            diff(&view(&title), &view(&other)),
+            vec![
+                Patch::BindAttribute {
+                    path: vec![0],
+                    key: "title".to_string(),
+                    binding: other.clone(),
+                },
+                Patch::BindText {
+                    path: vec![0, 0],
+                    binding: other,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_diff_text_update() {
         let old = VNode::Text(VText::new("old"));
@@ -224,4 +819,335 @@ mod tests {
         let patches = diff(&node, &node);
         assert_eq!(patches.len(), 0);
     }
+
+    fn row(key: &str) -> VNode {
+        VElement::new("li")
+            .key(key)
+            .child(VNode::Text(VText::new(key)))
+            .into()
+    }
+
+    fn list(keys: &[&str]) -> VNode {
+        VElement::new("ul")
+            .children(keys.iter().map(|key| row(key)).collect())
+            .into()
+    }
+
+    #[test]
+    fn test_keyed_insert_at_head_is_one_patch() {
+        let keys: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let old_keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let mut new_keys = old_keys.clone();
+        new_keys.insert(0, "new");
+
+        let patches = diff(&list(&old_keys), &list(&new_keys));
+        assert_eq!(
+            patches,
+            vec![Patch::Insert {
+                path: vec![0],
+                index: 0,
+                node: row("new"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_keyed_moves_only_out_of_order_children() {
+        // Moving the last row to the front is a single move
+        let patches = diff(&list(&["a", "b", "c", "d"]), &list(&["d", "a", "b", "c"]));
+        assert_eq!(
+            patches,
+            vec![Patch::Move {
+                path: vec![0],
+                from: 3,
+                to: 0,
+            }]
+        );
+
+        // Swapping the ends moves two rows
+        let patches = diff(&list(&["a", "b", "c", "d"]), &list(&["d", "b", "c", "a"]));
+        let moves = patches
+            .iter()
+            .filter(|patch| matches!(patch, Patch::Move { .. }))
+            .count();
+        assert_eq!(moves, 2);
+    }
+
+    #[test]
+    fn test_keyed_removals_and_updates() {
+        let old = list(&["a", "b", "c"]);
+        let new: VNode = VElement::new("ul")
+            .child(row("c"))
+            .child(
+                VElement::new("li")
+                    .key("a")
+                    .child(VNode::Text(VText::new("changed")))
+                    .into(),
+            )
+            .into();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Patch::Remove { path: vec![0, 1] },
+                Patch::Move {
+                    path: vec![0],
+                    from: 1,
+                    to: 0,
+                },
+                Patch::UpdateText {
+                    path: vec![0, 1, 0],
+                    content: "changed".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys_diff_by_position() {
+        use crate::memory_dom::MemoryRenderer;
+        use crate::renderer::Renderer;
+
+        let rows = |rows: &[(&str, &str)]| -> VNode {
+            VElement::new("ul")
+                .children(
+                    rows.iter()
+                        .map(|(key, text)| {
+                            VElement::new("li").key(*key).child((*text).into()).into()
+                        })
+                        .collect(),
+                )
+                .into()
+        };
+        let cases = [
+            (vec![("a", "1")], vec![("a", "1"), ("a", "2")]),
+            (vec![("a", "1"), ("a", "2")], vec![("a", "1")]),
+            (
+                vec![("a", "1"), ("b", "2"), ("a", "3")],
+                vec![("a", "1"), ("a", "3"), ("b", "2")],
+            ),
+        ];
+        for (old, new) in cases {
+            let (old, new) = (rows(&old), rows(&new));
+            let mut patched = MemoryRenderer::new();
+            patched.init().unwrap();
+            patched.render(&old).unwrap();
+            patched.patch(&diff(&old, &new)).unwrap();
+            let mut rendered = MemoryRenderer::new();
+            rendered.init().unwrap();
+            rendered.render(&new).unwrap();
+            assert_eq!(patched.roots(), rendered.roots());
+        }
+    }
+
+    #[test]
+    fn test_longest_increasing_subsequence() {
+        assert_eq!(longest_increasing_subsequence(&[]), Vec::<usize>::new());
+        let values = [3, 0, 1, 4, 2, 5];
+        let lis = longest_increasing_subsequence(&values);
+        assert_eq!(lis.len(), 4);
+        assert!(lis.windows(2).all(|w| values[w[0]] < values[w[1]]));
+    }
+
+    #[test]
+    fn test_diff_removes_attributes_and_sets_properties() {
+        let old: VNode = VElement::new("input")
+            .attr("class", "invalid")
+            .attr("disabled", "")
+            .attr("value", "a")
+            .into();
+        let new: VNode = VElement::new("input").attr("value", "ab").into();
+
+        let mut patches = diff(&old, &new);
+        patches.sort_by_key(|patch| format!("{:?}", patch));
+        assert_eq!(
+            patches,
+            vec![
+                Patch::RemoveAttribute {
+                    path: vec![0],
+                    key: "class".to_string(),
+                },
+                Patch::RemoveAttribute {
+                    path: vec![0],
+                    key: "disabled".to_string(),
+                },
+                Patch::SetProperty {
+                    path: vec![0],
+                    key: "value".to_string(),
+                    value: "ab".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unkeyed_removals_run_from_the_end() {
+        let old: VNode = VElement::new("ul")
+            .children(vec!["a".into(), "b".into(), "c".into()])
+            .into();
+        let new: VNode = VElement::new("ul").child("a".into()).into();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Patch::Remove { path: vec![0, 2] },
+                Patch::Remove { path: vec![0, 1] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fragment_children_take_flat_indices() {
+        let old: VNode = VElement::new("ul")
+            .child(VNode::fragment(vec!["a".into(), "b".into()]))
+            .child("c".into())
+            .into();
+        let new: VNode = VElement::new("ul")
+            .child(VNode::fragment(vec!["a".into()]))
+            .child("b".into())
+            .child("x".into())
+            .into();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::UpdateText {
+                path: vec![0, 2],
+                content: "x".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_portal_patches_are_relative_to_the_container() {
+        let portal = |text: &str| VNode::portal("#modals", vec![text.into()]);
+        let old: VNode = VElement::new("div").child(portal("a")).into();
+        let new: VNode = VElement::new("div").child(portal("b")).into();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::Portal {
+                path: vec![0, 0],
+                patches: vec![Patch::UpdateText {
+                    path: vec![0],
+                    content: "b".to_string(),
+                }],
+            }]
+        );
+
+        let moved: VNode = VElement::new("div")
+            .child(VNode::portal("#toasts", vec!["b".into()]))
+            .into();
+        assert!(matches!(
+            diff(&old, &moved).as_slice(),
+            [Patch::Replace { path, .. }] if path == &vec![0, 0]
+        ));
+    }
+
+    #[test]
+    fn test_diff_sets_and_removes_event_handlers() {
+        let (save, focus) = (Listener::new(|| {}), Listener::new(|| {}));
+        let button = |events: Vec<(&str, Listener)>| -> VNode {
+            let mut button = VElement::new("button");
+            for (event, listener) in events {
+                button.events.insert(event.to_string(), listener);
+            }
+            button.into()
+        };
+
+        let old = button(vec![("click", save.clone()), ("focus", focus.clone())]);
+        assert!(diff(&old, &button(vec![("click", save), ("focus", focus)])).is_empty());
+
+        let replaced = Listener::new(|| {});
+        assert_eq!(
+            diff(&old, &button(vec![("click", replaced.clone())])),
+            vec![
+                Patch::RemoveEvent {
+                    path: vec![0],
+                    event: "focus".to_string(),
+                },
+                Patch::SetEvent {
+                    path: vec![0],
+                    event: "click".to_string(),
+                    listener: replaced,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchanged_memo_is_skipped() {
+        let renders = std::cell::Cell::new(0);
+        let row = |label: &str| {
+            VNode::memo("row", label, || {
+                renders.set(renders.get() + 1);
+                VElement::new("li").child(label.into()).into()
+            })
+        };
+        let list = |rows: Vec<VNode>| -> VNode { VElement::new("ul").children(rows).into() };
+
+        // Same props: nothing is compared below the memo, even if the
+        // subtrees are separate allocations
+        assert!(diff(&list(vec![row("a")]), &list(vec![row("a")])).is_empty());
+        assert_eq!(renders.get(), 2);
+
+        assert_eq!(
+            diff(&list(vec![row("a")]), &list(vec![row("b")])),
+            vec![Patch::UpdateText {
+                path: vec![0, 0, 0],
+                content: "b".to_string(),
+            }]
+        );
+
+        // A memo and a plain node diff by content
+        let plain: VNode = VElement::new("li").child("a".into()).into();
+        assert!(diff(&list(vec![plain]), &list(vec![row("a")])).is_empty());
+    }
+
+    #[test]
+    fn test_memo_cache_reuses_subtrees_until_props_change() {
+        let cache = MemoCache::new();
+        let renders = std::cell::Cell::new(0);
+        let render = |count: u32| {
+            cache.memo("counter", &count, || {
+                renders.set(renders.get() + 1);
+                format!("{}", count).into()
+            })
+        };
+
+        let (first, second) = (render(1), render(1));
+        assert_eq!(renders.get(), 1);
+        let (VNode::Memo(first), VNode::Memo(second)) = (&first, &second) else {
+            panic!("expected memos");
+        };
+        assert!(Rc::ptr_eq(&first.node, &second.node));
+
+        render(2);
+        assert_eq!(renders.get(), 2);
+
+        cache.sweep();
+        assert_eq!(cache.len(), 1);
+        cache.sweep();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_memoized_fragments_take_one_slot_per_child() {
+        let pair = |a: &str| VNode::memo("pair", a, || VNode::fragment(vec![a.into(), "x".into()]));
+        let old: VNode = VElement::new("div")
+            .child(pair("a"))
+            .child("end".into())
+            .into();
+        let new: VNode = VElement::new("div")
+            .child(pair("b"))
+            .child("end".into())
+            .into();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::UpdateText {
+                path: vec![0, 0],
+                content: "b".to_string(),
+            }]
+        );
+    }
 }
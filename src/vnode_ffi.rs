@@ -175,6 +175,17 @@ pub fn vnode_clear() {
     VNODE_REGISTRY.with(|registry| registry.borrow_mut().clear());
 }
 
+impl crate::components::generated::vnode::VNode {
+    /// Take the finished tree out of the registry as a diffable node
+    ///
+    /// Like [`vnode_take`], this removes the tree: a handle that is no longer
+    /// registered (already taken or added to a parent) gives
+    /// [`crate::vdom::VNode::Empty`].
+    pub fn into_vdom(self) -> crate::vdom::VNode {
+        vnode_take(self.handle).map_or(crate::vdom::VNode::Empty, Into::into)
+    }
+}
+
 // ============================================================================
 // Convenience builders for common elements
 // ============================================================================
@@ -290,6 +301,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_handle_tree_converts_to_vdom() {
+        use crate::components::generated::vnode::VNode as Handle;
+        use crate::vdom::{self, VElement};
+
+        vnode_clear();
+        let handle = Handle::div()
+            .add_class("card".to_string())
+            .child(Handle::span().add_text("Title".to_string()));
+
+        assert_eq!(
+            handle.into_vdom(),
+            VElement::new("div")
+                .attr("class", "card")
+                .child(VElement::new("span").child("Title".into()).into())
+                .into()
+        );
+        // The tree was taken by the first conversion
+        assert_eq!(handle.into_vdom(), vdom::VNode::Empty);
+    }
+
     #[test]
     fn test_convenience_builders() {
         vnode_clear();
@@ -0,0 +1,90 @@
+//! Property test: patching a rendered tree with `diff(old, new)` must give
+//! the same DOM as rendering `new` directly, fragments, portals and memos included
+
+use proptest::prelude::*;
+use windjammer_ui::memory_dom::MemoryRenderer;
+use windjammer_ui::renderer::Renderer;
+use windjammer_ui::vdom::{diff, VElement, VNode, VText};
+
+const TAGS: &[&str] = &["div", "span", "ul", "li"];
+const ATTRS: &[&str] = &["class", "id", "title", "value", "checked"];
+const WORDS: &[&str] = &["", "a", "b", "hello", "world"];
+const PORTALS: &[&str] = &["#modal", "#toast"];
+
+fn text() -> impl Strategy<Value = VNode> {
+    prop::sample::select(WORDS).prop_map(|word| VNode::Text(VText::new(word)))
+}
+
+fn attrs() -> impl Strategy<Value = Vec<(&'static str, &'static str)>> {
+    prop::collection::vec(
+        (prop::sample::select(ATTRS), prop::sample::select(WORDS)),
+        0..3,
+    )
+}
+
+fn element(children: Vec<VNode>) -> impl Strategy<Value = VElement> {
+    (prop::sample::select(TAGS), attrs()).prop_map(move |(tag, attrs)| {
+        attrs
+            .into_iter()
+            .fold(VElement::new(tag), |el, (key, value)| el.attr(key, value))
+            .children(children.clone())
+    })
+}
+
+/// Children that are either all keyed (keys from a small pool, so old and
+/// new lists share many and sometimes repeat one) or positional
+fn children(inner: BoxedStrategy<VNode>) -> impl Strategy<Value = Vec<VNode>> {
+    let keyed = prop::collection::vec((0u8..8, inner.clone()), 0..6).prop_map(|rows| {
+        rows.into_iter()
+            .map(|(key, node)| {
+                let mut row = match node {
+                    VNode::Element(el) => el,
+                    other => VElement::new("li").child(other),
+                };
+                row.key = Some(format!("k{}", key));
+                VNode::Element(row)
+            })
+            .collect()
+    });
+    let positional = prop::collection::vec(inner, 0..5);
+    prop_oneof![keyed, positional]
+}
+
+fn tree() -> impl Strategy<Value = VNode> {
+    let leaf = prop_oneof![
+        3 => text(),
+        1 => element(Vec::new()).prop_map(VNode::Element),
+    ];
+    leaf.prop_recursive(4, 48, 6, |inner| {
+        let inner = inner.boxed();
+        prop_oneof![
+            4 => children(inner.clone())
+                .prop_flat_map(element)
+                .prop_map(VNode::Element),
+            1 => prop::collection::vec(inner.clone(), 0..4).prop_map(VNode::fragment),
+            1 => (prop::sample::select(PORTALS), prop::collection::vec(inner.clone(), 0..3))
+                .prop_map(|(target, children)| VNode::portal(target, children)),
+            // Props that describe the subtree, as real props do
+            1 => inner.prop_map(|node| VNode::memo("memo", &format!("{:?}", node), || node)),
+        ]
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn patching_matches_a_fresh_render(old in tree(), new in tree()) {
+        let mut dom = MemoryRenderer::new();
+        dom.init().unwrap();
+        dom.render(&old).unwrap();
+        dom.patch(&diff(&old, &new)).unwrap();
+
+        prop_assert_eq!(dom, MemoryRenderer::rendered(&new).unwrap());
+    }
+
+    #[test]
+    fn diffing_a_tree_with_itself_is_empty(tree in tree()) {
+        prop_assert!(diff(&tree, &tree).is_empty());
+    }
+}
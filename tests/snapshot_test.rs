@@ -0,0 +1,100 @@
+//! Snapshot tests for component output
+//!
+//! Snapshots live in `tests/snapshots/`; rerun with `UPDATE_SNAPSHOTS=1` to
+//! accept changes, then review them with `git diff`.
+
+use windjammer_ui::assert_snapshot;
+use windjammer_ui::components::generated::traits::{Renderable, RenderableVNode};
+use windjammer_ui::components::generated::{alert, button, checkbox, tabs};
+use windjammer_ui::reactivity::Signal;
+use windjammer_ui::simple_vnode::{VAttr, VNode as SimpleNode};
+use windjammer_ui::snapshot;
+use windjammer_ui::vdom::{self, VElement};
+
+#[test]
+fn test_button_html_snapshot() {
+    let html = button::Button::new("Save".to_string())
+        .variant(button::ButtonVariant::Danger)
+        .disabled(true)
+        .render();
+    assert_snapshot!("button_html", snapshot::html(&html));
+}
+
+#[test]
+fn test_alert_html_snapshot() {
+    let html = alert::Alert::error("Disk full".to_string()).render();
+    assert_snapshot!("alert_html", snapshot::html(&html));
+}
+
+#[test]
+fn test_checkbox_html_snapshot() {
+    let html = checkbox::Checkbox::new("Remember me".to_string()).render();
+    assert_snapshot!("checkbox_html", snapshot::html(&html));
+}
+
+#[test]
+fn test_tabs_html_snapshot() {
+    let html = tabs::Tabs::new()
+        .tab(tabs::Tab::new(
+            "general".to_string(),
+            "General".to_string(),
+            "Settings".to_string(),
+        ))
+        .tab(tabs::Tab::new(
+            "about".to_string(),
+            "About".to_string(),
+            "Version 1".to_string(),
+        ))
+        .render();
+    assert_snapshot!("tabs_html", snapshot::html(&html));
+}
+
+#[test]
+fn test_button_vnode_snapshot() {
+    let node = button::Button::new("Save".to_string())
+        .to_vnode()
+        .into_vdom();
+    assert_snapshot!("button_vnode", snapshot::vnode(&node));
+}
+
+#[test]
+fn test_vdom_tree_snapshot() {
+    let node: vdom::VNode = VElement::new("ul")
+        .attr("class", "todos")
+        .children(
+            ["Milk", "Eggs"]
+                .iter()
+                .map(|item| {
+                    VElement::new("li")
+                        .key(*item)
+                        .on("click", || {})
+                        .child((*item).into())
+                        .into()
+                })
+                .collect(),
+        )
+        .child(vdom::VNode::Portal {
+            target: "#toast".to_string(),
+            children: vec!["Saved".into()],
+        })
+        .into();
+    assert_snapshot!("vdom_tree", snapshot::vnode(&node));
+}
+
+#[test]
+fn test_simple_vnode_snapshot() {
+    let count = Signal::new(3);
+    let node = SimpleNode::element(
+        "div",
+        vec![
+            ("class", VAttr::Static("counter".to_string())),
+            ("title", VAttr::Dynamic(count.clone().into())),
+        ],
+        vec![
+            SimpleNode::text("Count: "),
+            SimpleNode::dynamic(count),
+            SimpleNode::element("button", vec![], vec![SimpleNode::text("+")]),
+        ],
+    );
+    assert_snapshot!("simple_vnode", snapshot::simple_vnode(&node));
+}
@@ -0,0 +1,51 @@
+//! Tests for `#[derive(Lenses)]` on store state structs
+
+use std::cell::Cell;
+use std::rc::Rc;
+use windjammer_ui::reactivity::Effect;
+use windjammer_ui::store::Store;
+use windjammer_ui::Lenses;
+
+#[derive(Clone, Default, Lenses)]
+struct Viewport {
+    zoom: f32,
+    offset: (i32, i32),
+}
+
+#[derive(Clone, Default, Lenses)]
+struct EditorState {
+    title: String,
+    viewport: Viewport,
+}
+
+#[test]
+fn test_derived_lenses_read_and_write_fields() {
+    let store = Store::new(EditorState::default());
+
+    store.title().set("untitled".to_string());
+    store.viewport().zoom().set(1.5);
+
+    let state = store.get();
+    assert_eq!(state.title, "untitled");
+    assert_eq!(state.viewport.zoom, 1.5);
+}
+
+#[test]
+fn test_derived_lenses_notify_only_their_field() {
+    let store = Store::new(EditorState::default());
+    let runs = Rc::new(Cell::new(0));
+
+    let zoom = store.viewport().zoom();
+    let runs_c = runs.clone();
+    Effect::new(move || {
+        zoom.get();
+        runs_c.set(runs_c.get() + 1);
+    });
+
+    store.viewport().offset().set((10, 20));
+    store.title().set("renamed".to_string());
+    assert_eq!(runs.get(), 1);
+
+    store.viewport().zoom().update(|z| *z += 1.0);
+    assert_eq!(runs.get(), 2);
+}
@@ -1,6 +1,7 @@
 //! Procedural macros for windjammer-ui
 //!
-//! Provides the `#[component]` attribute macro for defining UI components.
+//! Provides the `#[component]` attribute macro for defining UI components
+//! and the `Props` and `Lenses` derive macros.
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -119,6 +120,97 @@ pub fn derive_props(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Derive macro generating store lenses for each field of a struct
+///
+/// For a struct `Editor` this generates an `EditorLenses` trait with one
+/// method per field, implemented for `Store<Editor>` and for any
+/// `Lens<_, Editor>` so nested structs compose.
+///
+/// # Example
+///
+/// ```ignore
+/// use windjammer_ui::store::Store;
+/// use windjammer_ui::Lenses;
+///
+/// #[derive(Lenses)]
+/// struct Editor {
+///     zoom: f32,
+/// }
+///
+/// let store = Store::new(Editor { zoom: 1.0 });
+/// store.zoom().set(2.0);
+/// ```
+#[proc_macro_derive(Lenses)]
+pub fn derive_lenses(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "Lenses cannot be derived for generic structs",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input,
+                    "Lenses requires a struct with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(&input, "Lenses can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let trait_name = quote::format_ident!("{}Lenses", name);
+    let field_names: Vec<_> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let docs: Vec<_> = field_names
+        .iter()
+        .map(|field| format!("Lens focused on `{}`", field))
+        .collect();
+
+    let expanded = quote! {
+        /// Field lenses generated by `#[derive(Lenses)]`
+        #vis trait #trait_name<__Root> {
+            #(
+                #[doc = #docs]
+                fn #field_names(&self) -> windjammer_ui::store::Lens<__Root, #field_types>;
+            )*
+        }
+
+        impl #trait_name<#name> for windjammer_ui::store::Store<#name> {
+            #(
+                fn #field_names(&self) -> windjammer_ui::store::Lens<#name, #field_types> {
+                    self.at(|state| &state.#field_names, |state| &mut state.#field_names)
+                }
+            )*
+        }
+
+        impl<__Root: 'static> #trait_name<__Root> for windjammer_ui::store::Lens<__Root, #name> {
+            #(
+                fn #field_names(&self) -> windjammer_ui::store::Lens<__Root, #field_types> {
+                    self.at(|state| &state.#field_names, |state| &mut state.#field_names)
+                }
+            )*
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
 #[cfg(test)]
 mod tests {
     // Tests for proc macros are typically done with trybuild in tests/ directory